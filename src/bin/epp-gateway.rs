@@ -0,0 +1,69 @@
+//! An HTTP gateway translating JSON requests into EPP commands
+//!
+//! Usage: `epp-gateway <config.toml>`. See `epp_client::gateway::GatewayConfig` for the format of
+//! the configuration file. The registry sessions are logged out on SIGINT.
+//!
+//! Without `tokens` in the configuration the gateway doesn't authenticate its clients, so it then
+//! refuses to listen on anything but a loopback address.
+
+use std::env;
+use std::process;
+
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use epp_client::config;
+use epp_client::gateway::{Gateway, GatewayConfig};
+use epp_client::pool::Pool;
+use epp_client::Error;
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: epp-gateway <config.toml>");
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(&path).await {
+        error!("{}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(path: &str) -> Result<(), Error> {
+    let config: GatewayConfig = config::load(path)?;
+    if config.tokens.is_empty() && !config.listen.ip().is_loopback() {
+        return Err(Error::Other(
+            format!(
+                "{}: refusing to listen on {} without tokens",
+                path, config.listen
+            )
+            .into(),
+        ));
+    }
+
+    let listener = TcpListener::bind(config.listen).await?;
+
+    let pool = Pool::connect(&config.registry, config.sessions, config.queue_depth).await?;
+    let gateway = config
+        .tokens
+        .iter()
+        .fold(Gateway::new(pool.clone()), |gateway, token| {
+            gateway.token(token)
+        });
+
+    tokio::select! {
+        result = gateway.run(listener) => result?,
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            info!("Shutting down");
+        }
+    }
+
+    pool.shutdown().await
+}
@@ -0,0 +1,57 @@
+//! An EPP proxy sharing a few registry sessions between many local clients
+//!
+//! Usage: `epp-proxy <config.toml>`. See `epp_client::proxy::ProxyConfig` for the format of the
+//! configuration file. The registry sessions are logged out on SIGINT.
+
+use std::env;
+use std::process;
+
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use epp_client::config;
+use epp_client::pool::Pool;
+use epp_client::proxy::{Proxy, ProxyConfig};
+use epp_client::server::Server;
+use epp_client::Error;
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let path = match env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: epp-proxy <config.toml>");
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(&path).await {
+        error!("{}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(path: &str) -> Result<(), Error> {
+    let config: ProxyConfig = config::load(path)?;
+    let listener = TcpListener::bind(config.listen).await?;
+
+    let pool = Pool::connect(&config.registry, config.sessions, config.queue_depth).await?;
+    let proxy = config
+        .clients
+        .into_iter()
+        .fold(Proxy::new(pool.clone()), Proxy::client);
+    let greeting = proxy.greeting()?;
+    let server = Server::new(proxy, greeting);
+
+    tokio::select! {
+        result = server.run(listener) => result?,
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            info!("Shutting down");
+        }
+    }
+
+    pool.shutdown().await
+}
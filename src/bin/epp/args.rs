@@ -0,0 +1,441 @@
+//! Command-line arguments
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use epp_client::common::{ContactStatus, DomainName, DomainStatus, HostStatus};
+use epp_client::contact::PostalInfoType;
+use epp_client::domain::info::Hosts;
+use epp_client::domain::{ContactRole, Period};
+
+/// Runs EPP commands against the registry configured in a TOML file
+#[derive(Debug, Parser)]
+#[command(name = "epp", version)]
+pub struct Cli {
+    /// The configuration file holding the [registry] table
+    #[arg(short, long, default_value = "epp.toml")]
+    pub config: PathBuf,
+    /// Print results as JSON instead of tables
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// The client transaction ID to send with the command
+    #[arg(long, global = true)]
+    pub id: Option<String>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(flatten)]
+    Object(Box<Object>),
+    /// Start an interactive session, keeping one login open for all commands
+    Shell {
+        /// The file to keep the command history in, with passwords redacted
+        #[arg(long)]
+        history: Option<PathBuf>,
+    },
+}
+
+/// A line entered in the interactive shell
+#[derive(Debug, Parser)]
+#[command(name = "", no_binary_name = true, disable_version_flag = true)]
+pub struct Line {
+    /// Print the result as JSON instead of a table
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: LineCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LineCommand {
+    #[command(flatten)]
+    Object(Box<Object>),
+    /// Show the registry's greeting
+    Greeting,
+    /// Send raw XML, read from a file or pasted on the following lines up to </epp>
+    Xml { file: Option<PathBuf> },
+    /// Log out and leave the shell
+    #[command(alias = "quit")]
+    Exit,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Object {
+    /// Domain commands
+    #[command(subcommand)]
+    Domain(DomainCommand),
+    /// Contact commands
+    #[command(subcommand)]
+    Contact(ContactCommand),
+    /// Host commands
+    #[command(subcommand)]
+    Host(HostCommand),
+    /// Message queue commands
+    #[command(subcommand)]
+    Poll(PollCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DomainCommand {
+    /// Check the availability of domains
+    Check {
+        #[arg(required = true)]
+        names: Vec<DomainName>,
+    },
+    /// Show a domain
+    Info {
+        name: DomainName,
+        /// The domain's authorization password, for domains sponsored by other registrars
+        #[arg(long)]
+        auth: Option<String>,
+        /// Which subordinate and delegated hosts to return: all, del, sub or none
+        #[arg(long, default_value = "all")]
+        hosts: Hosts,
+    },
+    /// Register a domain
+    Create {
+        name: DomainName,
+        /// The registration period, in years or with a unit, e.g. 2 or 6m
+        #[arg(long, default_value = "1y")]
+        period: Period,
+        /// The ID of the registrant contact
+        #[arg(long)]
+        registrant: Option<String>,
+        /// The authorization password for the domain
+        #[arg(long)]
+        auth: String,
+        /// A name server for the domain
+        #[arg(long = "ns")]
+        name_servers: Vec<DomainName>,
+        #[command(flatten)]
+        contacts: ContactArgs,
+    },
+    /// Renew a domain
+    Renew {
+        name: DomainName,
+        /// The current expiry date of the domain, as YYYY-MM-DD
+        #[arg(long)]
+        expiry: NaiveDate,
+        /// The renewal period, in years or with a unit, e.g. 2 or 6m
+        #[arg(long, default_value = "1y")]
+        period: Period,
+    },
+    /// Request, query, approve, reject or cancel a domain transfer
+    #[command(subcommand)]
+    Transfer(TransferCommand),
+    /// Change a domain's name servers, contacts, statuses, registrant or password
+    Update {
+        name: DomainName,
+        /// A name server to add
+        #[arg(long)]
+        add_ns: Vec<DomainName>,
+        /// A name server to remove
+        #[arg(long)]
+        remove_ns: Vec<DomainName>,
+        /// A contact to add, as TYPE=ID, e.g. tech=contact-1
+        #[arg(long, value_parser = contact)]
+        add_contact: Vec<(ContactRole, String)>,
+        /// A contact to remove, as TYPE=ID
+        #[arg(long, value_parser = contact)]
+        remove_contact: Vec<(ContactRole, String)>,
+        /// A status to add, e.g. clientHold
+        #[arg(long)]
+        add_status: Vec<DomainStatus>,
+        /// A status to remove
+        #[arg(long)]
+        remove_status: Vec<DomainStatus>,
+        /// The ID of the new registrant contact
+        #[arg(long)]
+        registrant: Option<String>,
+        /// The new authorization password
+        #[arg(long)]
+        auth: Option<String>,
+    },
+    /// Delete a domain
+    Delete { name: DomainName },
+    /// Request the restore of a domain in its redemption grace period
+    Restore { name: DomainName },
+    /// Send the report for a requested domain restore
+    RestoreReport(RestoreReportArgs),
+}
+
+/// The contacts of a new domain
+#[derive(Debug, Args)]
+pub struct ContactArgs {
+    /// The ID of an administrative contact
+    #[arg(long)]
+    pub admin: Vec<String>,
+    /// The ID of a technical contact
+    #[arg(long)]
+    pub tech: Vec<String>,
+    /// The ID of a billing contact
+    #[arg(long)]
+    pub billing: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TransferCommand {
+    /// Request the transfer of a domain to this registrar
+    Request {
+        name: DomainName,
+        /// The domain's authorization password
+        #[arg(long)]
+        auth: String,
+        /// The period to extend the registration by, in years or with a unit, e.g. 2 or 6m
+        #[arg(long)]
+        period: Option<Period>,
+    },
+    /// Show the status of a domain transfer
+    Query {
+        name: DomainName,
+        /// The domain's authorization password
+        #[arg(long)]
+        auth: String,
+    },
+    /// Approve the transfer of a domain away from this registrar
+    Approve { name: DomainName },
+    /// Reject the transfer of a domain away from this registrar
+    Reject { name: DomainName },
+    /// Cancel a transfer requested by this registrar
+    Cancel { name: DomainName },
+}
+
+#[derive(Debug, Args)]
+pub struct RestoreReportArgs {
+    pub name: DomainName,
+    /// The registration data before the domain was deleted
+    #[arg(long)]
+    pub pre_data: String,
+    /// The registration data when the restore was requested
+    #[arg(long)]
+    pub post_data: String,
+    /// When the domain was deleted, in RFC 3339 format
+    #[arg(long)]
+    pub deleted_at: DateTime<Utc>,
+    /// When the domain was restored, in RFC 3339 format
+    #[arg(long)]
+    pub restored_at: DateTime<Utc>,
+    /// The reason for the restore
+    #[arg(long)]
+    pub reason: String,
+    /// A statement required by the registry, usually two are needed
+    #[arg(long = "statement", required = true)]
+    pub statements: Vec<String>,
+    /// Any other information supporting the restore
+    #[arg(long, default_value = "")]
+    pub other: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContactCommand {
+    /// Check the availability of contact IDs
+    Check {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Show a contact
+    Info {
+        id: String,
+        /// The contact's authorization password, for contacts sponsored by other registrars
+        #[arg(long)]
+        auth: Option<String>,
+    },
+    /// Create a contact
+    Create {
+        id: String,
+        /// The contact's email address
+        #[arg(long)]
+        email: String,
+        /// The contact's voice telephone number, e.g. +1.5555555555
+        #[arg(long)]
+        voice: String,
+        /// The contact's fax number
+        #[arg(long)]
+        fax: Option<String>,
+        /// The authorization password for the contact
+        #[arg(long)]
+        auth: String,
+        #[command(flatten)]
+        postal: PostalArgs,
+        #[command(flatten)]
+        disclose: DiscloseArgs,
+    },
+    /// Change a contact's details or statuses
+    Update {
+        id: String,
+        /// The new email address
+        #[arg(long)]
+        email: Option<String>,
+        /// The new voice telephone number
+        #[arg(long)]
+        voice: Option<String>,
+        /// The new fax number
+        #[arg(long)]
+        fax: Option<String>,
+        /// The new authorization password
+        #[arg(long)]
+        auth: Option<String>,
+        #[command(flatten)]
+        postal: PostalArgs,
+        #[command(flatten)]
+        disclose: DiscloseArgs,
+        /// A status to add, e.g. clientDeleteProhibited
+        #[arg(long)]
+        add_status: Vec<ContactStatus>,
+        /// A status to remove
+        #[arg(long)]
+        remove_status: Vec<ContactStatus>,
+    },
+    /// Request, query, approve, reject or cancel a contact transfer
+    #[command(subcommand)]
+    Transfer(ContactTransferCommand),
+    /// Delete a contact
+    Delete { id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContactTransferCommand {
+    /// Request the transfer of a contact to this registrar
+    Request {
+        id: String,
+        /// The contact's authorization password
+        #[arg(long)]
+        auth: String,
+    },
+    /// Show the status of a contact transfer
+    Query {
+        id: String,
+        /// The contact's authorization password
+        #[arg(long)]
+        auth: String,
+    },
+    /// Approve the transfer of a contact away from this registrar
+    Approve { id: String },
+    /// Reject the transfer of a contact away from this registrar
+    Reject { id: String },
+    /// Cancel a transfer requested by this registrar
+    Cancel { id: String },
+}
+
+/// The postal information of a contact
+///
+/// The name, city and country are required to create a contact, or to change its postal
+/// information.
+#[derive(Debug, Args)]
+pub struct PostalArgs {
+    /// The contact's name
+    #[arg(long)]
+    pub name: Option<String>,
+    /// The contact's organization
+    #[arg(long)]
+    pub org: Option<String>,
+    /// A line of the contact's street address
+    #[arg(long)]
+    pub street: Vec<String>,
+    /// The contact's city
+    #[arg(long)]
+    pub city: Option<String>,
+    /// The contact's state or province
+    #[arg(long)]
+    pub province: Option<String>,
+    /// The contact's postal code
+    #[arg(long)]
+    pub postal_code: Option<String>,
+    /// The contact's ISO 3166 country code
+    #[arg(long)]
+    pub country: Option<String>,
+    /// Whether the postal information is localized (loc) or ASCII-only (int)
+    #[arg(long = "type", default_value = "int")]
+    pub info_type: PostalInfoType,
+}
+
+/// The disclosure preferences of a contact
+///
+/// The name, organization and address refer to the postal information of the given --type.
+#[derive(Debug, Args)]
+pub struct DiscloseArgs {
+    /// Contact fields the registry should disclose, e.g. voice,email
+    #[arg(long, value_delimiter = ',')]
+    pub disclose: Vec<ContactField>,
+    /// Contact fields the registry should keep private
+    #[arg(long, value_delimiter = ',', conflicts_with = "disclose")]
+    pub hide: Vec<ContactField>,
+}
+
+/// A contact field whose disclosure can be set
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ContactField {
+    Name,
+    Org,
+    Addr,
+    Voice,
+    Fax,
+    Email,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HostCommand {
+    /// Check the availability of host names
+    Check {
+        #[arg(required = true)]
+        names: Vec<DomainName>,
+    },
+    /// Show a host
+    Info { name: DomainName },
+    /// Create a host
+    Create {
+        name: DomainName,
+        /// An IPv4 or IPv6 address of the host
+        #[arg(long = "addr")]
+        addresses: Vec<String>,
+        /// A zone the host may have addresses in, e.g. its superordinate domain
+        #[arg(long = "zone")]
+        zones: Vec<DomainName>,
+    },
+    /// Change a host's addresses, statuses or name
+    Update {
+        name: DomainName,
+        /// An address to add
+        #[arg(long)]
+        add_addr: Vec<String>,
+        /// An address to remove
+        #[arg(long)]
+        remove_addr: Vec<String>,
+        /// A status to add, e.g. clientUpdateProhibited
+        #[arg(long)]
+        add_status: Vec<HostStatus>,
+        /// A status to remove
+        #[arg(long)]
+        remove_status: Vec<HostStatus>,
+        /// The new name of the host
+        #[arg(long)]
+        rename: Option<DomainName>,
+        /// A zone the host may have added addresses in, e.g. its superordinate domain
+        #[arg(long = "zone")]
+        zones: Vec<DomainName>,
+    },
+    /// Delete a host
+    Delete { name: DomainName },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PollCommand {
+    /// Show the oldest message in the queue
+    Req,
+    /// Remove a message from the queue
+    Ack { id: u32 },
+}
+
+/// Parses a domain contact given as TYPE=ID
+fn contact(value: &str) -> Result<(ContactRole, String), String> {
+    match value.split_once('=') {
+        Some((ty, id)) if !id.is_empty() => {
+            let role = ty.parse::<ContactRole>().map_err(|e| e.to_string())?;
+            Ok((role, id.to_owned()))
+        }
+        _ => Err(format!("expected TYPE=ID, found {}", value)),
+    }
+}
@@ -0,0 +1,1031 @@
+//! Execution of the commands given on the command line
+
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use epp_client::common::{
+    DomainName, DomainStatus, HostAddr, HostStatus, ObjectStatus, StringValue,
+};
+use epp_client::contact::transfer::ContactTransferResponseData;
+use epp_client::contact::update::ContactChangeInfo;
+use epp_client::contact::{
+    Address, ContactAuthInfo, ContactCheck, ContactCreate, ContactDelete, ContactInfo,
+    ContactTransfer, ContactUpdate, Disclose, Phone, PostalInfo, PostalInfoType,
+};
+use epp_client::domain::transfer::DomainTransferResponseData;
+use epp_client::domain::update::{DomainAddRemove, DomainChangeInfo};
+use epp_client::domain::{
+    ContactRole, DomainAuthInfo, DomainCheck, DomainContact, DomainCreate, DomainDelete,
+    DomainInfo, DomainRenew, DomainTransfer, DomainUpdate, HostList, HostObjList,
+};
+use epp_client::extensions::rgp::report::RgpRestoreReport;
+use epp_client::extensions::rgp::request::RgpRestoreRequest;
+use epp_client::extensions::rgp::Update;
+use epp_client::host::info::HostInfoResponseData;
+use epp_client::host::update::{HostAddRemove, HostChangeInfo};
+use epp_client::host::{HostCheck, HostCreate, HostDelete, HostInfo, HostUpdate};
+use epp_client::message::poll::MessageData;
+use epp_client::message::{MessageAck, MessagePoll};
+use epp_client::response::{EppResult, MessageQueue};
+use epp_client::{EppClient, Error};
+
+use crate::args::{
+    ContactCommand, ContactField, ContactTransferCommand, DiscloseArgs, DomainCommand, HostCommand,
+    Object, PollCommand, PostalArgs, TransferCommand,
+};
+use crate::output::{Output, Record};
+
+/// Sends the command described by `object` and describes its result
+pub async fn execute<IO>(
+    client: &mut EppClient<IO>,
+    object: &Object,
+    id: &str,
+) -> Result<Output, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    match object {
+        Object::Domain(command) => domain(client, command, id).await,
+        Object::Contact(command) => contact(client, command, id).await,
+        Object::Host(command) => host(client, command, id).await,
+        Object::Poll(command) => poll(client, command, id).await,
+    }
+}
+
+async fn domain<IO>(
+    client: &mut EppClient<IO>,
+    command: &DomainCommand,
+    id: &str,
+) -> Result<Output, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    Ok(match command {
+        DomainCommand::Check { names } => {
+            let check = DomainCheck::new(names.iter().map(DomainName::as_str).collect());
+            let response = client.transact(&check, id).await?;
+            let data = response.res_data.map(|r| r.check_data.domain_list);
+            Output::List(
+                data.unwrap_or_default()
+                    .into_iter()
+                    .map(|item| {
+                        Record::default()
+                            .field("name", item.domain.name.to_string())
+                            .field("unicode", unicode(&item.domain.name))
+                            .field("available", item.domain.available)
+                            .field("reason", item.reason.map(text))
+                    })
+                    .collect(),
+            )
+        }
+        DomainCommand::Info { name, auth, hosts } => {
+            let mut info = DomainInfo::new(name, auth.as_deref());
+            info.set_hosts(*hosts);
+            let response = client.transact(&info, id).await?;
+            let data = required(response.res_data)?.info_data;
+            let ns = data.ns.map(|ns| match (ns.host_obj, ns.host_attr) {
+                (Some(hosts), _) => hosts.into_iter().map(text).collect(),
+                (None, Some(hosts)) => hosts.into_iter().map(|h| text(h.name)).collect(),
+                (None, None) => Vec::new(),
+            });
+
+            let unicode_name = unicode(data.name.as_ref());
+
+            Output::Object(
+                Record::default()
+                    .field("name", text(data.name))
+                    .field("unicode", unicode_name)
+                    .field("roid", text(data.roid))
+                    .field("statuses", data.statuses.map(statuses))
+                    .field("registrant", data.registrant.map(text))
+                    .field(
+                        "contacts",
+                        data.contacts.map(|contacts| {
+                            contacts
+                                .iter()
+                                .map(|c| format!("{}={}", c.contact_type, c.id))
+                                .collect::<Vec<_>>()
+                        }),
+                    )
+                    .field("ns", ns)
+                    .field(
+                        "hosts",
+                        data.hosts
+                            .map(|h| h.into_iter().map(text).collect::<Vec<_>>()),
+                    )
+                    .field("sponsor", text(data.client_id))
+                    .field("created_by", data.creator_id.map(text))
+                    .field("created_at", data.created_at)
+                    .field("expires_at", data.expiring_at)
+                    .field("updated_by", data.updater_id.map(text))
+                    .field("updated_at", data.updated_at)
+                    .field("transferred_at", data.transferred_at)
+                    .field("auth", data.auth_info.map(|a| text(a.password))),
+            )
+        }
+        DomainCommand::Create {
+            name,
+            period,
+            registrant,
+            auth,
+            name_servers,
+            contacts,
+        } => {
+            let hosts = name_servers
+                .iter()
+                .map(|ns| ns.as_str().into())
+                .collect::<Vec<StringValue>>();
+            let ns = if hosts.is_empty() {
+                None
+            } else {
+                Some(HostList::HostObjList(HostObjList {
+                    hosts: hosts.into(),
+                }))
+            };
+
+            let contacts = [
+                (ContactRole::Admin, &contacts.admin),
+                (ContactRole::Tech, &contacts.tech),
+                (ContactRole::Billing, &contacts.billing),
+            ]
+            .iter()
+            .flat_map(|(role, ids)| {
+                ids.iter()
+                    .map(move |id| DomainContact::new(role.clone(), id))
+            })
+            .collect::<Vec<_>>();
+            let contacts = if contacts.is_empty() {
+                None
+            } else {
+                Some(contacts.as_slice())
+            };
+
+            let create =
+                DomainCreate::new(name, *period, ns, registrant.as_deref(), auth, contacts);
+            let response = client.transact(&create, id).await?;
+            let data = required(response.res_data)?.create_data;
+            Output::Object(
+                Record::default()
+                    .field("name", text(data.name))
+                    .field("created_at", data.created_at)
+                    .field("expires_at", data.expiring_at),
+            )
+        }
+        DomainCommand::Renew {
+            name,
+            expiry,
+            period,
+        } => {
+            let renew = DomainRenew::new(name, *expiry, *period);
+            let response = client.transact(&renew, id).await?;
+            let data = required(response.res_data)?.renew_data;
+            Output::Object(
+                Record::default()
+                    .field("name", text(data.name))
+                    .field("expires_at", data.expiring_at),
+            )
+        }
+        DomainCommand::Transfer(command) => {
+            let transfer = match command {
+                TransferCommand::Request { name, auth, period } => {
+                    DomainTransfer::new(name, *period, auth)
+                }
+                TransferCommand::Query { name, auth } => DomainTransfer::query(name, auth),
+                TransferCommand::Approve { name } => DomainTransfer::approve(name),
+                TransferCommand::Reject { name } => DomainTransfer::reject(name),
+                TransferCommand::Cancel { name } => DomainTransfer::cancel(name),
+            };
+
+            let response = client.transact(&transfer, id).await?;
+            match response.res_data {
+                Some(data) => Output::Object(transfer_record(data.transfer_data)),
+                None => Output::Object(result(&response.result)),
+            }
+        }
+        DomainCommand::Update {
+            name,
+            add_ns,
+            remove_ns,
+            add_contact,
+            remove_contact,
+            add_status,
+            remove_status,
+            registrant,
+            auth,
+        } => {
+            let mut update = DomainUpdate::new(name);
+            if let Some(add) = domain_add_remove(add_ns, add_contact, add_status) {
+                update.add(add);
+            }
+            if let Some(remove) = domain_add_remove(remove_ns, remove_contact, remove_status) {
+                update.remove(remove);
+            }
+            if registrant.is_some() || auth.is_some() {
+                update.info(DomainChangeInfo {
+                    registrant: registrant.as_deref().map(Into::into),
+                    auth_info: auth.as_deref().map(DomainAuthInfo::new),
+                });
+            }
+
+            let response = client.transact(&update, id).await?;
+            Output::Object(result(&response.result))
+        }
+        DomainCommand::Delete { name } => {
+            let response = client.transact(&DomainDelete::new(name), id).await?;
+            Output::Object(result(&response.result))
+        }
+        DomainCommand::Restore { name } => {
+            let update = restore_update(name);
+            let extension = Update {
+                data: RgpRestoreRequest::default(),
+            };
+
+            let response = client.transact((&update, &extension), id).await?;
+            let rgp_statuses = response.extension.map(|ext| {
+                ext.data
+                    .rgp_status
+                    .into_iter()
+                    .map(|s| s.status)
+                    .collect::<Vec<_>>()
+            });
+            Output::Object(result(&response.result).field("rgp_statuses", rgp_statuses))
+        }
+        DomainCommand::RestoreReport(args) => {
+            let update = restore_update(&args.name);
+            let statements = args
+                .statements
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            let extension = Update {
+                data: RgpRestoreReport::new(
+                    &args.pre_data,
+                    &args.post_data,
+                    args.deleted_at,
+                    args.restored_at,
+                    &args.reason,
+                    &statements,
+                    &args.other,
+                ),
+            };
+
+            let response = client.transact((&update, &extension), id).await?;
+            Output::Object(result(&response.result))
+        }
+    })
+}
+
+async fn contact<IO>(
+    client: &mut EppClient<IO>,
+    command: &ContactCommand,
+    id: &str,
+) -> Result<Output, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    Ok(match command {
+        ContactCommand::Check { ids } => {
+            let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+            let response = client.transact(&ContactCheck::new(&ids), id).await?;
+            let data = response.res_data.map(|r| r.check_data.contact_list);
+            Output::List(
+                data.unwrap_or_default()
+                    .into_iter()
+                    .map(|item| {
+                        Record::default()
+                            .field("id", item.contact.id.to_string())
+                            .field("available", item.contact.available == 1)
+                            .field("reason", item.reason.map(text))
+                    })
+                    .collect(),
+            )
+        }
+        ContactCommand::Info {
+            id: contact_id,
+            auth,
+        } => {
+            let response = client
+                .transact(&ContactInfo::new(contact_id, auth.as_deref()), id)
+                .await?;
+            let data = required(response.res_data)?.info_data;
+            let (disclosed, hidden) = match &data.disclose {
+                Some(disclose) if disclose.flag => (disclosed_fields(disclose), Vec::new()),
+                Some(disclose) => (Vec::new(), disclosed_fields(disclose)),
+                None => (Vec::new(), Vec::new()),
+            };
+
+            let mut record = Record::default()
+                .field("id", text(data.id))
+                .field("roid", text(data.roid))
+                .field("statuses", statuses(data.statuses));
+            for (i, postal) in data.postal_info.into_iter().enumerate() {
+                record = record.extend(postal_record(postal, i == 0));
+            }
+
+            Output::Object(
+                record
+                    .field("voice", data.voice.number.to_string())
+                    .field("fax", data.fax.map(|fax| fax.number.to_string()))
+                    .field("email", text(data.email))
+                    .field("disclosed", disclosed)
+                    .field("hidden", hidden)
+                    .field("sponsor", text(data.client_id))
+                    .field("created_by", text(data.creator_id))
+                    .field("created_at", data.created_at)
+                    .field("updated_by", data.updater_id.map(text))
+                    .field("updated_at", data.updated_at)
+                    .field("transferred_at", data.transferred_at)
+                    .field("auth", data.auth_info.map(|a| a.password.to_string())),
+            )
+        }
+        ContactCommand::Create {
+            id: contact_id,
+            email,
+            voice,
+            fax,
+            auth,
+            postal,
+            disclose,
+        } => {
+            let postal_info = postal_info(postal)?
+                .ok_or_else(|| usage("--name, --city and --country are required"))?;
+            let mut create =
+                ContactCreate::new(contact_id, email, postal_info, Phone::new(voice), auth);
+            if let Some(fax) = fax {
+                create.set_fax(Phone::new(fax));
+            }
+            if let Some(disclose) = disclose_info(disclose, postal.info_type) {
+                create.set_disclose(disclose);
+            }
+
+            let response = client.transact(&create, id).await?;
+            let data = required(response.res_data)?.create_data;
+            Output::Object(
+                Record::default()
+                    .field("id", text(data.id))
+                    .field("created_at", data.created_at),
+            )
+        }
+        ContactCommand::Update {
+            id: contact_id,
+            email,
+            voice,
+            fax,
+            auth,
+            postal,
+            disclose,
+            add_status,
+            remove_status,
+        } => {
+            let mut update = ContactUpdate::new(contact_id);
+            let change = ContactChangeInfo {
+                postal_info: postal_info(postal)?.into_iter().collect(),
+                voice: voice.as_deref().map(Phone::new),
+                fax: fax.as_deref().map(Phone::new),
+                email: email.as_deref().map(Into::into),
+                auth_info: auth.as_deref().map(ContactAuthInfo::new),
+                disclose: disclose_info(disclose, postal.info_type),
+            };
+            if !change.postal_info.is_empty()
+                || change.voice.is_some()
+                || change.fax.is_some()
+                || change.email.is_some()
+                || change.auth_info.is_some()
+                || change.disclose.is_some()
+            {
+                update.contact.change_info = Some(change);
+            }
+
+            let add = add_status
+                .iter()
+                .cloned()
+                .map(ObjectStatus::new)
+                .collect::<Vec<_>>();
+            if !add.is_empty() {
+                update.add(&add);
+            }
+            let remove = remove_status
+                .iter()
+                .cloned()
+                .map(ObjectStatus::new)
+                .collect::<Vec<_>>();
+            if !remove.is_empty() {
+                update.remove(&remove);
+            }
+
+            let response = client.transact(&update, id).await?;
+            Output::Object(result(&response.result))
+        }
+        ContactCommand::Transfer(command) => {
+            let transfer = match command {
+                ContactTransferCommand::Request {
+                    id: contact_id,
+                    auth,
+                } => ContactTransfer::new(contact_id, auth),
+                ContactTransferCommand::Query {
+                    id: contact_id,
+                    auth,
+                } => ContactTransfer::query(contact_id, auth),
+                ContactTransferCommand::Approve { id: contact_id } => {
+                    ContactTransfer::approve(contact_id)
+                }
+                ContactTransferCommand::Reject { id: contact_id } => {
+                    ContactTransfer::reject(contact_id)
+                }
+                ContactTransferCommand::Cancel { id: contact_id } => {
+                    ContactTransfer::cancel(contact_id)
+                }
+            };
+
+            let response = client.transact(&transfer, id).await?;
+            match response.res_data {
+                Some(data) => Output::Object(contact_transfer_record(data.transfer_data)),
+                None => Output::Object(result(&response.result)),
+            }
+        }
+        ContactCommand::Delete { id: contact_id } => {
+            let response = client.transact(&ContactDelete::new(contact_id), id).await?;
+            Output::Object(result(&response.result))
+        }
+    })
+}
+
+#[allow(clippy::result_large_err)]
+async fn host<IO>(
+    client: &mut EppClient<IO>,
+    command: &HostCommand,
+    id: &str,
+) -> Result<Output, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    Ok(match command {
+        HostCommand::Check { names } => {
+            let names = names.iter().map(DomainName::as_str).collect::<Vec<_>>();
+            let response = client.transact(&HostCheck::new(&names), id).await?;
+            let data = response.res_data.map(|r| r.check_data.host_list);
+            Output::List(
+                data.unwrap_or_default()
+                    .into_iter()
+                    .map(|item| {
+                        Record::default()
+                            .field("name", item.host.name.to_string())
+                            .field("available", item.host.available == 1)
+                            .field("reason", item.reason.map(text))
+                    })
+                    .collect(),
+            )
+        }
+        HostCommand::Info { name } => {
+            let response = client.transact(&HostInfo::new(name), id).await?;
+            Output::Object(host_record(required(response.res_data)?.info_data))
+        }
+        HostCommand::Create {
+            name,
+            addresses,
+            zones,
+        } => {
+            let addresses = addresses
+                .iter()
+                .map(|a| host_addr(a))
+                .collect::<Result<Vec<_>, _>>()?;
+            let addresses = if addresses.is_empty() {
+                None
+            } else {
+                Some(addresses.as_slice())
+            };
+
+            let zones = zones.iter().map(DomainName::as_str).collect::<Vec<_>>();
+            let create = HostCreate::new(name, addresses).glue_zones(&zones);
+            let response = client.transact(&create, id).await?;
+            let data = required(response.res_data)?.create_data;
+            Output::Object(
+                Record::default()
+                    .field("name", text(data.name))
+                    .field("created_at", data.created_at),
+            )
+        }
+        HostCommand::Update {
+            name,
+            add_addr,
+            remove_addr,
+            add_status,
+            remove_status,
+            rename,
+            zones,
+        } => {
+            let zones = zones.iter().map(DomainName::as_str).collect::<Vec<_>>();
+            let mut update = HostUpdate::new(name);
+            update.glue_zones(&zones);
+            if let Some(add) = host_add_remove(add_addr, add_status)? {
+                update.add(add);
+            }
+            if let Some(remove) = host_add_remove(remove_addr, remove_status)? {
+                update.remove(remove);
+            }
+            if let Some(rename) = rename {
+                update.info(HostChangeInfo {
+                    name: rename.as_str().into(),
+                });
+            }
+
+            let response = client.transact(&update, id).await?;
+            Output::Object(result(&response.result))
+        }
+        HostCommand::Delete { name } => {
+            let response = client.transact(&HostDelete::new(name), id).await?;
+            Output::Object(result(&response.result))
+        }
+    })
+}
+
+async fn poll<IO>(
+    client: &mut EppClient<IO>,
+    command: &PollCommand,
+    id: &str,
+) -> Result<Output, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    Ok(match command {
+        PollCommand::Req => {
+            let response = client.transact(&MessagePoll::default(), id).await?;
+            let mut record = result(&response.result);
+            if let Some(queue) = response.message_queue {
+                record = record.extend(queue_record(queue));
+            }
+
+            match response.res_data.map(|r| r.message_data) {
+                Some(MessageData::DomainTransfer(data)) => {
+                    record = record.extend(transfer_record(data));
+                }
+                Some(MessageData::ContactTransfer(data)) => {
+                    record = record.extend(contact_transfer_record(data));
+                }
+                Some(MessageData::HostInfo(data)) => record = record.extend(host_record(data)),
+                None => {}
+            }
+            Output::Object(record)
+        }
+        PollCommand::Ack { id: message_id } => {
+            let response = client.transact(&MessageAck::new(*message_id), id).await?;
+            let mut record = result(&response.result);
+            if let Some(queue) = response.message_queue {
+                record = record.field("remaining", u64::from(queue.count));
+            }
+            Output::Object(record)
+        }
+    })
+}
+
+/// Describes the result of a command that returns no data
+fn result(result: &EppResult) -> Record {
+    Record::default()
+        .field("code", u64::from(result.code))
+        .field("message", result.message.to_string())
+}
+
+fn transfer_record(data: DomainTransferResponseData) -> Record {
+    let deadline = data.transfer().deadline();
+    Record::default()
+        .field("name", text(data.name))
+        .field("transfer_status", data.transfer_status.as_str())
+        .field("requested_by", text(data.requester_id))
+        .field("requested_at", data.requested_at)
+        .field("action_by", text(data.ack_id))
+        .field("action_at", data.ack_by)
+        .field("deadline", deadline)
+        .field("expires_at", data.expiring_at)
+}
+
+fn contact_transfer_record(data: ContactTransferResponseData) -> Record {
+    let deadline = data.transfer().deadline();
+    Record::default()
+        .field("id", text(data.id))
+        .field("transfer_status", data.transfer_status.as_str())
+        .field("requested_by", text(data.requester_id))
+        .field("requested_at", data.requested_at)
+        .field("action_by", text(data.ack_id))
+        .field("action_at", data.ack_by)
+        .field("deadline", deadline)
+}
+
+fn host_record(data: HostInfoResponseData) -> Record {
+    Record::default()
+        .field("name", text(data.name))
+        .field("roid", text(data.roid))
+        .field("statuses", statuses(data.statuses))
+        .field(
+            "addresses",
+            data.addresses
+                .into_iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .field("sponsor", text(data.client_id))
+        .field("created_by", text(data.creator_id))
+        .field("created_at", data.created_at)
+        .field("updated_by", data.updater_id.map(text))
+        .field("updated_at", data.updated_at)
+        .field("transferred_at", data.transferred_at)
+}
+
+fn queue_record(queue: MessageQueue) -> Record {
+    Record::default()
+        .field("queued", u64::from(queue.count))
+        .field("message_id", queue.id)
+        .field("queued_at", queue.date)
+        .field("queued_message", queue.message.map(text))
+}
+
+/// The update sent with RGP restore requests and reports, which changes nothing itself
+fn restore_update(name: &str) -> DomainUpdate<'_> {
+    let mut update = DomainUpdate::new(name);
+    update.info(DomainChangeInfo {
+        registrant: None,
+        auth_info: None,
+    });
+    update
+}
+
+fn domain_add_remove<'a>(
+    hosts: &'a [DomainName],
+    contacts: &'a [(ContactRole, String)],
+    statuses: &'a [DomainStatus],
+) -> Option<DomainAddRemove<'a>> {
+    if hosts.is_empty() && contacts.is_empty() && statuses.is_empty() {
+        return None;
+    }
+
+    let hosts = hosts
+        .iter()
+        .map(|h| h.as_str().into())
+        .collect::<Vec<StringValue>>();
+    let contacts = contacts
+        .iter()
+        .map(|(role, id)| DomainContact::new(role.clone(), id))
+        .collect::<Vec<_>>();
+    let statuses = statuses
+        .iter()
+        .cloned()
+        .map(ObjectStatus::new)
+        .collect::<Vec<_>>();
+
+    Some(DomainAddRemove {
+        ns: non_empty(hosts).map(|hosts| {
+            HostList::HostObjList(HostObjList {
+                hosts: hosts.into(),
+            })
+        }),
+        contacts: non_empty(contacts).map(Into::into),
+        statuses: non_empty(statuses).map(Into::into),
+    })
+}
+
+#[allow(clippy::result_large_err)]
+fn host_add_remove<'a>(
+    addresses: &'a [String],
+    statuses: &'a [HostStatus],
+) -> Result<Option<HostAddRemove<'a>>, Error> {
+    if addresses.is_empty() && statuses.is_empty() {
+        return Ok(None);
+    }
+
+    let addresses = addresses
+        .iter()
+        .map(|a| host_addr(a))
+        .collect::<Result<Vec<_>, _>>()?;
+    let statuses = statuses
+        .iter()
+        .cloned()
+        .map(ObjectStatus::new)
+        .collect::<Vec<_>>();
+
+    Ok(Some(HostAddRemove {
+        addresses: non_empty(addresses).map(Into::into),
+        statuses: non_empty(statuses).map(Into::into),
+    }))
+}
+
+/// Builds postal information from the command line, if any was given
+#[allow(clippy::result_large_err)]
+fn postal_info(args: &PostalArgs) -> Result<Option<PostalInfo<'_>>, Error> {
+    let (name, city, country) = match (&args.name, &args.city, &args.country) {
+        (Some(name), Some(city), Some(country)) => (name, city, country),
+        (None, None, None) => return Ok(None),
+        _ => return Err(usage("--name, --city and --country must be given together")),
+    };
+
+    let country = country
+        .parse()
+        .map_err(|_| usage(&format!("unknown country code {}", country)))?;
+    let street = args.street.iter().map(String::as_str).collect::<Vec<_>>();
+    let address = Address::new(
+        &street,
+        city,
+        args.province.as_deref().unwrap_or_default(),
+        args.postal_code.as_deref().unwrap_or_default(),
+        country,
+    );
+
+    let org = args.org.as_deref().unwrap_or_default();
+    Ok(Some(PostalInfo::new(args.info_type, name, org, address)))
+}
+
+/// Describes a contact's postal info, with field names prefixed by its type unless `primary`
+fn postal_record(postal: PostalInfo<'_>, primary: bool) -> Record {
+    let names = match (primary, postal.info_type) {
+        (true, _) => [
+            "name",
+            "org",
+            "street",
+            "city",
+            "province",
+            "postal_code",
+            "country",
+        ],
+        (false, PostalInfoType::Int) => [
+            "int_name",
+            "int_org",
+            "int_street",
+            "int_city",
+            "int_province",
+            "int_postal_code",
+            "int_country",
+        ],
+        (false, PostalInfoType::Loc) => [
+            "loc_name",
+            "loc_org",
+            "loc_street",
+            "loc_city",
+            "loc_province",
+            "loc_postal_code",
+            "loc_country",
+        ],
+    };
+
+    let address = postal.address;
+    let mut record = Record::default();
+    if primary {
+        record = record.field("postal_type", postal.info_type.as_str());
+    }
+    record
+        .field(names[0], text(postal.name))
+        .field(names[1], text(postal.organization))
+        .field(
+            names[2],
+            address.street.into_iter().map(text).collect::<Vec<_>>(),
+        )
+        .field(names[3], text(address.city))
+        .field(names[4], text(address.province))
+        .field(names[5], text(address.postal_code))
+        .field(names[6], address.country.alpha2)
+}
+
+fn disclose_info(args: &DiscloseArgs, info_type: PostalInfoType) -> Option<Disclose> {
+    let (flag, fields) = match (args.disclose.is_empty(), args.hide.is_empty()) {
+        (false, _) => (true, &args.disclose),
+        (true, false) => (false, &args.hide),
+        (true, true) => return None,
+    };
+
+    Some(
+        fields
+            .iter()
+            .fold(Disclose::new(flag), |disclose, field| match field {
+                ContactField::Name => disclose.name(info_type),
+                ContactField::Org => disclose.organization(info_type),
+                ContactField::Addr => disclose.address(info_type),
+                ContactField::Voice => disclose.voice(),
+                ContactField::Fax => disclose.fax(),
+                ContactField::Email => disclose.email(),
+            }),
+    )
+}
+
+/// Lists the fields covered by disclosure preferences, e.g. "name (int)" or "voice"
+fn disclosed_fields(disclose: &Disclose) -> Vec<String> {
+    let typed = [
+        ("name", &disclose.name),
+        ("org", &disclose.organization),
+        ("addr", &disclose.address),
+    ];
+    let mut fields = typed
+        .iter()
+        .flat_map(|(field, types)| {
+            types
+                .iter()
+                .map(move |ty| format!("{} ({})", field, ty.info_type))
+        })
+        .collect::<Vec<_>>();
+    for (field, set) in [
+        ("voice", disclose.voice.is_some()),
+        ("fax", disclose.fax.is_some()),
+        ("email", disclose.email.is_some()),
+    ] {
+        if set {
+            fields.push(field.to_owned());
+        }
+    }
+    fields
+}
+
+#[allow(clippy::result_large_err)]
+fn host_addr(address: &str) -> Result<HostAddr, Error> {
+    match address.parse::<IpAddr>() {
+        Ok(address) => Ok(HostAddr::new(address)),
+        Err(_) => Err(usage(&format!("invalid IP address {}", address))),
+    }
+}
+
+fn statuses<S: Display>(statuses: Vec<ObjectStatus<S>>) -> Vec<String> {
+    statuses.into_iter().map(|s| s.status.to_string()).collect()
+}
+
+fn text(value: StringValue) -> String {
+    value.to_string()
+}
+
+/// The U-label form of a name returned by the server, if it is an IDN
+fn unicode(name: &str) -> Option<String> {
+    match DomainName::new(name) {
+        Ok(name) if name.is_idn() => Some(name.to_unicode()),
+        _ => None,
+    }
+}
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+/// Returns the data of a response the registry should always include it in
+#[allow(clippy::result_large_err)]
+fn required<T>(data: Option<T>) -> Result<T, Error> {
+    data.ok_or_else(|| Error::Other("response contains no data".into()))
+}
+
+fn usage(message: &str) -> Error {
+    Error::Other(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use clap::Parser;
+    use tokio::net::TcpStream;
+
+    use epp_client::domain::ContactRole;
+    use epp_client::login::Login;
+    use epp_client::testing::{MockServer, Simulator};
+    use epp_client::EppClient;
+
+    use super::execute;
+    use crate::args::{Cli, Command, DomainCommand, Object};
+    use crate::output::{Field, Output};
+
+    async fn client(server: &MockServer) -> EppClient<TcpStream> {
+        let stream = TcpStream::connect(server.addr()).await.unwrap();
+        let mut client = EppClient::new("simulator".into(), stream).await.unwrap();
+        let login = Login::new("eppdev", "password", None);
+        client.transact(&login, "login").await.unwrap();
+        client
+    }
+
+    /// Runs the command given as a command line, without the binary name
+    async fn run(client: &mut EppClient<TcpStream>, args: &str) -> Output {
+        let cli = Cli::try_parse_from(iter::once("epp").chain(args.split_whitespace())).unwrap();
+        match &cli.command {
+            Command::Object(object) => execute(client, object, "cli-1").await.unwrap(),
+            Command::Shell { .. } => panic!("expected an object command"),
+        }
+    }
+
+    fn field<'a>(output: &'a Output, name: &str) -> &'a Field {
+        let record = match output {
+            Output::Object(record) => record,
+            Output::List(records) => &records[0],
+        };
+        record.get(name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn commands() {
+        let server = Simulator::default()
+            .registrar("eppdev", "password")
+            .start()
+            .await
+            .unwrap();
+        let mut client = client(&server).await;
+
+        let output = run(&mut client, "domain check eppdev.com").await;
+        assert_eq!(field(&output, "available"), &Field::Flag(true));
+
+        run(&mut client, "host create ns1.eppdev.net").await;
+        run(
+            &mut client,
+            "contact create eppdev-1 --email contact@eppdev.net --voice +1.5555555555 \
+             --auth epP4uthd#v --name Doe --street Main --city Paris --country FR",
+        )
+        .await;
+
+        let output = run(
+            &mut client,
+            "domain create eppdev.com --auth epP4uthd#v --registrant eppdev-1 --tech eppdev-1 \
+             --ns ns1.eppdev.net",
+        )
+        .await;
+        assert_eq!(field(&output, "name"), &Field::Text("eppdev.com".into()));
+
+        let output = run(
+            &mut client,
+            "domain update eppdev.com --add-status clientHold",
+        )
+        .await;
+        assert_eq!(field(&output, "code"), &Field::Number(1000));
+
+        let output = run(&mut client, "domain info eppdev.com").await;
+        assert_eq!(
+            field(&output, "registrant"),
+            &Field::Text("eppdev-1".into())
+        );
+        assert_eq!(
+            field(&output, "ns"),
+            &Field::List(vec!["ns1.eppdev.net".into()])
+        );
+        assert_eq!(
+            field(&output, "contacts"),
+            &Field::List(vec!["tech=eppdev-1".into()])
+        );
+        match field(&output, "statuses") {
+            Field::List(statuses) => assert!(statuses.contains(&"clientHold".into())),
+            other => panic!("unexpected statuses: {:?}", other),
+        }
+
+        let output = run(&mut client, "domain create Bücher.com --auth epP4uthd#v").await;
+        assert_eq!(
+            field(&output, "name"),
+            &Field::Text("xn--bcher-kva.com".into())
+        );
+        let output = run(&mut client, "domain info xn--bcher-kva.com").await;
+        assert_eq!(field(&output, "unicode"), &Field::Text("bücher.com".into()));
+
+        let create = "host create ns1.eppdev.com --addr 192.0.2.1 --zone eppdev.com";
+        run(&mut client, create).await;
+        let output = run(&mut client, "host info ns1.eppdev.com").await;
+        assert_eq!(
+            field(&output, "addresses"),
+            &Field::List(vec!["192.0.2.1".into()])
+        );
+
+        let output = run(&mut client, "contact info eppdev-1").await;
+        assert_eq!(field(&output, "country"), &Field::Text("FR".into()));
+
+        let output = run(&mut client, "poll req").await;
+        assert_eq!(field(&output, "code"), &Field::Number(1300));
+    }
+
+    #[test]
+    fn arguments() {
+        let cli = Cli::try_parse_from([
+            "epp",
+            "--json",
+            "domain",
+            "update",
+            "eppdev.com",
+            "--add-contact",
+            "admin=c1",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        match &cli.command {
+            Command::Object(object) => match &**object {
+                Object::Domain(DomainCommand::Update { add_contact, .. }) => {
+                    assert_eq!(add_contact, &[(ContactRole::Admin, "c1".to_owned())])
+                }
+                _ => panic!("expected a domain update"),
+            },
+            Command::Shell { .. } => panic!("expected an object command"),
+        }
+
+        for contact in ["c1", "=c1", "admin="].iter() {
+            let bad = [
+                "epp",
+                "domain",
+                "update",
+                "eppdev.com",
+                "--add-contact",
+                contact,
+            ];
+            assert!(Cli::try_parse_from(bad).is_err(), "{}", contact);
+        }
+        assert!(Cli::try_parse_from(["epp", "domain", "check"]).is_err());
+        assert!(Cli::try_parse_from(["epp", "domain", "check", "exa_mple.com"]).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Runs ad-hoc EPP commands from the command line
+//!
+//! Usage: `epp [--config epp.toml] [--json] <object> <command> [args]`, e.g.
+//! `epp domain check example.com example.net`. The registry to connect to is read from the
+//! `[registry]` table of the configuration file, see `epp_client::config::RegistryConfig`.
+//! Results are printed as tables, or as JSON with `--json`; error results from the registry are
+//! printed to stderr and make the command exit with status 1.
+//!
+//! `epp shell` logs in once and then reads commands interactively, see the `shell` module.
+
+use std::process;
+
+use chrono::Utc;
+use clap::Parser;
+use serde::Deserialize;
+
+use epp_client::config::{self, RegistryConfig};
+use epp_client::logout::Logout;
+use epp_client::Error;
+
+mod args;
+mod commands;
+mod output;
+mod shell;
+
+use args::{Cli, Command};
+
+/// The configuration file of the `epp` binary
+#[derive(Debug, Deserialize)]
+struct Config {
+    registry: RegistryConfig,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    match run(&cli).await {
+        Ok(Some(output)) => print(&output, cli.json),
+        Ok(None) => {}
+        Err(e) => {
+            report(&e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn run(cli: &Cli) -> Result<Option<output::Output>, Error> {
+    let config: Config = config::load(&cli.config)?;
+    let id = match &cli.id {
+        Some(id) => id.clone(),
+        None => format!("epp-{}", Utc::now().format("%Y%m%d%H%M%S%3f")),
+    };
+
+    let mut client = config.registry.connect(&format!("{}-login", id)).await?;
+    let output = match &cli.command {
+        Command::Object(object) => commands::execute(&mut client, object, &id).await.map(Some),
+        Command::Shell { history } => shell::run(&mut client, &id, history.as_deref(), cli.json)
+            .await
+            .map(|_| None),
+    };
+    client.transact(&Logout, &format!("{}-logout", id)).await?;
+    output
+}
+
+/// Prints the result of a command to stdout
+fn print(output: &output::Output, json: bool) {
+    if json {
+        println!("{}", output.json());
+    } else {
+        print!("{}", output.table());
+    }
+}
+
+/// Prints an error to stderr, with the code and reason of error results from the registry
+fn report(error: &Error) {
+    match error {
+        Error::Command(status) => {
+            let reason = status.result.ext_value.as_ref().map(|ext| &ext.reason);
+            match reason {
+                Some(reason) => eprintln!(
+                    "error: {} {} ({})",
+                    status.result.code, status.result.message, reason
+                ),
+                None => eprintln!("error: {} {}", status.result.code, status.result.message),
+            }
+        }
+        e => eprintln!("{}", e),
+    }
+}
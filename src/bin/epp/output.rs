@@ -0,0 +1,289 @@
+//! Rendering of command results as human-readable tables or JSON
+
+use std::fmt::{self, Display, Write};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+/// The text redacted field values are replaced with
+pub const REDACTED: &str = "REDACTED";
+
+/// The value of a single field in a [`Record`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Field {
+    Text(String),
+    List(Vec<String>),
+    Flag(bool),
+    Number(u64),
+    Empty,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Text(text) => f.write_str(text),
+            Field::List(items) => f.write_str(&items.join(", ")),
+            Field::Flag(true) => f.write_str("yes"),
+            Field::Flag(false) => f.write_str("no"),
+            Field::Number(n) => write!(f, "{}", n),
+            Field::Empty => Ok(()),
+        }
+    }
+}
+
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Field::Text(text) => serializer.serialize_str(text),
+            Field::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Field::Flag(flag) => serializer.serialize_bool(*flag),
+            Field::Number(n) => serializer.serialize_u64(*n),
+            Field::Empty => serializer.serialize_none(),
+        }
+    }
+}
+
+impl From<String> for Field {
+    fn from(text: String) -> Self {
+        Field::Text(text)
+    }
+}
+
+impl From<&str> for Field {
+    fn from(text: &str) -> Self {
+        Field::Text(text.to_owned())
+    }
+}
+
+impl From<DateTime<Utc>> for Field {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        Field::Text(datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+impl From<bool> for Field {
+    fn from(flag: bool) -> Self {
+        Field::Flag(flag)
+    }
+}
+
+impl From<u64> for Field {
+    fn from(n: u64) -> Self {
+        Field::Number(n)
+    }
+}
+
+impl From<Vec<String>> for Field {
+    fn from(items: Vec<String>) -> Self {
+        Field::List(items)
+    }
+}
+
+impl<T: Into<Field>> From<Option<T>> for Field {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Field::Empty)
+    }
+}
+
+/// Named fields describing a single object or result, kept in insertion order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Record(Vec<(&'static str, Field)>);
+
+impl Record {
+    /// Appends the field `name`
+    pub fn field(mut self, name: &'static str, value: impl Into<Field>) -> Self {
+        self.0.push((name, value.into()));
+        self
+    }
+
+    /// Appends the fields of `other`
+    pub fn extend(mut self, other: Record) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    /// Replaces the value of the field `name`, if it has one
+    fn redact(&mut self, name: &str) {
+        for (_, value) in self.0.iter_mut().filter(|(n, _)| *n == name) {
+            if *value != Field::Empty {
+                *value = Field::Text(REDACTED.to_owned());
+            }
+        }
+    }
+
+    /// Returns the value of the field `name`
+    #[cfg(test)]
+    pub fn get(&self, name: &str) -> Option<&Field> {
+        self.0
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl Serialize for Record {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// The result of a command: a single record, or a list of records of the same shape
+#[derive(Clone, Debug, PartialEq)]
+pub enum Output {
+    Object(Record),
+    List(Vec<Record>),
+}
+
+impl Output {
+    /// Hides the value of the field `name` in all records, e.g. for passwords
+    pub fn redact(mut self, name: &str) -> Self {
+        match &mut self {
+            Output::Object(record) => record.redact(name),
+            Output::List(records) => records.iter_mut().for_each(|r| r.redact(name)),
+        }
+        self
+    }
+
+    /// Renders the output as a table, with a row per record for lists and a line per field for
+    /// single records
+    pub fn table(&self) -> String {
+        let mut out = String::new();
+        match self {
+            Output::Object(record) => {
+                let fields = record.0.iter().filter(|(_, value)| *value != Field::Empty);
+                let width = fields.clone().map(|(name, _)| name.len()).max();
+                for (name, value) in fields {
+                    let width = width.unwrap_or_default();
+                    writeln!(out, "{:width$}  {}", name, value, width = width).unwrap();
+                }
+            }
+            Output::List(records) => {
+                let names = match records.first() {
+                    Some(first) => first.0.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+                    None => return out,
+                };
+
+                let rows = records
+                    .iter()
+                    .map(|r| r.0.iter().map(|(_, v)| v.to_string()).collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                let widths = names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| rows.iter().map(|r| r[i].len()).fold(name.len(), usize::max))
+                    .collect::<Vec<_>>();
+
+                let header = names.iter().map(|name| name.to_uppercase());
+                write_row(&mut out, header, &widths);
+                for row in rows {
+                    write_row(&mut out, row.into_iter(), &widths);
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the output as pretty-printed JSON
+    pub fn json(&self) -> String {
+        let json = match self {
+            Output::Object(record) => serde_json::to_string_pretty(record),
+            Output::List(records) => serde_json::to_string_pretty(records),
+        };
+        json.expect("records always serialize")
+    }
+}
+
+/// Writes a table row, padding every cell but the last to its column's width
+fn write_row(out: &mut String, cells: impl Iterator<Item = String>, widths: &[usize]) {
+    let line = cells
+        .zip(widths)
+        .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join("  ");
+    writeln!(out, "{}", line.trim_end()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Output, Record};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record::default()
+                .field("name", "eppdev.com")
+                .field("available", true)
+                .field("reason", None::<String>),
+            Record::default()
+                .field("name", "eppdev-example.net")
+                .field("available", false)
+                .field("reason", "In use"),
+        ]
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            Output::List(records()).table(),
+            "NAME                AVAILABLE  REASON\n\
+             eppdev.com          yes\n\
+             eppdev-example.net  no         In use\n"
+        );
+
+        let record = Record::default()
+            .field("name", "eppdev.com")
+            .field("statuses", vec!["ok".to_owned(), "inactive".to_owned()])
+            .field("registrant", None::<String>)
+            .field("period", 1u64);
+        assert_eq!(
+            Output::Object(record).table(),
+            "name      eppdev.com\nstatuses  ok, inactive\nperiod    1\n"
+        );
+    }
+
+    #[test]
+    fn redact() {
+        let record = Record::default()
+            .field("name", "eppdev.com")
+            .field("auth", "epP4uthd#v");
+        let output = Output::Object(record).redact("auth");
+        assert_eq!(output.table(), "name  eppdev.com\nauth  REDACTED\n");
+
+        let output = Output::List(records()).redact("reason");
+        assert_eq!(
+            output.table(),
+            "NAME                AVAILABLE  REASON\n\
+             eppdev.com          yes\n\
+             eppdev-example.net  no         REDACTED\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            Output::List(records()).json(),
+            r#"[
+  {
+    "name": "eppdev.com",
+    "available": true,
+    "reason": null
+  },
+  {
+    "name": "eppdev-example.net",
+    "available": false,
+    "reason": "In use"
+  }
+]"#
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! The interactive shell started by `epp shell`
+//!
+//! The shell keeps the session it was started with logged in and reads commands with the same
+//! syntax as the command line, without the `epp` prefix, e.g. `domain info example.com`. Commands
+//! and their options are completed with tab. Besides the object commands, `greeting` shows the
+//! registry's greeting, `xml [FILE]` sends raw XML read from a file or pasted up to `</epp>` and
+//! prints the response, and `exit` or `quit` (or Ctrl-D) logs out. Passwords are redacted from
+//! the history, from printed XML and from printed results.
+
+use std::fs;
+use std::path::Path;
+
+use clap::{CommandFactory, Parser};
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::task;
+
+use epp_client::hello::Greeting;
+use epp_client::recording;
+use epp_client::{EppClient, Error};
+
+use crate::args::{Line, LineCommand};
+use crate::output::{Output, Record, REDACTED};
+use crate::{commands, print, report};
+
+/// The options whose values are redacted from the history
+const SECRET_OPTIONS: &[&str] = &["--auth"];
+
+/// The prompt shown for commands
+const PROMPT: &str = "epp> ";
+
+/// The prompt shown for lines of pasted XML
+const CONTINUATION: &str = "...> ";
+
+type ShellEditor = Editor<Completion, DefaultHistory>;
+
+/// Reads and runs commands until `exit` or the end of input
+///
+/// Client transaction IDs are `prefix` followed by a counter. Results are printed as JSON
+/// instead of tables for all commands if `json` is set, or for single commands given `--json`.
+pub async fn run<IO>(
+    client: &mut EppClient<IO>,
+    prefix: &str,
+    history: Option<&Path>,
+    json: bool,
+) -> Result<(), Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    let mut editor = ShellEditor::new().map_err(|e| Error::Other(e.into()))?;
+    editor.set_helper(Some(Completion::default()));
+    if let Some(path) = history {
+        // The history file doesn't exist on first use
+        let _ = editor.load_history(path);
+    }
+
+    print(&Output::Object(greeting(&client.greeting()?)), json);
+
+    let mut count = 0;
+    while let Some(line) = readline(&mut editor, PROMPT)? {
+        let words = match shell_words::split(&line) {
+            Ok(words) if words.is_empty() => continue,
+            Ok(words) => words,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                continue;
+            }
+        };
+        let _ = editor.add_history_entry(redact(&words));
+
+        let line = match Line::try_parse_from(&words) {
+            Ok(line) => line,
+            Err(e) => {
+                let _ = e.print();
+                continue;
+            }
+        };
+
+        count += 1;
+        let id = format!("{}-{}", prefix, count);
+        let output = match &line.command {
+            LineCommand::Object(object) => commands::execute(client, object, &id).await,
+            LineCommand::Greeting => client.hello().await.map(|g| Output::Object(greeting(&g))),
+            LineCommand::Xml { file } => {
+                let xml = match file {
+                    Some(path) => fs::read_to_string(path).map(Some),
+                    None => paste(&mut editor),
+                };
+                match xml {
+                    Ok(Some(xml)) => match client.transact_xml(&xml).await {
+                        Ok(response) => println!("{}", pretty(&response)),
+                        Err(e) => report(&e),
+                    },
+                    Ok(None) => {}
+                    Err(e) => report(&e.into()),
+                }
+                continue;
+            }
+            LineCommand::Exit => break,
+        };
+
+        match output {
+            Ok(output) => print(&output.redact("auth"), json || line.json),
+            Err(e) => report(&e),
+        }
+    }
+
+    if let Some(path) = history {
+        editor
+            .save_history(path)
+            .map_err(|e| Error::Other(e.into()))?;
+    }
+    Ok(())
+}
+
+/// Reads a line, returning `None` at the end of input
+///
+/// Ctrl-C discards the line being edited and starts a new one.
+#[allow(clippy::result_large_err)]
+fn readline(editor: &mut ShellEditor, prompt: &str) -> Result<Option<String>, Error> {
+    loop {
+        match task::block_in_place(|| editor.readline(prompt)) {
+            Ok(line) => return Ok(Some(line)),
+            Err(rustyline::error::ReadlineError::Interrupted) => continue,
+            Err(rustyline::error::ReadlineError::Eof) => return Ok(None),
+            Err(e) => return Err(Error::Other(e.into())),
+        }
+    }
+}
+
+/// Reads XML pasted after the `xml` command, up to the line holding `</epp>`
+///
+/// Returns `None` if the input ends before. Pasted lines are not added to the history.
+fn paste(editor: &mut ShellEditor) -> Result<Option<String>, std::io::Error> {
+    let mut xml = String::new();
+    loop {
+        let line = match readline(editor, CONTINUATION) {
+            Ok(Some(line)) => line,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(std::io::Error::other(e.to_string())),
+        };
+
+        xml.push_str(&line);
+        xml.push('\n');
+        if line.contains("</epp>") {
+            return Ok(Some(xml));
+        }
+    }
+}
+
+/// Joins the words of a command line for the history, hiding the values of secret options
+fn redact(words: &[String]) -> String {
+    let mut redacted = Vec::with_capacity(words.len());
+    let mut secret = false;
+    for word in words {
+        redacted.push(match word.split_once('=') {
+            _ if secret => REDACTED.to_owned(),
+            Some((option, _)) if SECRET_OPTIONS.contains(&option) => {
+                format!("{}={}", option, REDACTED)
+            }
+            _ => word.clone(),
+        });
+        secret = !secret && SECRET_OPTIONS.contains(&word.as_str());
+    }
+    shell_words::join(redacted)
+}
+
+/// Indents `xml` for display, with passwords redacted
+///
+/// XML that doesn't parse is returned as received, apart from the redaction.
+fn pretty(xml: &str) -> String {
+    let xml = recording::redact(xml);
+    let mut reader = Reader::from_str(&xml);
+    reader.trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(event) => {
+                if writer.write_event(event).is_err() {
+                    return xml;
+                }
+            }
+            Err(_) => return xml,
+        }
+        buf.clear();
+    }
+
+    String::from_utf8(writer.into_inner()).unwrap_or(xml)
+}
+
+fn greeting(greeting: &Greeting) -> Record {
+    let menu = &greeting.svc_menu;
+    let extensions = menu.services.svc_ext.as_ref();
+    let extensions = extensions.and_then(|ext| ext.ext_uris.as_ref());
+    Record::default()
+        .field("server", greeting.service_id.as_str())
+        .field("date", greeting.service_date)
+        .field("version", menu.options.version.to_string())
+        .field("lang", menu.options.lang.to_string())
+        .field(
+            "objects",
+            menu.services
+                .obj_uris
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .field(
+            "extensions",
+            extensions.map(|uris| uris.iter().map(ToString::to_string).collect::<Vec<_>>()),
+        )
+}
+
+/// Completes commands, their options, and the file names given to `xml`
+struct Completion {
+    command: clap::Command,
+    files: FilenameCompleter,
+}
+
+impl Completion {
+    /// Returns the start of the word being completed at the end of `line`, and its candidates
+    fn candidates(&self, line: &str) -> (usize, Vec<String>, &clap::Command) {
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let (done, partial) = line.split_at(start);
+
+        let mut command = &self.command;
+        for word in done.split_whitespace() {
+            if let Some(subcommand) = command.find_subcommand(word) {
+                command = subcommand;
+            }
+        }
+
+        let mut names = if partial.starts_with('-') {
+            command
+                .get_arguments()
+                .filter(|arg| !arg.is_hide_set())
+                .filter_map(|arg| arg.get_long())
+                .map(|long| format!("--{}", long))
+                .collect::<Vec<_>>()
+        } else {
+            command
+                .get_subcommands()
+                .filter(|subcommand| !subcommand.is_hide_set())
+                .map(|subcommand| subcommand.get_name().to_owned())
+                .collect()
+        };
+        names.retain(|name| name.starts_with(partial));
+        names.sort();
+        (start, names, command)
+    }
+}
+
+impl Default for Completion {
+    fn default() -> Self {
+        let mut command = Line::command();
+        command.build();
+        Self {
+            command,
+            files: FilenameCompleter::new(),
+        }
+    }
+}
+
+impl Completer for Completion {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, names, command) = self.candidates(&line[..pos]);
+        if command.get_name() == "xml" && !line[start..pos].starts_with('-') {
+            return self.files.complete(line, pos, ctx);
+        }
+
+        let pairs = names
+            .into_iter()
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: format!("{} ", name),
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for Completion {
+    type Hint = String;
+}
+
+impl Highlighter for Completion {}
+
+impl Validator for Completion {}
+
+impl Helper for Completion {}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use epp_client::hello::Greeting;
+
+    use super::{greeting, pretty, redact, Completion};
+    use crate::args::{Line, LineCommand, Object};
+    use crate::output::Field;
+
+    fn complete(line: &str) -> (usize, Vec<String>) {
+        let (start, names, _) = Completion::default().candidates(line);
+        (start, names)
+    }
+
+    #[test]
+    fn completion() {
+        assert_eq!(complete("dom"), (0, vec!["domain".to_owned()]));
+        assert_eq!(
+            complete("domain c"),
+            (7, vec!["check".to_owned(), "create".to_owned()])
+        );
+        assert_eq!(
+            complete("domain create eppdev.com --re"),
+            (25, vec!["--registrant".to_owned()])
+        );
+        assert_eq!(complete("host info --js"), (10, vec!["--json".to_owned()]));
+        assert_eq!(complete("poll ack 12 x"), (12, vec![]));
+        assert!(complete("").1.contains(&"xml".to_owned()));
+    }
+
+    #[test]
+    fn history() {
+        let words = [
+            "contact", "create", "c1", "--auth", "s3cret", "--name", "J Doe",
+        ];
+        let words = words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            redact(&words),
+            "contact create c1 --auth REDACTED --name 'J Doe'"
+        );
+
+        let words = ["domain", "info", "eppdev.com", "--auth=s3cret"];
+        let words = words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        assert_eq!(redact(&words), "domain info eppdev.com '--auth=REDACTED'");
+    }
+
+    #[test]
+    fn xml() {
+        let xml = "<epp><command><login><clID>eppdev</clID><pw>s3cret</pw></login></command></epp>";
+        assert_eq!(
+            pretty(xml),
+            "<epp>\n  <command>\n    <login>\n      <clID>eppdev</clID>\n      \
+             <pw>REDACTED</pw>\n    </login>\n  </command>\n</epp>"
+        );
+        assert_eq!(
+            pretty("<epp><pw>x</pw></ep>"),
+            "<epp><pw>REDACTED</pw></ep>"
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let line = Line::try_parse_from(["domain", "check", "eppdev.com", "--json"]).unwrap();
+        assert!(line.json);
+        match line.command {
+            LineCommand::Object(object) => assert!(matches!(*object, Object::Domain(_))),
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let line = Line::try_parse_from(["quit"]).unwrap();
+        assert!(matches!(line.command, LineCommand::Exit));
+        assert!(Line::try_parse_from(["shell"]).is_err());
+    }
+
+    #[test]
+    fn greeting_record() {
+        let hello = Greeting::builder("eppdev")
+            .extension("urn:ietf:params:xml:ns:rgp-1.0")
+            .build();
+        let record = greeting(&hello);
+        assert_eq!(record.get("server"), Some(&Field::Text("eppdev".into())));
+        assert_eq!(
+            record.get("extensions"),
+            Some(&Field::List(vec!["urn:ietf:params:xml:ns:rgp-1.0".into()]))
+        );
+    }
+}
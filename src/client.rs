@@ -51,7 +51,10 @@ use tracing::info;
 use crate::common::{Certificate, NoExtension, PrivateKey};
 use crate::connection::EppConnection;
 use crate::error::Error;
+use crate::handle::EppHandle;
 use crate::hello::{Greeting, GreetingDocument, HelloDocument};
+use crate::metrics::Recorder;
+use crate::middleware::{self, Middleware, Request};
 use crate::request::{Command, Extension, Transaction};
 use crate::response::Response;
 use crate::xml::EppXml;
@@ -61,6 +64,8 @@ use crate::xml::EppXml;
 /// to the registry and deserialize the XML responses from the registry to local types
 pub struct EppClient<IO> {
     connection: EppConnection<IO>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    dispatch: Option<middleware::Dispatch<IO>>,
 }
 
 #[cfg(feature = "tokio-rustls")]
@@ -126,6 +131,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppClient<IO> {
     pub async fn new(registry: String, stream: IO) -> Result<Self, Error> {
         Ok(Self {
             connection: EppConnection::new(registry, stream).await?,
+            middleware: Vec::new(),
+            dispatch: None,
         })
     }
 
@@ -138,25 +145,35 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppClient<IO> {
         Ok(GreetingDocument::deserialize(&response)?.data)
     }
 
-    pub async fn transact<'a, C: 'a, E: 'a>(
+    pub async fn transact<'a, C, E>(
         &mut self,
         data: impl Into<RequestData<'a, C, E>> + 'a,
         id: &str,
     ) -> Result<Response<C::Response, E::Response>, Error>
     where
-        C: Transaction<E> + Command,
-        E: Extension,
+        C: Transaction<E> + Command + 'a,
+        E: Extension + 'a,
     {
         let data = data.into();
-        let epp_xml = <C as Transaction<E>>::serialize_request(data.command, data.extension, id)?;
+        let request = Request {
+            registry: self.connection.registry.clone(),
+            command: C::COMMAND,
+            object: C::OBJECT,
+            client_tr_id: id.to_owned(),
+            xml: <C as Transaction<E>>::serialize_request(data.command, data.extension, id)?,
+        };
 
-        let response = self.connection.transact(&epp_xml).await?;
+        let reply = match self.dispatch {
+            Some(dispatch) => dispatch(&self.middleware, &mut self.connection, &request).await?,
+            None => middleware::send(&mut self.connection, &request).await?,
+        };
 
-        C::deserialize_response(&response)
+        C::deserialize_response(&reply.xml)
     }
 
     /// Accepts raw EPP XML and returns the raw EPP XML response to it.
-    /// Not recommended for direct use but sometimes can be useful for debugging
+    /// Not recommended for direct use but sometimes can be useful for debugging.
+    /// Raw XML bypasses any registered middleware
     pub async fn transact_xml(&mut self, xml: &str) -> Result<String, Error> {
         self.connection.transact(xml).await
     }
@@ -167,6 +184,7 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppClient<IO> {
     }
 
     /// Returns the greeting received on establishment of the connection as an `Greeting`
+    #[allow(clippy::result_large_err)]
     pub fn greeting(&self) -> Result<Greeting, Error> {
         GreetingDocument::deserialize(&self.connection.greeting).map(|obj| obj.data)
     }
@@ -176,9 +194,43 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppClient<IO> {
     }
 }
 
+impl<IO: AsyncRead + AsyncWrite + Unpin + Send> EppClient<IO> {
+    /// Adds a [`Middleware`] layer around the exchanges made by `transact()`
+    ///
+    /// Layers are called in the order they were added, so the first layer added sees each
+    /// request first and each reply last.
+    pub fn with_middleware(&mut self, layer: impl Middleware + 'static) -> &mut Self {
+        self.middleware.push(Arc::new(layer));
+        self.dispatch = Some(middleware::dispatch_boxed::<IO>);
+        self
+    }
+
+    /// Attaches a metrics [`Recorder`] to the connection
+    ///
+    /// The recorder is notified of every command sent through `transact()`, the bytes written to
+    /// and read from the connection, and the opening and closing of the session.
+    pub fn with_recorder(&mut self, recorder: Arc<dyn Recorder>) -> &mut Self {
+        self.connection.set_recorder(recorder);
+        self
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> EppClient<IO> {
+    /// Moves the connection to a background task and returns a cloneable [`EppHandle`] to it
+    ///
+    /// At most `queue_depth` commands are queued for the connection at any time; callers
+    /// submitting further commands wait until one of the queued commands has been sent.
+    /// `queue_depth` must be at least 1, or [`Error::Invalid`] is returned.
+    /// Must be called from within a tokio runtime.
+    #[allow(clippy::result_large_err)]
+    pub fn into_handle(self, queue_depth: usize) -> Result<EppHandle, Error> {
+        EppHandle::spawn(self.connection, self.middleware, queue_depth)
+    }
+}
+
 pub struct RequestData<'a, C, E> {
-    command: &'a C,
-    extension: Option<&'a E>,
+    pub(crate) command: &'a C,
+    pub(crate) extension: Option<&'a E>,
 }
 
 impl<'a, C: Command> From<&'a C> for RequestData<'a, C, NoExtension> {
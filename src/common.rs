@@ -1,10 +1,17 @@
 //! Common data types included in EPP Requests and Responses
 
-use std::{borrow::Cow, fmt::Display};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use idna::uts46::{AsciiDenyList, DnsLength, Hyphens, Uts46};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::request::Extension;
+use crate::Error;
 
 pub(crate) const EPP_XMLNS: &str = "urn:ietf:params:xml:ns:epp-1.0";
 
@@ -19,6 +26,12 @@ impl Display for StringValue<'_> {
     }
 }
 
+impl AsRef<str> for StringValue<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl<'a> From<&'a str> for StringValue<'a> {
     fn from(s: &'a str) -> Self {
         Self(s.into())
@@ -31,6 +44,12 @@ impl From<String> for StringValue<'static> {
     }
 }
 
+impl<'a> From<StringValue<'a>> for Cow<'a, str> {
+    fn from(s: StringValue<'a>) -> Self {
+        s.0
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// An empty placeholder tag. To be refactored to something more compliant later.
 pub struct NoExtension;
@@ -40,7 +59,7 @@ impl Extension for NoExtension {
 }
 
 /// The <option> type in EPP XML login requests
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename = "options")]
 pub struct Options<'a> {
     /// The EPP version being used
@@ -60,7 +79,7 @@ impl<'a> Options<'a> {
 }
 
 /// The <svcExtension> type in EPP XML
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "svcExtension")]
 pub struct ServiceExtension<'a> {
     /// The service extension URIs being represented by <extURI> in EPP XML
@@ -69,7 +88,7 @@ pub struct ServiceExtension<'a> {
 }
 
 /// The <svcs> type in EPP XML
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Services<'a> {
     /// The service URIs being used by this EPP session represented by <objURI> in EPP XML
     #[serde(rename = "objURI")]
@@ -79,47 +98,663 @@ pub struct Services<'a> {
     pub svc_ext: Option<ServiceExtension<'a>>,
 }
 
+/// A domain or host name, held in its normalized A-label (ASCII) form
+///
+/// Names are converted following IDNA 2008 as profiled by UTS #46, so `Bücher.Example.` becomes
+/// `xn--bcher-kva.example`, and checked to be made of LDH labels of at most 63 octets, making up
+/// at most 253 octets. `DomainName` derefs to `str`, so it can be passed to any domain or host
+/// command. Commands convert the names they're built with the same way, so `EPPDEV.com` is sent
+/// as `eppdev.com`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DomainName(String);
+
+impl DomainName {
+    /// Converts `name` to its A-label form, validating it
+    #[allow(clippy::result_large_err)]
+    pub fn new(name: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::Invalid(format!("domain name {} {}", name, reason));
+
+        let trimmed = name.strip_suffix('.').unwrap_or(name);
+        if trimmed.is_empty() {
+            return Err(invalid("is empty"));
+        }
+
+        let ascii = Uts46::new()
+            .to_ascii(
+                trimmed.as_bytes(),
+                AsciiDenyList::STD3,
+                Hyphens::CheckFirstLast,
+                DnsLength::Ignore,
+            )
+            .map_err(|_| invalid("isn't a valid IDNA name"))?;
+
+        for label in ascii.split('.') {
+            if label.is_empty() {
+                return Err(invalid("has an empty label"));
+            } else if label.len() > 63 {
+                return Err(invalid(&format!(
+                    "has a label longer than 63 octets: {}",
+                    label
+                )));
+            } else if !label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            {
+                return Err(invalid(&format!("has a non-LDH label: {}", label)));
+            } else if label.starts_with('-') || label.ends_with('-') {
+                return Err(invalid(&format!(
+                    "has a label starting or ending with '-': {}",
+                    label
+                )));
+            }
+        }
+
+        if ascii.len() > 253 {
+            return Err(invalid("is longer than 253 octets"));
+        }
+
+        Ok(Self(ascii.into_owned()))
+    }
+
+    /// The A-label form, as sent to the server
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether any of the labels is an A-label
+    pub fn is_idn(&self) -> bool {
+        self.0.split('.').any(|label| label.starts_with("xn--"))
+    }
+
+    /// The U-label form, for display
+    pub fn to_unicode(&self) -> String {
+        let (unicode, result) =
+            Uts46::new().to_unicode(self.0.as_bytes(), AsciiDenyList::STD3, Hyphens::Allow);
+        match result {
+            Ok(()) => unicode.into_owned(),
+            // Punycode that doesn't decode to a valid name is shown as is
+            Err(_) => self.0.clone(),
+        }
+    }
+
+    /// Whether the name is `zone` itself or lies below it
+    pub fn is_within(&self, zone: &DomainName) -> bool {
+        match self.0.strip_suffix(zone.as_str()) {
+            Some(prefix) => prefix.is_empty() || prefix.ends_with('.'),
+            None => false,
+        }
+    }
+
+    /// The A-label form of `name`, if it's a valid name that isn't already in that form
+    pub(crate) fn converted(name: &str) -> Option<Self> {
+        Self::new(name)
+            .ok()
+            .filter(|normalized| normalized.as_str() != name)
+    }
+
+    /// The A-label form of `name` for a command, or `name` as is if it isn't a valid name, which
+    /// the command's validation then reports
+    pub(crate) fn normalize(name: &str) -> StringValue<'_> {
+        match Self::converted(name) {
+            Some(normalized) => normalized.into(),
+            None => name.into(),
+        }
+    }
+
+    /// Checks that `name` is already in normalized A-label form, as required in commands
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn validate(name: &str) -> Result<(), Error> {
+        let normalized = Self::new(name)?;
+        if normalized.as_str() != name {
+            return Err(Error::Invalid(format!(
+                "domain name {} isn't in normalized A-label form, use {}",
+                name, normalized
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The U-label form of a name returned by the server, or the name as is if it isn't a valid name
+pub(crate) fn unicode(name: &str) -> String {
+    match DomainName::new(name) {
+        Ok(name) => name.to_unicode(),
+        Err(_) => name.to_owned(),
+    }
+}
+
+impl FromStr for DomainName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl std::ops::Deref for DomainName {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for DomainName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for DomainName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> From<&'a DomainName> for StringValue<'a> {
+    fn from(name: &'a DomainName) -> Self {
+        Self(name.as_str().into())
+    }
+}
+
+impl From<DomainName> for StringValue<'static> {
+    fn from(name: DomainName) -> Self {
+        Self(name.0.into())
+    }
+}
+
+impl Serialize for DomainName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for DomainName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::new(&name).map_err(D::Error::custom)
+    }
+}
+
 /// The &lt;hostAddr&gt; types domain or host transactions
-#[derive(Serialize, Deserialize, Debug)]
-pub struct HostAddr<'a> {
+///
+/// The 'ip' attr is derived from the address, so it can't disagree with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostAddr {
+    pub address: IpAddr,
+}
+
+impl HostAddr {
+    /// Creates a HostAddr for the given address
+    pub fn new(address: IpAddr) -> Self {
+        Self { address }
+    }
+
+    /// The value of the 'ip' attr, 'v4' or 'v6'
+    pub fn ip_version(&self) -> &'static str {
+        match self.address {
+            IpAddr::V4(_) => "v4",
+            IpAddr::V6(_) => "v6",
+        }
+    }
+}
+
+impl From<IpAddr> for HostAddr {
+    fn from(address: IpAddr) -> Self {
+        Self::new(address)
+    }
+}
+
+impl From<Ipv4Addr> for HostAddr {
+    fn from(address: Ipv4Addr) -> Self {
+        Self::new(address.into())
+    }
+}
+
+impl From<Ipv6Addr> for HostAddr {
+    fn from(address: Ipv6Addr) -> Self {
+        Self::new(address.into())
+    }
+}
+
+impl Display for HostAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.address.fmt(f)
+    }
+}
+
+/// The &lt;hostAddr&gt; tag as it appears in the XML
+#[derive(Serialize, Deserialize)]
+struct RawHostAddr<'a> {
     #[serde(rename = "ip")]
-    pub ip_version: Option<Cow<'a, str>>,
+    ip_version: Option<Cow<'a, str>>,
+    #[serde(rename = "$value")]
+    address: Cow<'a, str>,
+}
+
+impl Serialize for HostAddr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawHostAddr {
+            ip_version: Some(self.ip_version().into()),
+            address: self.address.to_string().into(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HostAddr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawHostAddr::deserialize(deserializer)?;
+        let address = raw.address.trim();
+        let addr = match address.parse::<IpAddr>() {
+            Ok(address) => HostAddr::new(address),
+            Err(_) => return Err(D::Error::custom(format!("invalid IP address {}", address))),
+        };
+
+        match raw.ip_version.as_deref() {
+            // 'v4' is the default, but be lenient with servers leaving it out for IPv6 addresses
+            None => Ok(addr),
+            Some(ip_version) if ip_version == addr.ip_version() => Ok(addr),
+            Some(ip_version) => Err(D::Error::custom(format!(
+                "{} address {} has ip attr {}",
+                addr.ip_version(),
+                address,
+                ip_version
+            ))),
+        }
+    }
+}
+
+/// The &lt;status&gt; type of domain, contact and host objects
+///
+/// `S` is one of [`DomainStatus`], [`ContactStatus`] or [`HostStatus`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ObjectStatus<'a, S> {
+    /// The status, represented by the 's' attr on &lt;status&gt; tags
+    #[serde(rename = "s")]
+    pub status: S,
+    /// The language of the reason, English if not set
+    pub lang: Option<Cow<'a, str>>,
+    /// Why the status was set, in free text
     #[serde(rename = "$value")]
-    pub address: Cow<'a, str>,
+    pub reason: Option<Cow<'a, str>>,
 }
 
-impl<'a> HostAddr<'a> {
-    /// Creates a 'v4' type HostAddr (mostly useful when you don't want to include an 'ip' attr in the XML)
-    pub fn new(ip_version: &'a str, address: &'a str) -> Self {
+impl<'a, S> ObjectStatus<'a, S> {
+    /// Creates a status without a reason
+    pub fn new(status: S) -> Self {
         Self {
-            ip_version: Some(ip_version.into()),
-            address: address.into(),
+            status,
+            lang: None,
+            reason: None,
         }
     }
 
-    /// Creates a 'v4' type HostAddr
-    pub fn new_v4(address: &'a str) -> HostAddr {
-        HostAddr {
-            ip_version: Some("v4".into()),
-            address: address.into(),
+    /// Sets why the status was set, in `lang` or English if `None`
+    pub fn reason(mut self, reason: &'a str, lang: Option<&'a str>) -> Self {
+        self.reason = Some(reason.into());
+        self.lang = lang.map(Into::into);
+        self
+    }
+}
+
+impl<S> From<S> for ObjectStatus<'_, S> {
+    fn from(status: S) -> Self {
+        Self::new(status)
+    }
+}
+
+/// The error returned when parsing a status not defined for the object
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownStatus(pub String);
+
+impl Display for UnknownStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown status: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownStatus {}
+
+/// Defines a status enum, (de)serialized as the status names the EPP RFCs define
+///
+/// Parsing with `FromStr` only accepts the RFC names, while deserializing a response keeps any
+/// other name a registry returns in the `Other` variant.
+macro_rules! status_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        pub enum $name {
+            $(
+                #[doc = concat!("`", $value, "`")]
+                $variant,
+            )*
+            /// A status the RFCs do not define, as returned by the server
+            Other(String),
+        }
+
+        impl $name {
+            /// Returns the status name as used in EPP XML
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Other(status) => status,
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnknownStatus;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($value => Ok(Self::$variant),)*
+                    _ => Err(UnknownStatus(s.to_owned())),
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
         }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let status = String::deserialize(deserializer)?;
+                Ok(status.parse().unwrap_or(Self::Other(status)))
+            }
+        }
+    };
+}
+
+/// Adds the predicates of object statuses to status enums
+macro_rules! object_status {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// Returns whether the status prohibits some transform command
+                pub fn is_prohibition(&self) -> bool {
+                    self.as_str().ends_with("Prohibited")
+                }
+
+                /// Returns whether a transform command on the object awaits completion
+                pub fn is_pending(&self) -> bool {
+                    self.as_str().starts_with("pending")
+                }
+            }
+        )*
+    };
+}
+
+object_status!(DomainStatus, ContactStatus, HostStatus);
+
+status_enum! {
+    /// The statuses of domain objects (RFC 5731)
+    DomainStatus {
+        ClientDeleteProhibited = "clientDeleteProhibited",
+        ClientHold = "clientHold",
+        ClientRenewProhibited = "clientRenewProhibited",
+        ClientTransferProhibited = "clientTransferProhibited",
+        ClientUpdateProhibited = "clientUpdateProhibited",
+        Inactive = "inactive",
+        Ok = "ok",
+        PendingCreate = "pendingCreate",
+        PendingDelete = "pendingDelete",
+        PendingRenew = "pendingRenew",
+        PendingTransfer = "pendingTransfer",
+        PendingUpdate = "pendingUpdate",
+        ServerDeleteProhibited = "serverDeleteProhibited",
+        ServerHold = "serverHold",
+        ServerRenewProhibited = "serverRenewProhibited",
+        ServerTransferProhibited = "serverTransferProhibited",
+        ServerUpdateProhibited = "serverUpdateProhibited",
+    }
+}
+
+status_enum! {
+    /// The statuses of contact objects (RFC 5733)
+    ContactStatus {
+        ClientDeleteProhibited = "clientDeleteProhibited",
+        ClientTransferProhibited = "clientTransferProhibited",
+        ClientUpdateProhibited = "clientUpdateProhibited",
+        Linked = "linked",
+        Ok = "ok",
+        PendingCreate = "pendingCreate",
+        PendingDelete = "pendingDelete",
+        PendingTransfer = "pendingTransfer",
+        PendingUpdate = "pendingUpdate",
+        ServerDeleteProhibited = "serverDeleteProhibited",
+        ServerTransferProhibited = "serverTransferProhibited",
+        ServerUpdateProhibited = "serverUpdateProhibited",
+    }
+}
+
+status_enum! {
+    /// The statuses of host objects (RFC 5732)
+    HostStatus {
+        ClientDeleteProhibited = "clientDeleteProhibited",
+        ClientUpdateProhibited = "clientUpdateProhibited",
+        Linked = "linked",
+        Ok = "ok",
+        PendingCreate = "pendingCreate",
+        PendingDelete = "pendingDelete",
+        PendingTransfer = "pendingTransfer",
+        PendingUpdate = "pendingUpdate",
+        ServerDeleteProhibited = "serverDeleteProhibited",
+        ServerUpdateProhibited = "serverUpdateProhibited",
+    }
+}
+
+status_enum! {
+    /// The status of an object transfer (RFC 5730)
+    TransferStatus {
+        ClientApproved = "clientApproved",
+        ClientCancelled = "clientCancelled",
+        ClientRejected = "clientRejected",
+        Pending = "pending",
+        ServerApproved = "serverApproved",
+        ServerCancelled = "serverCancelled",
+    }
+}
+
+impl TransferStatus {
+    /// Returns whether the transfer awaits action by the losing client or the server
+    pub fn is_pending(&self) -> bool {
+        *self == Self::Pending
+    }
+
+    /// Returns whether the transfer was approved, moving the object to the requesting client
+    pub fn is_completed(&self) -> bool {
+        matches!(self, Self::ClientApproved | Self::ServerApproved)
+    }
+
+    /// Returns whether the transfer was rejected or cancelled, leaving the object in place
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self,
+            Self::ClientRejected | Self::ClientCancelled | Self::ServerCancelled
+        )
+    }
+}
+
+/// The state of an object transfer, as reported in the &lt;trnData&gt; of transfer responses and
+/// poll messages
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferRecord {
+    /// The transfer status
+    pub status: TransferStatus,
+    /// The client that requested the transfer
+    pub requested_by: String,
+    /// When the transfer was requested
+    pub requested_at: DateTime<Utc>,
+    /// The client that should act, or acted, on the transfer
+    pub action_by: String,
+    /// When the transfer is to be acted upon if pending, or was acted upon otherwise
+    pub action_at: DateTime<Utc>,
+}
+
+impl TransferRecord {
+    /// Returns the date by which the losing client must approve or reject a pending transfer
+    ///
+    /// Past it, the server acts on the transfer, usually approving it.
+    pub fn deadline(&self) -> Option<DateTime<Utc>> {
+        self.status.is_pending().then_some(self.action_at)
+    }
+
+    /// Returns how long remains until the deadline of a pending transfer at `now`, or zero if it
+    /// has passed
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let deadline = self.deadline()?;
+        Some((deadline - now).max(Duration::zero()))
+    }
+
+    /// Returns whether the transfer is still pending past its deadline at `now`
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.deadline().is_some_and(|deadline| deadline < now)
     }
 
-    /// Creates a 'v6' type HostAddr
-    pub fn new_v6(address: &'a str) -> HostAddr {
-        HostAddr {
-            ip_version: Some("v6".into()),
-            address: address.into(),
+    /// Returns when the transfer was approved, rejected or cancelled
+    pub fn resolved_at(&self) -> Option<DateTime<Utc>> {
+        if self.status.is_pending() {
+            None
+        } else {
+            Some(self.action_at)
         }
     }
 }
 
-/// The &lt;status&gt; type on contact transactions
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ObjectStatus<'a> {
-    /// The status name, represented by the 's' attr on &lt;status&gt; tags
-    #[serde(rename = "s")]
-    pub status: Cow<'a, str>,
+/// Parses a date and time as found in EPP responses
+///
+/// Registries differ in how they format timestamps, so besides RFC 3339 timestamps with or without
+/// fractional seconds (`2021-07-25T18:11:35.0Z`, `2022-01-02T11:30:45Z`) this accepts offsets
+/// without a colon (`+0100`), timestamps without an offset or with a space instead of the `T`,
+/// which are taken to be in UTC, and plain dates, taken to be midnight UTC.
+pub fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(datetime.and_utc());
+        }
+    }
+
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    Some(date.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// (De)serializes fields that convert to and from strings, such as [`TransferStatus`], as the
+/// inner text of an element rather than as an attribute
+pub(crate) mod element {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("element", &value.to_string())
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(D::Error::custom)
+    }
+}
+
+/// (De)serializes `bool` fields as XML schema booleans, written as "1" or "0" and read from any of
+/// "1", "0", "true" or "false"
+pub(crate) mod boolean {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(if *value { "1" } else { "0" })
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<bool, D::Error> {
+        match String::deserialize(deserializer)?.trim() {
+            "1" | "true" => Ok(true),
+            "0" | "false" => Ok(false),
+            value => Err(D::Error::custom(format!("invalid boolean: {}", value))),
+        }
+    }
+}
+
+/// (De)serializes `DateTime<Utc>` fields as the inner text of an element, using
+/// [`parse_datetime()`] to parse them
+pub(crate) mod datetime {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let value = value.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        serializer.serialize_newtype_struct("element", &value)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse_datetime(&value)
+            .ok_or_else(|| D::Error::custom(format!("invalid date and time: {}", value)))
+    }
+
+    /// (De)serializes `Option<DateTime<Utc>>` fields, which must also be marked
+    /// `#[serde(default)]`
+    pub(crate) mod option {
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub(crate) fn serialize<S: Serializer>(
+            value: &Option<DateTime<Utc>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            #[derive(Deserialize)]
+            struct Wrapper(#[serde(with = "super")] DateTime<Utc>);
+
+            let value = Option::<Wrapper>::deserialize(deserializer)?;
+            Ok(value.map(|Wrapper(value)| value))
+        }
+    }
 }
 
 /// This type contains a single DER-encoded X.509 certificate.
@@ -131,3 +766,174 @@ pub struct Certificate(pub Vec<u8>);
 ///
 /// The rustls-pemfile crate can be used to parse a PEM file in these formats.
 pub struct PrivateKey(pub Vec<u8>);
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use std::borrow::Cow;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::{
+        parse_datetime, DomainName, DomainStatus, Error, HostAddr, HostStatus, ObjectStatus,
+        StringValue, TransferRecord, TransferStatus, UnknownStatus,
+    };
+    use crate::common::NoExtension;
+    use crate::domain::update::{DomainAddRemove, DomainUpdate};
+    use crate::request::Transaction;
+
+    #[test]
+    fn datetimes() {
+        let expected = Utc.with_ymd_and_hms(2022, 1, 2, 11, 30, 45).unwrap();
+        for s in [
+            "2022-01-02T11:30:45Z",
+            "2022-01-02T11:30:45.0Z",
+            "2022-01-02T11:30:45.000Z",
+            "2022-01-02T12:30:45+01:00",
+            "2022-01-02T12:30:45+0100",
+            "2022-01-02T11:30:45",
+            "2022-01-02 11:30:45.0",
+            " 2022-01-02T11:30:45Z\n",
+        ] {
+            assert_eq!(parse_datetime(s), Some(expected), "{}", s);
+        }
+
+        let midnight = Utc.with_ymd_and_hms(2022, 1, 2, 0, 0, 0).unwrap();
+        assert_eq!(parse_datetime("2022-01-02"), Some(midnight));
+        assert_eq!(
+            parse_datetime("2022-01-02T11:30:45.123Z")
+                .unwrap()
+                .timestamp_subsec_millis(),
+            123
+        );
+        assert_eq!(parse_datetime("yesterday"), None);
+        assert_eq!(parse_datetime("2022-13-02T11:30:45Z"), None);
+    }
+
+    #[test]
+    fn domain_names() {
+        let name = DomainName::new("Bücher.Example.").unwrap();
+        assert_eq!(name.as_str(), "xn--bcher-kva.example");
+        assert!(name.is_idn());
+        assert_eq!(name.to_unicode(), "bücher.example");
+        assert_eq!("xn--bcher-kva.example".parse::<DomainName>().unwrap(), name);
+
+        let name = DomainName::new("eppdev-1.com").unwrap();
+        assert!(!name.is_idn());
+        assert_eq!(name.to_unicode(), "eppdev-1.com");
+
+        let long_label = format!("{}.com", "a".repeat(64));
+        let long_name = format!("{}.com", vec!["a".repeat(63); 4].join("."));
+        for name in [
+            "",
+            ".",
+            "example..com",
+            "-example.com",
+            "example-.com",
+            "exa_mple.com",
+            "exa mple.com",
+            &long_label,
+            &long_name,
+        ]
+        .iter()
+        {
+            assert!(DomainName::new(name).is_err(), "{}", name);
+        }
+
+        assert_eq!(
+            DomainName::normalize("EPPDEV.com."),
+            StringValue::from("eppdev.com")
+        );
+        assert_eq!(
+            DomainName::normalize("exa_mple.com"),
+            StringValue::from("exa_mple.com")
+        );
+        assert!(matches!(
+            DomainName::normalize("eppdev.com").0,
+            Cow::Borrowed(_)
+        ));
+
+        assert!(DomainName::validate("xn--bcher-kva.example").is_ok());
+        for name in ["Bücher.example", "Example.com", "example.com."].iter() {
+            let err = DomainName::validate(name).unwrap_err();
+            assert!(matches!(err, Error::Invalid(_)), "{}", err);
+        }
+    }
+
+    #[test]
+    fn host_addrs() {
+        let addr = HostAddr::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(addr.ip_version(), "v6");
+
+        let parse = |xml: &str| quick_xml::de::from_str::<HostAddr>(xml);
+        assert_eq!(
+            parse(r#"<addr ip="v4">192.0.2.1</addr>"#).unwrap(),
+            HostAddr::from(Ipv4Addr::new(192, 0, 2, 1))
+        );
+        assert_eq!(parse("<addr>2001:db8::1</addr>").unwrap(), addr);
+        assert!(parse(r#"<addr ip="v6">192.0.2.1</addr>"#).is_err());
+        assert!(parse(r#"<addr ip="v4">192.0.2.256</addr>"#).is_err());
+    }
+
+    #[test]
+    fn statuses() {
+        assert_eq!("clientHold".parse(), Ok(DomainStatus::ClientHold));
+        assert_eq!(
+            "clientDeleteProhibitted".parse::<DomainStatus>(),
+            Err(UnknownStatus("clientDeleteProhibitted".into()))
+        );
+        assert!("clientRenewProhibited".parse::<HostStatus>().is_err());
+
+        let parse = |xml: &str| quick_xml::de::from_str::<ObjectStatus<DomainStatus>>(xml);
+        let status = parse(r#"<status s="serverHold"/>"#).unwrap();
+        assert_eq!(status.status, DomainStatus::ServerHold);
+        let status = parse(r#"<status s="serverRedemptionPeriod"/>"#).unwrap();
+        assert_eq!(
+            status.status,
+            DomainStatus::Other("serverRedemptionPeriod".into())
+        );
+        assert_eq!(status.status.as_str(), "serverRedemptionPeriod");
+
+        assert!(DomainStatus::ServerTransferProhibited.is_prohibition());
+        assert!(!DomainStatus::ServerHold.is_prohibition());
+        assert!(HostStatus::PendingDelete.is_pending());
+        assert!(!HostStatus::Linked.is_pending());
+
+        let statuses = &[ObjectStatus::new(DomainStatus::ClientHold).reason("Unpaid", Some("en"))];
+        let mut update = DomainUpdate::new("eppdev.com");
+        update.add(DomainAddRemove {
+            ns: None,
+            contacts: None,
+            statuses: Some(statuses.into()),
+        });
+        let xml =
+            <DomainUpdate as Transaction<NoExtension>>::serialize_request(&update, None, "id")
+                .unwrap();
+        assert!(xml.contains(r#"<domain:status s="clientHold" lang="en">Unpaid</domain:status>"#));
+    }
+
+    #[test]
+    fn transfers() {
+        assert!(TransferStatus::Pending.is_pending());
+        assert!(TransferStatus::ServerApproved.is_completed());
+        assert!(TransferStatus::ClientCancelled.is_rejected());
+        assert!(!TransferStatus::ClientRejected.is_completed());
+        assert_eq!(
+            "serverCancelled".parse(),
+            Ok(TransferStatus::ServerCancelled)
+        );
+
+        let action_at = Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap();
+        let transfer = TransferRecord {
+            status: TransferStatus::ClientApproved,
+            requested_by: "gaining".into(),
+            requested_at: action_at,
+            action_by: "losing".into(),
+            action_at,
+        };
+        assert_eq!(transfer.deadline(), None);
+        assert_eq!(transfer.remaining(action_at), None);
+        assert!(!transfer.is_overdue(action_at + chrono::Duration::days(1)));
+        assert_eq!(transfer.resolved_at(), Some(action_at));
+    }
+}
@@ -0,0 +1,203 @@
+//! Registry connection settings read from TOML configuration files
+//!
+//! The binaries shipped with this crate read the registry to connect to, and the credentials to
+//! log in with, from a TOML file. A [`RegistryConfig`] holds one `[registry]` table and can
+//! connect and log in a new [`EppClient`]; [`load()`] reads any configuration type from a file.
+//!
+//! ## Example
+//!
+//! ```toml
+//! [registry]
+//! name = "example"
+//! host = "epp.example.com"
+//! username = "registrar"
+//! password = "secret"
+//! extensions = ["urn:ietf:params:xml:ns:rgp-1.0"]
+//! certificate = "/etc/epp/client.der"
+//! key = "/etc/epp/client.key.der"
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::net::{lookup_host, TcpStream};
+use tokio_rustls::client::TlsStream;
+use tracing::info;
+
+use crate::client::EppClient;
+use crate::common::{Certificate, PrivateKey};
+use crate::error::Error;
+use crate::login::Login;
+
+/// The port registries listen on for EPP over TLS, as assigned in RFC 5734
+pub const EPP_PORT: u16 = 700;
+
+/// The connection and login settings for a registry
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct RegistryConfig {
+    /// The name used for the registry in logs
+    pub name: String,
+    /// The host name to connect to, also used for TLS server name indication
+    pub host: String,
+    /// The port to connect to
+    #[serde(default = "epp_port")]
+    pub port: u16,
+    /// The client ID to log in with
+    pub username: String,
+    /// The password to log in with
+    pub password: String,
+    /// The extension URIs to announce at login
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// The path of a DER-encoded certificate for TLS client authentication
+    pub certificate: Option<PathBuf>,
+    /// The path of the DER-encoded private key for `certificate`
+    pub key: Option<PathBuf>,
+}
+
+impl RegistryConfig {
+    /// Returns the login command for the configured credentials and extensions
+    pub fn login(&self) -> Login<'_> {
+        let extensions = self
+            .extensions
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let ext_uris = if extensions.is_empty() {
+            None
+        } else {
+            Some(extensions.as_slice())
+        };
+
+        Login::new(&self.username, &self.password, ext_uris)
+    }
+
+    /// Connects to the registry over TLS and logs in, using `client_tr_id` for the login
+    pub async fn connect(
+        &self,
+        client_tr_id: &str,
+    ) -> Result<EppClient<TlsStream<TcpStream>>, Error> {
+        let addr = lookup_host((self.host.as_str(), self.port))
+            .await?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{}: no address found for {}", self.name, self.host),
+                )
+            })?;
+
+        let mut client =
+            EppClient::connect(self.name.clone(), addr, &self.host, self.identity()?).await?;
+        client.transact(&self.login(), client_tr_id).await?;
+        info!("Logged in to {} as {}", self.name, self.username);
+        Ok(client)
+    }
+
+    /// Reads the configured client certificate and key, if any
+    #[allow(clippy::result_large_err)]
+    fn identity(&self) -> Result<Option<(Vec<Certificate>, PrivateKey)>, Error> {
+        match (&self.certificate, &self.key) {
+            (Some(certificate), Some(key)) => Ok(Some((
+                vec![Certificate(read(certificate)?)],
+                PrivateKey(read(key)?),
+            ))),
+            (None, None) => Ok(None),
+            _ => Err(Error::Other(
+                format!("{}: certificate and key must be given together", self.name).into(),
+            )),
+        }
+    }
+}
+
+/// Reads a configuration of type `T` from the TOML file at `path`
+#[allow(clippy::result_large_err)]
+pub fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, Error> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| context(path, e))?;
+    toml::from_str(&text).map_err(|e| Error::Other(format!("{}: {}", path.display(), e).into()))
+}
+
+/// Reads the file at `path`, naming it in errors
+#[allow(clippy::result_large_err)]
+fn read(path: &Path) -> Result<Vec<u8>, Error> {
+    fs::read(path).map_err(|e| context(path, e))
+}
+
+fn context(path: &Path, e: io::Error) -> Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e)).into()
+}
+
+fn epp_port() -> u16 {
+    EPP_PORT
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde::Deserialize;
+
+    use super::{RegistryConfig, EPP_PORT};
+
+    #[derive(Deserialize)]
+    struct Config {
+        registry: RegistryConfig,
+    }
+
+    #[test]
+    fn registry() {
+        let config: Config = toml::from_str(
+            r#"
+            [registry]
+            name = "example"
+            host = "epp.example.com"
+            username = "registrar"
+            password = "secret"
+            extensions = ["urn:ietf:params:xml:ns:rgp-1.0"]
+            certificate = "client.der"
+            key = "client.key.der"
+            "#,
+        )
+        .unwrap();
+
+        let registry = config.registry;
+        assert_eq!(registry.port, EPP_PORT);
+        assert_eq!(registry.certificate, Some(PathBuf::from("client.der")));
+
+        let login = registry.login();
+        assert_eq!(login.username, "registrar".into());
+        let ext = login.services.svc_ext.unwrap().ext_uris.unwrap();
+        assert_eq!(ext, vec!["urn:ietf:params:xml:ns:rgp-1.0".into()]);
+    }
+
+    #[test]
+    fn identity() {
+        let config: Config = toml::from_str(
+            r#"
+            [registry]
+            name = "example"
+            host = "epp.example.com"
+            port = 7000
+            username = "registrar"
+            password = "secret"
+            certificate = "client.der"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.registry.port, 7000);
+        assert!(config
+            .registry
+            .login()
+            .services
+            .svc_ext
+            .unwrap()
+            .ext_uris
+            .is_none());
+        assert!(config.registry.identity().is_err());
+    }
+}
@@ -1,18 +1,21 @@
 //! Manages registry connections and reading/writing to them
 
 use std::convert::TryInto;
-use std::{io, str, u32};
+use std::sync::Arc;
+use std::{io, str};
 
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tracing::{debug, info};
 
 use crate::error::Error;
+use crate::metrics::Recorder;
 
 /// EPP Connection struct with some metadata for the connection
 pub(crate) struct EppConnection<IO> {
-    registry: String,
+    pub registry: String,
     stream: IO,
     pub greeting: String,
+    pub recorder: Option<Arc<dyn Recorder>>,
 }
 
 impl<IO: AsyncRead + AsyncWrite + Unpin> EppConnection<IO> {
@@ -21,6 +24,7 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppConnection<IO> {
             registry,
             stream,
             greeting: String::new(),
+            recorder: None,
         };
 
         this.greeting = this.get_epp_response().await?;
@@ -42,6 +46,9 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppConnection<IO> {
 
         let wrote = self.stream.write(&buf).await?;
         debug!("{}: Wrote {} bytes", self.registry, wrote);
+        if let Some(recorder) = &self.recorder {
+            recorder.bytes_sent(&self.registry, wrote);
+        }
         Ok(())
     }
 
@@ -76,9 +83,24 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppConnection<IO> {
             }
         }
 
+        if let Some(recorder) = &self.recorder {
+            recorder.bytes_received(&self.registry, buf_size);
+        }
+
         Ok(String::from_utf8(buf)?)
     }
 
+    /// Attaches a metrics recorder to the connection
+    ///
+    /// The session and the greeting that opened it are reported to the recorder immediately.
+    pub(crate) fn set_recorder(&mut self, recorder: Arc<dyn Recorder>) {
+        recorder.session_opened(&self.registry);
+        recorder.bytes_received(&self.registry, self.greeting.len() + 4);
+        if let Some(previous) = self.recorder.replace(recorder) {
+            previous.session_closed(&self.registry);
+        }
+    }
+
     /// Sends an EPP XML request to the registry and return the response
     /// receieved to the request
     pub(crate) async fn transact(&mut self, content: &str) -> Result<String, Error> {
@@ -99,3 +121,11 @@ impl<IO: AsyncRead + AsyncWrite + Unpin> EppConnection<IO> {
         Ok(())
     }
 }
+
+impl<IO> Drop for EppConnection<IO> {
+    fn drop(&mut self) {
+        if let Some(recorder) = &self.recorder {
+            recorder.session_closed(&self.registry);
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use std::borrow::Cow;
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::common::StringValue;
+use crate::Error;
 
 pub mod check;
 pub use check::ContactCheck;
@@ -17,11 +20,19 @@ pub use delete::ContactDelete;
 pub mod info;
 pub use info::ContactInfo;
 
+pub mod transfer;
+pub use transfer::ContactTransfer;
+
 pub mod update;
 pub use update::ContactUpdate;
 
 pub const XMLNS: &str = "urn:ietf:params:xml:ns:contact-1.0";
 
+/// The namespace restored on deserialized contact commands
+pub(crate) fn xmlns() -> &'static str {
+    XMLNS
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Country(celes::Country);
 
@@ -46,7 +57,7 @@ impl std::ops::Deref for Country {
 pub struct ContactAuthInfo<'a> {
     /// The &lt;pw&gt; tag under &lt;authInfo&gt;
     #[serde(rename = "contact:pw", alias = "pw")]
-    pub password: StringValue<'a>,
+    pub password: Password<'a>,
 }
 
 impl<'a> ContactAuthInfo<'a> {
@@ -56,6 +67,38 @@ impl<'a> ContactAuthInfo<'a> {
             password: password.into(),
         }
     }
+
+    /// Sets the ROID of the object the password belongs to
+    pub fn roid(mut self, roid: &'a str) -> Self {
+        self.password.roid = Some(roid.into());
+        self
+    }
+}
+
+/// The &lt;pw&gt; tag under &lt;authInfo&gt;
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Password<'a> {
+    /// The 'roid' attr, identifying the object the password belongs to when it isn't the object
+    /// the command is about
+    pub roid: Option<Cow<'a, str>>,
+    /// The password itself
+    #[serde(rename = "$value", default)]
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> From<&'a str> for Password<'a> {
+    fn from(value: &'a str) -> Self {
+        Self {
+            roid: None,
+            value: value.into(),
+        }
+    }
+}
+
+impl Display for Password<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
 }
 
 /// The data for &lt;voice&gt; and &lt;fax&gt; types on domain transactions
@@ -125,12 +168,62 @@ impl<'a> Address<'a> {
     }
 }
 
+/// The form of a contact's postal info, given by the 'type' attr on &lt;postalInfo&gt;
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PostalInfoType {
+    /// `int`, internationalized info restricted to 7-bit ASCII
+    Int,
+    /// `loc`, localized info that may use any character
+    Loc,
+}
+
+impl PostalInfoType {
+    /// Returns the type as used in EPP XML
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Int => "int",
+            Self::Loc => "loc",
+        }
+    }
+}
+
+impl FromStr for PostalInfoType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "int" => Ok(Self::Int),
+            "loc" => Ok(Self::Loc),
+            _ => Err(Error::Invalid(format!("unknown postal info type: {}", s))),
+        }
+    }
+}
+
+impl Display for PostalInfoType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for PostalInfoType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PostalInfoType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let info_type = String::deserialize(deserializer)?;
+        info_type.parse().map_err(D::Error::custom)
+    }
+}
+
 /// The &lt;postalInfo&gt; type on contact transactions
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PostalInfo<'a> {
     /// The 'type' attr on &lt;postalInfo&gt;
     #[serde(rename = "type")]
-    pub info_type: String,
+    pub info_type: PostalInfoType,
     /// The &lt;name&gt; tag under &lt;postalInfo&gt;
     #[serde(rename = "contact:name", alias = "name")]
     pub name: StringValue<'a>,
@@ -145,16 +238,171 @@ pub struct PostalInfo<'a> {
 impl<'a> PostalInfo<'a> {
     /// Creates a new PostalInfo instance
     pub fn new(
-        info_type: &str,
+        info_type: PostalInfoType,
         name: &'a str,
         organization: &'a str,
         address: Address<'a>,
     ) -> Self {
         Self {
-            info_type: info_type.to_string(),
+            info_type,
             name: name.into(),
             organization: organization.into(),
             address,
         }
     }
+
+    /// Checks that internationalized info only uses 7-bit ASCII characters
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.info_type != PostalInfoType::Int {
+            return Ok(());
+        }
+
+        let address = &self.address;
+        let fields = [
+            ("name", &self.name),
+            ("org", &self.organization),
+            ("city", &address.city),
+            ("sp", &address.province),
+            ("pc", &address.postal_code),
+        ];
+        let streets = address.street.iter().map(|street| ("street", street));
+        match fields
+            .iter()
+            .copied()
+            .chain(streets)
+            .find(|(_, v)| !v.as_ref().is_ascii())
+        {
+            Some((field, value)) => Err(Error::Invalid(format!(
+                "int postal info {} must be ASCII: {}",
+                field, value
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks that a contact has at most one postal info of each type, and that each is valid
+#[allow(clippy::result_large_err)]
+pub(crate) fn validate_postal_info(postal_info: &[PostalInfo<'_>]) -> Result<(), Error> {
+    for (i, info) in postal_info.iter().enumerate() {
+        if postal_info[..i]
+            .iter()
+            .any(|p| p.info_type == info.info_type)
+        {
+            return Err(Error::Invalid(format!(
+                "more than one {} postal info",
+                info.info_type
+            )));
+        }
+        info.validate()?;
+    }
+    Ok(())
+}
+
+/// The 'type' attr of a &lt;name&gt;, &lt;org&gt; or &lt;addr&gt; tag under &lt;disclose&gt;,
+/// selecting the internationalized or localized postal info
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DiscloseType {
+    #[serde(rename = "type")]
+    pub info_type: PostalInfoType,
+}
+
+/// An empty &lt;voice&gt;, &lt;fax&gt; or &lt;email&gt; tag under &lt;disclose&gt;
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiscloseField {}
+
+/// The &lt;disclose&gt; type on contact transactions
+///
+/// Lists the contact elements that the server is asked to disclose to third parties when `flag` is
+/// true, or to keep private when it is false, overriding the server's data collection policy.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Disclose {
+    /// The 'flag' attr on &lt;disclose&gt;
+    #[serde(with = "crate::common::boolean")]
+    pub flag: bool,
+    /// The &lt;name&gt; tags under &lt;disclose&gt;, one per postal info type
+    #[serde(rename = "contact:name", alias = "name", default)]
+    pub name: Vec<DiscloseType>,
+    /// The &lt;org&gt; tags under &lt;disclose&gt;, one per postal info type
+    #[serde(rename = "contact:org", alias = "org", default)]
+    pub organization: Vec<DiscloseType>,
+    /// The &lt;addr&gt; tags under &lt;disclose&gt;, one per postal info type
+    #[serde(rename = "contact:addr", alias = "addr", default)]
+    pub address: Vec<DiscloseType>,
+    /// The &lt;voice&gt; tag under &lt;disclose&gt;
+    #[serde(rename = "contact:voice", alias = "voice")]
+    pub voice: Option<DiscloseField>,
+    /// The &lt;fax&gt; tag under &lt;disclose&gt;
+    #[serde(rename = "contact:fax", alias = "fax")]
+    pub fax: Option<DiscloseField>,
+    /// The &lt;email&gt; tag under &lt;disclose&gt;
+    #[serde(rename = "contact:email", alias = "email")]
+    pub email: Option<DiscloseField>,
+}
+
+impl Disclose {
+    /// Creates a Disclose instance listing no elements, with the given flag
+    pub fn new(flag: bool) -> Self {
+        Self {
+            flag,
+            name: Vec::new(),
+            organization: Vec::new(),
+            address: Vec::new(),
+            voice: None,
+            fax: None,
+            email: None,
+        }
+    }
+
+    /// Lists the name of the given postal info type
+    pub fn name(mut self, info_type: PostalInfoType) -> Self {
+        self.name.push(DiscloseType { info_type });
+        self
+    }
+
+    /// Lists the organization of the given postal info type
+    pub fn organization(mut self, info_type: PostalInfoType) -> Self {
+        self.organization.push(DiscloseType { info_type });
+        self
+    }
+
+    /// Lists the address of the given postal info type
+    pub fn address(mut self, info_type: PostalInfoType) -> Self {
+        self.address.push(DiscloseType { info_type });
+        self
+    }
+
+    /// Lists the voice telephone number
+    pub fn voice(mut self) -> Self {
+        self.voice = Some(DiscloseField {});
+        self
+    }
+
+    /// Lists the fax number
+    pub fn fax(mut self) -> Self {
+        self.fax = Some(DiscloseField {});
+        self
+    }
+
+    /// Lists the email address
+    pub fn email(mut self) -> Self {
+        self.email = Some(DiscloseField {});
+        self
+    }
+
+    /// Checks that at least one element is listed, as the schema requires
+    #[allow(clippy::result_large_err)]
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.is_empty()
+            && self.organization.is_empty()
+            && self.address.is_empty()
+            && self.voice.is_none()
+            && self.fax.is_none()
+            && self.email.is_none()
+        {
+            return Err(Error::Invalid("disclose lists no elements".into()));
+        }
+        Ok(())
+    }
 }
@@ -1,7 +1,9 @@
 use std::fmt::Debug;
 
 /// Types for EPP contact check request
-use super::XMLNS;
+use std::borrow::Cow;
+
+use super::{xmlns, XMLNS};
 use crate::common::{NoExtension, StringValue};
 use crate::request::{Command, Transaction};
 use serde::{Deserialize, Serialize};
@@ -11,22 +13,22 @@ impl<'a> Transaction<NoExtension> for ContactCheck<'a> {}
 // Request
 
 /// Type that represents the &lt;check&gt; command for contact transactions
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactList<'a> {
     /// The XML namespace for the contact &lt;check&gt;
-    #[serde(rename = "xmlns:contact")]
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The list of contact ids to check for availability
-    #[serde(rename = "contact:id")]
+    #[serde(rename = "contact:id", alias = "id")]
     pub contact_ids: Vec<StringValue<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// The &lt;command&gt; type for contact check command
 pub struct ContactCheck<'a> {
     /// The &lt;check&gt; tag for the contact check command
-    #[serde(rename = "contact:check")]
-    list: ContactList<'a>,
+    #[serde(rename = "contact:check", alias = "check")]
+    pub list: ContactList<'a>,
 }
 
 impl<'a> ContactCheck<'a> {
@@ -43,44 +45,49 @@ impl<'a> ContactCheck<'a> {
 impl<'a> Command for ContactCheck<'a> {
     type Response = ContactCheckResponse;
     const COMMAND: &'static str = "check";
+    const OBJECT: Option<&'static str> = Some("contact");
 }
 
 // Response
 
 /// Type that represents the &lt;id&gt; tag for contact check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactAvailable {
     /// The text of the &lt;id&gt; tag
     #[serde(rename = "$value")]
-    pub id: StringValue<'static>,
+    pub id: Cow<'static, str>,
     /// The avail attr on the &lt;id&gt; tag
     #[serde(rename = "avail")]
     pub available: u16,
 }
 
 /// Type that represents the &lt;cd&gt; tag for contact check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactCheckResponseDataItem {
     /// Data under the &lt;id&gt; tag
-    #[serde(rename = "id")]
+    #[serde(rename = "contact:id", alias = "id")]
     pub contact: ContactAvailable,
     /// The reason for (un)availability
+    #[serde(rename = "contact:reason", alias = "reason")]
     pub reason: Option<StringValue<'static>>,
 }
 
 /// Type that represents the &lt;chkData&gt; tag for contact check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactCheckResponseData {
+    /// XML namespace for contact response data
+    #[serde(rename = "xmlns:contact")]
+    pub xmlns: String,
     /// Data under the &lt;cd&gt; tag
-    #[serde(rename = "cd")]
+    #[serde(rename = "contact:cd", alias = "cd")]
     pub contact_list: Vec<ContactCheckResponseDataItem>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for contact check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactCheckResponse {
     /// Data under the &lt;chkData&gt; tag
-    #[serde(rename = "chkData")]
+    #[serde(rename = "contact:chkData", alias = "chkData")]
     pub check_data: ContactCheckResponseData,
 }
 
@@ -114,12 +121,12 @@ mod tests {
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(
             results.check_data.contact_list[0].contact.id,
-            "eppdev-contact-1".into()
+            "eppdev-contact-1"
         );
         assert_eq!(results.check_data.contact_list[0].contact.available, 0);
         assert_eq!(
             results.check_data.contact_list[1].contact.id,
-            "eppdev-contact-2".into()
+            "eppdev-contact-2"
         );
         assert_eq!(results.check_data.contact_list[1].contact.available, 1);
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
@@ -1,8 +1,10 @@
 //! Types for EPP contact create request
 
-use super::{ContactAuthInfo, Phone, PostalInfo, XMLNS};
+use super::{validate_postal_info, xmlns, ContactAuthInfo, Disclose, Phone, PostalInfo, XMLNS};
 use crate::common::{NoExtension, StringValue};
 use crate::request::{Command, Transaction};
+use crate::Error;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for ContactCreate<'a> {}
@@ -10,41 +12,53 @@ impl<'a> Transaction<NoExtension> for ContactCreate<'a> {}
 impl<'a> Command for ContactCreate<'a> {
     type Response = ContactCreateResponse;
     const COMMAND: &'static str = "create";
+    const OBJECT: Option<&'static str> = Some("contact");
+
+    fn validate(&self) -> Result<(), Error> {
+        validate_postal_info(&self.contact.postal_info)?;
+        match &self.contact.disclose {
+            Some(disclose) => disclose.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 // Request
 
 /// Type for elements under the contact &lt;create&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Contact<'a> {
     /// XML namespace for contact commands
-    #[serde(rename = "xmlns:contact")]
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// Contact &lt;id&gt; tag
-    #[serde(rename = "contact:id")]
-    id: StringValue<'a>,
-    /// Contact &lt;postalInfo&gt; tag
-    #[serde(rename = "contact:postalInfo")]
-    postal_info: PostalInfo<'a>,
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'a>,
+    /// Contact &lt;postalInfo&gt; tags, at most one of each type
+    #[serde(rename = "contact:postalInfo", alias = "postalInfo")]
+    pub postal_info: Vec<PostalInfo<'a>>,
     /// Contact &lt;voice&gt; tag
-    #[serde(rename = "contact:voice")]
-    voice: Phone<'a>,
+    #[serde(rename = "contact:voice", alias = "voice")]
+    pub voice: Phone<'a>,
     /// Contact &lt;fax&gt; tag,
-    #[serde(rename = "contact:fax")]
-    fax: Option<Phone<'a>>,
+    #[serde(rename = "contact:fax", alias = "fax")]
+    pub fax: Option<Phone<'a>>,
     /// Contact &lt;email&gt; tag
-    #[serde(rename = "contact:email")]
-    email: StringValue<'a>,
+    #[serde(rename = "contact:email", alias = "email")]
+    pub email: StringValue<'a>,
     /// Contact &lt;authInfo&gt; tag
-    #[serde(rename = "contact:authInfo")]
-    auth_info: ContactAuthInfo<'a>,
+    #[serde(rename = "contact:authInfo", alias = "authInfo")]
+    pub auth_info: ContactAuthInfo<'a>,
+    /// Contact &lt;disclose&gt; tag
+    #[serde(rename = "contact:disclose", alias = "disclose")]
+    pub disclose: Option<Disclose>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;create&gt; command for contacts
 pub struct ContactCreate<'a> {
     /// Data for &lt;create&gt; command for contact
-    #[serde(rename = "contact:create")]
+    #[serde(rename = "contact:create", alias = "create")]
     pub contact: Contact<'a>,
 }
 
@@ -60,38 +74,57 @@ impl<'a> ContactCreate<'a> {
             contact: Contact {
                 xmlns: XMLNS,
                 id: id.into(),
-                postal_info,
+                postal_info: vec![postal_info],
                 voice,
                 fax: None,
                 email: email.into(),
                 auth_info: ContactAuthInfo::new(auth_password),
+                disclose: None,
             },
         }
     }
 
+    /// Adds a &lt;postalInfo&gt; of the other type to the request
+    pub fn add_postal_info(&mut self, postal_info: PostalInfo<'a>) {
+        self.contact.postal_info.push(postal_info);
+    }
+
     /// Sets the &lt;fax&gt; data for the request
     pub fn set_fax(&mut self, fax: Phone<'a>) {
         self.contact.fax = Some(fax);
     }
+
+    /// Sets the &lt;disclose&gt; data for the request
+    pub fn set_disclose(&mut self, disclose: Disclose) {
+        self.contact.disclose = Some(disclose);
+    }
 }
 
 // Response
 
 /// Type that represents the &lt;creData&gt; tag for contact create response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactCreateData {
+    /// XML namespace for contact response data
+    #[serde(rename = "xmlns:contact")]
+    pub xmlns: String,
     /// The contact id
+    #[serde(rename = "contact:id", alias = "id")]
     pub id: StringValue<'static>,
-    #[serde(rename = "crDate")]
+    #[serde(
+        rename = "contact:crDate",
+        alias = "crDate",
+        with = "crate::common::datetime"
+    )]
     /// The contact creation date
-    pub created_at: StringValue<'static>,
+    pub created_at: DateTime<Utc>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for contact create response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactCreateResponse {
     /// Data under the &lt;creData&gt; tag
-    #[serde(rename = "creData")]
+    #[serde(rename = "contact:creData", alias = "creData")]
     pub create_data: ContactCreateData,
 }
 
@@ -99,9 +132,11 @@ pub struct ContactCreateResponse {
 mod tests {
     use super::{ContactCreate, Phone, PostalInfo};
     use crate::common::NoExtension;
-    use crate::contact::Address;
+    use crate::contact::{Address, Disclose, PostalInfoType};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
@@ -109,7 +144,16 @@ mod tests {
 
         let street = &["58", "Orchid Road"];
         let address = Address::new(street, "Paris", "Paris", "392374", "FR".parse().unwrap());
-        let postal_info = PostalInfo::new("int", "John Doe", "Acme Widgets", address);
+        let postal_info = PostalInfo::new(PostalInfoType::Int, "John Doe", "Acme Widgets", address);
+        let street = &["58 rue des Orchidées"];
+        let address = Address::new(
+            street,
+            "Paris",
+            "Île-de-France",
+            "392374",
+            "FR".parse().unwrap(),
+        );
+        let localized = PostalInfo::new(PostalInfoType::Loc, "Jérôme Doe", "Acme Widgets", address);
         let mut voice = Phone::new("+33.47237942");
         voice.set_extension("123");
         let mut fax = Phone::new("+33.86698799");
@@ -122,7 +166,9 @@ mod tests {
             voice,
             "eppdev-387323",
         );
+        object.add_postal_info(localized);
         object.set_fax(fax);
+        object.set_disclose(Disclose::new(false).voice().email());
 
         let serialized =
             <ContactCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -131,6 +177,46 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn invalid_postal_info() {
+        let address = || Address::new(&["58"], "Zürich", "ZH", "8001", "CH".parse().unwrap());
+        let postal_info = PostalInfo::new(PostalInfoType::Int, "John Doe", "", address());
+        let mut object = ContactCreate::new(
+            "eppdev-contact-3",
+            "contact@eppdev.net",
+            postal_info,
+            Phone::new("+41.447654321"),
+            "eppdev-387323",
+        );
+
+        let result =
+            <ContactCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(matches!(result, Err(Error::Invalid(e)) if e.contains("city")));
+
+        object.contact.postal_info[0].address.city = "Zurich".into();
+        object.add_postal_info(PostalInfo::new(
+            PostalInfoType::Int,
+            "J. Doe",
+            "",
+            address(),
+        ));
+        object.contact.postal_info[1].address.city = "Zurich".into();
+        let result =
+            <ContactCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(matches!(result, Err(Error::Invalid(e)) if e.contains("more than one int")));
+
+        object.contact.postal_info[1].info_type = PostalInfoType::Loc;
+        object.contact.postal_info[1].address.city = "Zürich".into();
+        let result =
+            <ContactCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(result.is_ok());
+
+        object.set_disclose(Disclose::new(false));
+        let result =
+            <ContactCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(matches!(result, Err(Error::Invalid(e)) if e.contains("disclose")));
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/contact/create.xml").unwrap();
@@ -145,7 +231,7 @@ mod tests {
         assert_eq!(results.create_data.id, "eppdev-contact-4".into());
         assert_eq!(
             results.create_data.created_at,
-            "2021-07-25T16:05:32.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 25, 16, 5, 32).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
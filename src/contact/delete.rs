@@ -1,34 +1,35 @@
 //! Types for EPP contact delete request
 
-use super::XMLNS;
+use super::{xmlns, XMLNS};
 use crate::common::{NoExtension, StringValue};
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for ContactDelete<'a> {}
 
 impl<'a> Command for ContactDelete<'a> {
     type Response = ();
     const COMMAND: &'static str = "delete";
+    const OBJECT: Option<&'static str> = Some("contact");
 }
 
 /// Type containing the data for the &lt;delete&gt; tag for contacts
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactDeleteRequestData<'a> {
     /// XML namespace for the &lt;delete&gt; command for contacts
-    #[serde(rename = "xmlns:contact")]
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The id of the contact to be deleted
-    #[serde(rename = "contact:id")]
-    id: StringValue<'a>,
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// The &lt;delete&gt; type for the contact delete EPP command
 pub struct ContactDelete<'a> {
-    #[serde(rename = "contact:delete")]
+    #[serde(rename = "contact:delete", alias = "delete")]
     /// The data for the &lt;delete&gt; tag for a contact delete command
-    contact: ContactDeleteRequestData<'a>,
+    pub contact: ContactDeleteRequestData<'a>,
 }
 
 impl<'a> ContactDelete<'a> {
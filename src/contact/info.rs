@@ -1,8 +1,9 @@
 //! Types for EPP contact info request
 
-use super::{ContactAuthInfo, Phone, PostalInfo, XMLNS};
-use crate::common::{NoExtension, ObjectStatus, StringValue};
+use super::{xmlns, ContactAuthInfo, Disclose, Phone, PostalInfo, PostalInfoType, XMLNS};
+use crate::common::{ContactStatus, NoExtension, ObjectStatus, StringValue};
 use crate::request::{Command, Transaction};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for ContactInfo<'a> {}
@@ -10,108 +11,158 @@ impl<'a> Transaction<NoExtension> for ContactInfo<'a> {}
 impl<'a> Command for ContactInfo<'a> {
     type Response = ContactInfoResponse;
     const COMMAND: &'static str = "info";
+    const OBJECT: Option<&'static str> = Some("contact");
 }
 
 // Request
 
 /// Type for elements under the contact &lt;info&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactInfoRequestData<'a> {
     /// XML namespace for contact commands
-    #[serde(rename = "xmlns:contact")]
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The contact id for the info command
-    #[serde(rename = "contact:id")]
-    id: StringValue<'a>,
-    /// The &lt;authInfo&gt; data
-    #[serde(rename = "contact:authInfo")]
-    auth_info: ContactAuthInfo<'a>,
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'a>,
+    /// The &lt;authInfo&gt; data, needed for contacts sponsored by other registrars
+    #[serde(rename = "contact:authInfo", alias = "authInfo")]
+    pub auth_info: Option<ContactAuthInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;info&gt; command for contacts
 pub struct ContactInfo<'a> {
     /// Data for &lt;info&gt; command for contact
-    #[serde(rename = "contact:info")]
-    info: ContactInfoRequestData<'a>,
+    #[serde(rename = "contact:info", alias = "info")]
+    pub info: ContactInfoRequestData<'a>,
 }
 
 impl<'a> ContactInfo<'a> {
-    pub fn new(id: &'a str, auth_password: &'a str) -> ContactInfo<'a> {
+    pub fn new(id: &'a str, auth_password: Option<&'a str>) -> ContactInfo<'a> {
         Self {
             info: ContactInfoRequestData {
                 xmlns: XMLNS,
                 id: id.into(),
-                auth_info: ContactAuthInfo::new(auth_password),
+                auth_info: auth_password.map(ContactAuthInfo::new),
             },
         }
     }
+
+    /// Sets the &lt;authInfo&gt; data, e.g. a password with the ROID of the object it belongs to
+    pub fn set_auth_info(&mut self, auth_info: ContactAuthInfo<'a>) {
+        self.info.auth_info = Some(auth_info);
+    }
 }
 
 // Response
 
 /// Type that represents the &lt;infData&gt; tag for contact check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactInfoData<'a> {
+    /// XML namespace for contact response data
+    #[serde(rename = "xmlns:contact")]
+    pub xmlns: String,
     /// The contact id
+    #[serde(rename = "contact:id", alias = "id")]
     pub id: StringValue<'a>,
     /// The contact ROID
+    #[serde(rename = "contact:roid", alias = "roid")]
     pub roid: StringValue<'a>,
     /// The list of contact statuses
-    #[serde(rename = "status")]
-    pub statuses: Vec<ObjectStatus<'a>>,
-    /// The postal info for the contact
-    #[serde(rename = "postalInfo")]
-    pub postal_info: PostalInfo<'a>,
+    #[serde(rename = "contact:status", alias = "status")]
+    pub statuses: Vec<ObjectStatus<'a, ContactStatus>>,
+    /// The postal info for the contact, at most one of each type
+    #[serde(rename = "contact:postalInfo", alias = "postalInfo")]
+    pub postal_info: Vec<PostalInfo<'a>>,
     /// The voice data for the contact
+    #[serde(rename = "contact:voice", alias = "voice")]
     pub voice: Phone<'a>,
     /// The fax data for the contact
+    #[serde(rename = "contact:fax", alias = "fax")]
     pub fax: Option<Phone<'a>>,
     /// The email for the contact
+    #[serde(rename = "contact:email", alias = "email")]
     pub email: StringValue<'a>,
     /// The epp user to whom the contact belongs
-    #[serde(rename = "clID")]
+    #[serde(rename = "contact:clID", alias = "clID")]
     pub client_id: StringValue<'a>,
     /// The epp user who created the contact
-    #[serde(rename = "crID")]
+    #[serde(rename = "contact:crID", alias = "crID")]
     pub creator_id: StringValue<'a>,
     /// The creation date
-    #[serde(rename = "crDate")]
-    pub created_at: StringValue<'a>,
+    #[serde(
+        rename = "contact:crDate",
+        alias = "crDate",
+        with = "crate::common::datetime"
+    )]
+    pub created_at: DateTime<Utc>,
     /// The epp user who last updated the contact
-    #[serde(rename = "upID")]
+    #[serde(rename = "contact:upID", alias = "upID")]
     pub updater_id: Option<StringValue<'a>>,
     /// The last update date
-    #[serde(rename = "upDate")]
-    pub updated_at: Option<StringValue<'a>>,
+    #[serde(
+        rename = "contact:upDate",
+        alias = "upDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub updated_at: Option<DateTime<Utc>>,
     /// The contact transfer date
-    #[serde(rename = "trDate")]
-    pub transferred_at: Option<StringValue<'a>>,
+    #[serde(
+        rename = "contact:trDate",
+        alias = "trDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub transferred_at: Option<DateTime<Utc>>,
     /// The contact auth info
-    #[serde(rename = "authInfo")]
+    #[serde(rename = "contact:authInfo", alias = "authInfo")]
     pub auth_info: Option<ContactAuthInfo<'a>>,
+    /// The contact's disclosure preferences
+    #[serde(rename = "contact:disclose", alias = "disclose")]
+    pub disclose: Option<Disclose>,
+}
+
+impl<'a> ContactInfoData<'a> {
+    /// Returns the contact's postal info of the given type, if the server returned it
+    pub fn postal_info(&self, info_type: PostalInfoType) -> Option<&PostalInfo<'a>> {
+        self.postal_info.iter().find(|p| p.info_type == info_type)
+    }
+
+    /// Returns whether a client or server prohibition blocks some transform command on the contact
+    pub fn is_locked(&self) -> bool {
+        self.statuses.iter().any(|s| s.status.is_prohibition())
+    }
+
+    /// Returns whether a transform command on the contact awaits completion
+    pub fn is_pending(&self) -> bool {
+        self.statuses.iter().any(|s| s.status.is_pending())
+    }
 }
 
 /// Type that represents the &lt;resData&gt; tag for contact info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactInfoResponse {
     /// Data under the &lt;infData&gt; tag
-    #[serde(rename = "infData")]
+    #[serde(rename = "contact:infData", alias = "infData")]
     pub info_data: ContactInfoData<'static>,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ContactInfo;
-    use crate::common::NoExtension;
+    use super::{ContactAuthInfo, ContactInfo};
+    use crate::common::{ContactStatus, NoExtension};
+    use crate::contact::PostalInfoType;
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
         let xml = get_xml("request/contact/info.xml").unwrap();
 
-        let object = ContactInfo::new("eppdev-contact-3", "eppdev-387323");
+        let object = ContactInfo::new("eppdev-contact-3", Some("eppdev-387323"));
 
         let serialized =
             <ContactInfo as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -120,6 +171,23 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn command_roid() {
+        let xml = get_xml("request/contact/info_roid.xml").unwrap();
+
+        let mut object = ContactInfo::new("eppdev-contact-3", None);
+        let serialized =
+            <ContactInfo as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+        assert!(!serialized.contains("authInfo"));
+
+        object.set_auth_info(ContactAuthInfo::new("2fooBAR").roid("SH8013-REP"));
+        let serialized =
+            <ContactInfo as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+        assert_eq!(xml, serialized);
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/contact/info.xml").unwrap();
@@ -131,33 +199,27 @@ mod tests {
         let voice_ext = result.info_data.voice.extension.as_ref().unwrap();
         let fax_ext = fax.extension.as_ref().unwrap();
         let auth_info = result.info_data.auth_info.as_ref().unwrap();
+        let disclose = result.info_data.disclose.as_ref().unwrap();
+        let postal_info = result.info_data.postal_info(PostalInfoType::Loc).unwrap();
+        let int_postal_info = result.info_data.postal_info(PostalInfoType::Int).unwrap();
 
         assert_eq!(object.result.code, 1000);
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(result.info_data.id, "eppdev-contact-3".into());
         assert_eq!(result.info_data.roid, "UNDEF-ROID".into());
-        assert_eq!(result.info_data.statuses[0].status, "ok".to_string());
-        assert_eq!(result.info_data.postal_info.info_type, "loc".to_string());
-        assert_eq!(result.info_data.postal_info.name, "John Doe".into());
-        assert_eq!(
-            result.info_data.postal_info.organization,
-            "Acme Widgets".into()
-        );
-        assert_eq!(result.info_data.postal_info.address.street[0], "58".into());
-        assert_eq!(
-            result.info_data.postal_info.address.street[1],
-            "Orchid Road".into()
-        );
-        assert_eq!(result.info_data.postal_info.address.city, "Paris".into());
-        assert_eq!(
-            result.info_data.postal_info.address.province,
-            "Paris".into()
-        );
-        assert_eq!(
-            result.info_data.postal_info.address.postal_code,
-            "392374".into()
-        );
-        assert_eq!(result.info_data.postal_info.address.country.alpha2, "FR");
+        assert_eq!(result.info_data.statuses[0].status, ContactStatus::Ok);
+        assert!(!result.info_data.is_locked());
+        assert!(!result.info_data.is_pending());
+        assert_eq!(postal_info.name, "John Doe".into());
+        assert_eq!(postal_info.organization, "Acme Widgets".into());
+        assert_eq!(postal_info.address.street[0], "58".into());
+        assert_eq!(postal_info.address.street[1], "Orchid Road".into());
+        assert_eq!(postal_info.address.city, "Paris".into());
+        assert_eq!(postal_info.address.province, "Paris".into());
+        assert_eq!(postal_info.address.postal_code, "392374".into());
+        assert_eq!(postal_info.address.country.alpha2, "FR");
+        assert_eq!(result.info_data.postal_info.len(), 2);
+        assert_eq!(int_postal_info.address.street[0], "58 Orchid Road".into());
         assert_eq!(result.info_data.voice.number, "+33.47237942".to_string());
         assert_eq!(*voice_ext, "123".to_string());
         assert_eq!(fax.number, "+33.86698799".to_string());
@@ -165,16 +227,26 @@ mod tests {
         assert_eq!(result.info_data.email, "contact@eppdev.net".into());
         assert_eq!(result.info_data.client_id, "eppdev".into());
         assert_eq!(result.info_data.creator_id, "SYSTEM".into());
-        assert_eq!(result.info_data.created_at, "2021-07-23T13:09:09.0Z".into());
+        assert_eq!(
+            result.info_data.created_at,
+            Utc.with_ymd_and_hms(2021, 7, 23, 13, 9, 9).unwrap()
+        );
         assert_eq!(
             *(result.info_data.updater_id.as_ref().unwrap()),
             "SYSTEM".into()
         );
         assert_eq!(
             *(result.info_data.updated_at.as_ref().unwrap()),
-            "2021-07-23T13:09:09.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 13, 9, 9).unwrap()
         );
-        assert_eq!((*auth_info).password, "eppdev-387323".into());
+        assert_eq!(auth_info.password, "eppdev-387323".into());
+        assert!(!disclose.flag);
+        assert_eq!(disclose.name[0].info_type, PostalInfoType::Int);
+        assert!(disclose.organization.is_empty());
+        assert_eq!(disclose.address[0].info_type, PostalInfoType::Int);
+        assert!(disclose.voice.is_some());
+        assert!(disclose.fax.is_none());
+        assert!(disclose.email.is_some());
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
     }
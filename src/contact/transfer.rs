@@ -0,0 +1,231 @@
+//! Types for EPP contact transfer request
+
+use std::borrow::Cow;
+
+use super::{xmlns, ContactAuthInfo, XMLNS};
+use crate::common::{NoExtension, StringValue, TransferRecord, TransferStatus};
+use crate::request::{Command, Transaction};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+impl<'a> Transaction<NoExtension> for ContactTransfer<'a> {}
+
+impl<'a> Command for ContactTransfer<'a> {
+    type Response = ContactTransferResponse;
+    const COMMAND: &'static str = "transfer";
+    const OBJECT: Option<&'static str> = Some("contact");
+}
+
+impl<'a> ContactTransfer<'a> {
+    pub fn new(id: &'a str, auth_password: &'a str) -> Self {
+        Self::build("request", id, Some(ContactAuthInfo::new(auth_password)))
+    }
+
+    pub fn query(id: &'a str, auth_password: &'a str) -> Self {
+        Self::build("query", id, Some(ContactAuthInfo::new(auth_password)))
+    }
+
+    pub fn approve(id: &'a str) -> Self {
+        Self::build("approve", id, None)
+    }
+
+    pub fn reject(id: &'a str) -> Self {
+        Self::build("reject", id, None)
+    }
+
+    pub fn cancel(id: &'a str) -> Self {
+        Self::build("cancel", id, None)
+    }
+
+    fn build(operation: &'a str, id: &'a str, auth_info: Option<ContactAuthInfo<'a>>) -> Self {
+        Self {
+            operation: operation.into(),
+            contact: ContactTransferReqData {
+                xmlns: XMLNS,
+                id: id.into(),
+                auth_info,
+            },
+        }
+    }
+}
+
+// Request
+
+/// Type for elements under the contact &lt;transfer&gt; tag
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContactTransferReqData<'a> {
+    /// XML namespace for contact commands
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
+    xmlns: &'a str,
+    /// The id of the contact under transfer
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'a>,
+    /// The authInfo for the contact under transfer
+    /// Only applicable to contact transfer and contact transfer query requests
+    #[serde(rename = "contact:authInfo", alias = "authInfo")]
+    pub auth_info: Option<ContactAuthInfo<'a>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+/// Type for EPP XML &lt;transfer&gt; command for contacts
+pub struct ContactTransfer<'a> {
+    /// The transfer operation to perform indicated by the 'op' attr
+    /// The values are one of request, query, approve, reject or cancel
+    #[serde(rename = "op")]
+    pub operation: Cow<'a, str>,
+    /// The data under the &lt;transfer&gt; tag in the transfer request
+    #[serde(rename = "contact:transfer", alias = "transfer")]
+    pub contact: ContactTransferReqData<'a>,
+}
+
+// Response
+
+/// Type that represents the &lt;trnData&gt; tag for contact transfer response
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContactTransferResponseData {
+    /// XML namespace for contact response data
+    #[serde(rename = "xmlns:contact")]
+    pub xmlns: String,
+    /// The contact id
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'static>,
+    /// The contact transfer status
+    #[serde(
+        rename = "contact:trStatus",
+        alias = "trStatus",
+        with = "crate::common::element"
+    )]
+    pub transfer_status: TransferStatus,
+    /// The epp user who requested the transfer
+    #[serde(rename = "contact:reID", alias = "reID")]
+    pub requester_id: StringValue<'static>,
+    /// The transfer request date
+    #[serde(
+        rename = "contact:reDate",
+        alias = "reDate",
+        with = "crate::common::datetime"
+    )]
+    pub requested_at: DateTime<Utc>,
+    /// The epp user who should acknowledge the transfer request
+    #[serde(rename = "contact:acID", alias = "acID")]
+    pub ack_id: StringValue<'static>,
+    /// The date by which the acknowledgment should be made, or was made
+    #[serde(
+        rename = "contact:acDate",
+        alias = "acDate",
+        with = "crate::common::datetime"
+    )]
+    pub ack_by: DateTime<Utc>,
+}
+
+impl ContactTransferResponseData {
+    /// Returns the state of the transfer
+    pub fn transfer(&self) -> TransferRecord {
+        TransferRecord {
+            status: self.transfer_status.clone(),
+            requested_by: self.requester_id.to_string(),
+            requested_at: self.requested_at,
+            action_by: self.ack_id.to_string(),
+            action_at: self.ack_by,
+        }
+    }
+}
+
+/// Type that represents the &lt;resData&gt; tag for contact transfer response
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContactTransferResponse {
+    /// Data under the &lt;trnData&gt; tag
+    #[serde(rename = "contact:trnData", alias = "trnData")]
+    pub transfer_data: ContactTransferResponseData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContactTransfer;
+    use crate::common::{NoExtension, TransferStatus};
+    use crate::request::Transaction;
+    use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn commands() {
+        let commands = [
+            (
+                "request",
+                ContactTransfer::new("eppdev-contact-3", "epP4uthd#v"),
+            ),
+            (
+                "query",
+                ContactTransfer::query("eppdev-contact-3", "epP4uthd#v"),
+            ),
+            ("approve", ContactTransfer::approve("eppdev-contact-3")),
+            ("reject", ContactTransfer::reject("eppdev-contact-3")),
+            ("cancel", ContactTransfer::cancel("eppdev-contact-3")),
+        ];
+
+        for (op, object) in commands.iter() {
+            let xml = get_xml(&format!("request/contact/transfer_{}.xml", op)).unwrap();
+            let serialized = <ContactTransfer as Transaction<NoExtension>>::serialize_request(
+                object, None, CLTRID,
+            )
+            .unwrap();
+
+            assert_eq!(xml, serialized, "{}", op);
+        }
+    }
+
+    #[test]
+    fn request_response() {
+        let xml = get_xml("response/contact/transfer_request.xml").unwrap();
+        let object =
+            <ContactTransfer as Transaction<NoExtension>>::deserialize_response(xml.as_str())
+                .unwrap();
+
+        let result = object.res_data().unwrap();
+
+        assert_eq!(object.result.code, 1001);
+        assert_eq!(
+            object.result.message,
+            "Command completed successfully; action pending".into()
+        );
+        assert_eq!(result.transfer_data.id, "eppdev-contact-3".into());
+        assert_eq!(
+            result.transfer_data.transfer_status,
+            TransferStatus::Pending
+        );
+        assert_eq!(result.transfer_data.requester_id, "eppdev".into());
+        assert_eq!(
+            result.transfer_data.requested_at,
+            Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 21).unwrap()
+        );
+        assert_eq!(result.transfer_data.ack_id, "ClientY".into());
+        assert_eq!(
+            result.transfer_data.transfer().deadline(),
+            Some(Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap())
+        );
+        assert_eq!(*object.tr_ids.client_tr_id.as_ref().unwrap(), CLTRID.into());
+        assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
+    }
+
+    #[test]
+    fn query_response() {
+        let xml = get_xml("response/contact/transfer_query.xml").unwrap();
+        let object =
+            <ContactTransfer as Transaction<NoExtension>>::deserialize_response(xml.as_str())
+                .unwrap();
+
+        let result = object.res_data().unwrap();
+
+        assert_eq!(object.result.code, 1000);
+        assert_eq!(object.result.message, SUCCESS_MSG.into());
+        assert_eq!(
+            result.transfer_data.transfer_status,
+            TransferStatus::ClientApproved
+        );
+        assert_eq!(
+            result.transfer_data.transfer().resolved_at(),
+            Some(Utc.with_ymd_and_hms(2021, 7, 25, 9, 12, 0).unwrap())
+        );
+        assert_eq!(result.transfer_data.transfer().deadline(), None);
+    }
+}
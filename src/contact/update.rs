@@ -1,19 +1,35 @@
 //! Types for EPP contact create request
 
-use super::{ContactAuthInfo, Phone, PostalInfo, XMLNS};
-use crate::common::{NoExtension, ObjectStatus, StringValue};
+use std::borrow::Cow;
+
+use super::{validate_postal_info, xmlns, ContactAuthInfo, Disclose, Phone, PostalInfo, XMLNS};
+use crate::common::{ContactStatus, NoExtension, ObjectStatus, StringValue};
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use crate::Error;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for ContactUpdate<'a> {}
 
 impl<'a> Command for ContactUpdate<'a> {
     type Response = ();
     const COMMAND: &'static str = "update";
+    const OBJECT: Option<&'static str> = Some("contact");
+
+    fn validate(&self) -> Result<(), Error> {
+        let info = match &self.contact.change_info {
+            Some(info) => info,
+            None => return Ok(()),
+        };
+        validate_postal_info(&info.postal_info)?;
+        match &info.disclose {
+            Some(disclose) => disclose.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'a> ContactUpdate<'a> {
-    pub fn new(id: &'a str) -> ContactUpdate {
+    pub fn new(id: &'a str) -> ContactUpdate<'a> {
         Self {
             contact: ContactUpdateRequestData {
                 xmlns: XMLNS,
@@ -35,13 +51,24 @@ impl<'a> ContactUpdate<'a> {
     ) {
         self.contact.change_info = Some(ContactChangeInfo {
             email: Some(email.into()),
-            postal_info: Some(postal_info),
+            postal_info: vec![postal_info],
             voice: Some(voice),
             auth_info: Some(ContactAuthInfo::new(auth_password)),
             fax: None,
+            disclose: None,
         });
     }
 
+    /// Adds a &lt;postalInfo&gt; tag under &lt;chg&gt; for the contact update request, changing
+    /// the postal info of that type
+    pub fn add_postal_info(&mut self, postal_info: PostalInfo<'a>) {
+        let info = self
+            .contact
+            .change_info
+            .get_or_insert_with(Default::default);
+        info.postal_info.push(postal_info);
+    }
+
     /// Sets the data for the &lt;fax&gt; tag under &lt;chg&gt; for the contact update request
     pub fn set_fax(&mut self, fax: Phone<'a>) {
         if let Some(info) = &mut self.contact.change_info {
@@ -49,69 +76,86 @@ impl<'a> ContactUpdate<'a> {
         }
     }
 
+    /// Sets the data for the &lt;disclose&gt; tag under &lt;chg&gt; for the contact update request,
+    /// changing only the disclosure preferences if no other info was set
+    pub fn set_disclose(&mut self, disclose: Disclose) {
+        let info = self
+            .contact
+            .change_info
+            .get_or_insert_with(Default::default);
+        info.disclose = Some(disclose);
+    }
+
     /// Sets the data for the &lt;add&gt; tag for the contact update request
-    pub fn add(&mut self, status: &'a [ObjectStatus]) {
-        self.contact.add_statuses = Some(StatusList { status });
+    pub fn add(&mut self, status: &'a [ObjectStatus<'a, ContactStatus>]) {
+        self.contact.add_statuses = Some(StatusList {
+            status: status.into(),
+        });
     }
 
     /// Sets the data for the &lt;rem&gt; tag for the contact update request
-    pub fn remove(&mut self, status: &'a [ObjectStatus]) {
-        self.contact.remove_statuses = Some(StatusList { status });
+    pub fn remove(&mut self, status: &'a [ObjectStatus<'a, ContactStatus>]) {
+        self.contact.remove_statuses = Some(StatusList {
+            status: status.into(),
+        });
     }
 }
 
 /// Type for elements under the &lt;chg&gt; tag for contact update request
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ContactChangeInfo<'a> {
-    #[serde(rename = "contact:postalInfo")]
-    postal_info: Option<PostalInfo<'a>>,
-    #[serde(rename = "contact:voice")]
-    voice: Option<Phone<'a>>,
-    #[serde(rename = "contact:fax")]
-    fax: Option<Phone<'a>>,
-    #[serde(rename = "contact:email")]
-    email: Option<StringValue<'a>>,
-    #[serde(rename = "contact:authInfo")]
-    auth_info: Option<ContactAuthInfo<'a>>,
+    #[serde(rename = "contact:postalInfo", alias = "postalInfo", default)]
+    pub postal_info: Vec<PostalInfo<'a>>,
+    #[serde(rename = "contact:voice", alias = "voice")]
+    pub voice: Option<Phone<'a>>,
+    #[serde(rename = "contact:fax", alias = "fax")]
+    pub fax: Option<Phone<'a>>,
+    #[serde(rename = "contact:email", alias = "email")]
+    pub email: Option<StringValue<'a>>,
+    #[serde(rename = "contact:authInfo", alias = "authInfo")]
+    pub auth_info: Option<ContactAuthInfo<'a>>,
+    #[serde(rename = "contact:disclose", alias = "disclose")]
+    pub disclose: Option<Disclose>,
 }
 
 /// Type for list of elements of the &lt;status&gt; tag for contact update request
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct StatusList<'a> {
-    #[serde(rename = "contact:status")]
-    status: &'a [ObjectStatus<'a>],
+    #[serde(rename = "contact:status", alias = "status")]
+    pub status: Cow<'a, [ObjectStatus<'a, ContactStatus>]>,
 }
 
 /// Type for elements under the contact &lt;update&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContactUpdateRequestData<'a> {
-    #[serde(rename = "xmlns:contact")]
+    #[serde(rename = "xmlns:contact", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
-    #[serde(rename = "contact:id")]
-    id: StringValue<'a>,
-    #[serde(rename = "contact:add")]
-    add_statuses: Option<StatusList<'a>>,
-    #[serde(rename = "contact:rem")]
-    remove_statuses: Option<StatusList<'a>>,
-    #[serde(rename = "contact:chg")]
-    change_info: Option<ContactChangeInfo<'a>>,
+    #[serde(rename = "contact:id", alias = "id")]
+    pub id: StringValue<'a>,
+    #[serde(rename = "contact:add", alias = "add")]
+    pub add_statuses: Option<StatusList<'a>>,
+    #[serde(rename = "contact:rem", alias = "rem")]
+    pub remove_statuses: Option<StatusList<'a>>,
+    #[serde(rename = "contact:chg", alias = "chg")]
+    pub change_info: Option<ContactChangeInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;update&gt; command for contacts
 pub struct ContactUpdate<'a> {
     /// The data under the &lt;update&gt; tag for the contact update
-    #[serde(rename = "contact:update")]
-    contact: ContactUpdateRequestData<'a>,
+    #[serde(rename = "contact:update", alias = "update")]
+    pub contact: ContactUpdateRequestData<'a>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::{ContactUpdate, Phone, PostalInfo};
-    use crate::common::{NoExtension, ObjectStatus};
-    use crate::contact::Address;
+    use crate::common::{ContactStatus, NoExtension, ObjectStatus};
+    use crate::contact::{Address, Disclose, PostalInfoType};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
 
     #[test]
     fn command() {
@@ -121,17 +165,18 @@ mod tests {
 
         let street = &["58", "Orchid Road"];
         let address = Address::new(street, "Paris", "Paris", "392374", "FR".parse().unwrap());
-        let postal_info = PostalInfo::new("loc", "John Doe", "Acme Widgets", address);
+        let postal_info = PostalInfo::new(PostalInfoType::Loc, "John Doe", "Acme Widgets", address);
         let voice = Phone::new("+33.47237942");
 
         object.set_info("newemail@eppdev.net", postal_info, voice, "eppdev-387323");
-        let add_statuses = &[ObjectStatus {
-            status: "clientTransferProhibited".into(),
-        }];
+        object.set_disclose(
+            Disclose::new(true)
+                .name(PostalInfoType::Loc)
+                .address(PostalInfoType::Loc),
+        );
+        let add_statuses = &[ObjectStatus::new(ContactStatus::ClientTransferProhibited)];
         object.add(add_statuses);
-        let remove_statuses = &[ObjectStatus {
-            status: "clientDeleteProhibited".into(),
-        }];
+        let remove_statuses = &[ObjectStatus::new(ContactStatus::ClientDeleteProhibited)];
         object.remove(remove_statuses);
 
         let serialized =
@@ -141,6 +186,20 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn empty_disclose() {
+        let mut object = ContactUpdate::new("eppdev-contact-3");
+        object.set_disclose(Disclose::new(true));
+        let result =
+            <ContactUpdate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(matches!(result, Err(Error::Invalid(e)) if e.contains("disclose")));
+
+        object.set_disclose(Disclose::new(true).fax());
+        let result =
+            <ContactUpdate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn contact_update() {
         let xml = get_xml("response/contact/update.xml").unwrap();
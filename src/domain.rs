@@ -1,8 +1,12 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::common::{HostAddr, StringValue};
+use crate::common::{DomainName, HostAddr, StringValue};
+use crate::Error;
 
 pub mod check;
 pub use check::DomainCheck;
@@ -27,31 +31,50 @@ pub use update::DomainUpdate;
 
 pub const XMLNS: &str = "urn:ietf:params:xml:ns:domain-1.0";
 
+/// The namespace restored on deserialized domain commands
+pub(crate) fn xmlns() -> &'static str {
+    XMLNS
+}
+
 /// The &lt;hostAttr&gt; type for domain transactions
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HostAttr<'a> {
     /// The &lt;hostName&gt; tag
     #[serde(rename = "domain:hostName", alias = "hostName")]
     pub name: StringValue<'a>,
     /// The &lt;hostAddr&gt; tags
     #[serde(rename = "domain:hostAddr", alias = "hostAddr")]
-    pub addresses: Option<Vec<HostAddr<'a>>>,
+    pub addresses: Option<Vec<HostAddr>>,
+}
+
+impl HostAttr<'_> {
+    /// Whether the host is `domain` itself or one of its subordinates, the only hosts that can
+    /// have glue addresses when delegating `domain`
+    ///
+    /// Both names are compared in their A-label form, see [`DomainName::is_within()`]; invalid
+    /// names are never in bailiwick.
+    pub fn is_in_bailiwick(&self, domain: &str) -> bool {
+        match (DomainName::new(self.name.as_ref()), DomainName::new(domain)) {
+            (Ok(host), Ok(domain)) => host.is_within(&domain),
+            _ => false,
+        }
+    }
 }
 
 /// The list of &lt;hostAttr&gt; types for domain transactions. Typically under an &lt;ns&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostAttrList<'a> {
     /// The list of &lt;hostAttr&gt; tags
     #[serde(rename = "domain:hostAttr", alias = "hostAttr")]
-    pub hosts: &'a [HostAttr<'a>],
+    pub hosts: Cow<'a, [HostAttr<'a>]>,
 }
 
 /// The list of &lt;hostObj&gt; types for domain transactions. Typically under an &lt;ns&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostObjList<'a> {
     /// The list of &lt;hostObj&gt; tags
     #[serde(rename = "domain:hostObj", alias = "hostObj")]
-    pub hosts: &'a [StringValue<'a>],
+    pub hosts: Cow<'a, [StringValue<'a>]>,
 }
 
 /// Enum that can accept one type which corresponds to either the &lt;hostObj&gt; or &lt;hostAttr&gt;
@@ -63,39 +86,300 @@ pub enum HostList<'a> {
     HostAttrList(HostAttrList<'a>),
 }
 
+impl HostList<'_> {
+    /// Converts the names of the listed hosts to their A-label form, see [`DomainName`]
+    pub(crate) fn normalize(mut self) -> Self {
+        match &mut self {
+            HostList::HostObjList(list) => {
+                if list
+                    .hosts
+                    .iter()
+                    .any(|h| DomainName::converted(h.as_ref()).is_some())
+                {
+                    for host in list.hosts.to_mut() {
+                        if let Some(name) = DomainName::converted(host.as_ref()) {
+                            *host = name.into();
+                        }
+                    }
+                }
+            }
+            HostList::HostAttrList(list) => {
+                if list
+                    .hosts
+                    .iter()
+                    .any(|h| DomainName::converted(h.name.as_ref()).is_some())
+                {
+                    for host in list.hosts.to_mut() {
+                        if let Some(name) = DomainName::converted(host.name.as_ref()) {
+                            host.name = name.into();
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// The names of the listed hosts
+    pub fn names(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self {
+            HostList::HostObjList(list) => Box::new(list.hosts.iter().map(AsRef::as_ref)),
+            HostList::HostAttrList(list) => Box::new(list.hosts.iter().map(|h| h.name.as_ref())),
+        }
+    }
+
+    /// Checks the host names, and that &lt;hostAttr&gt; addresses are only given for hosts in
+    /// the bailiwick of `domain`, as RFC 5731 requires
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn validate(&self, domain: &str) -> Result<(), Error> {
+        self.names().try_for_each(DomainName::validate)?;
+
+        let hosts = match self {
+            HostList::HostAttrList(list) => &list.hosts,
+            HostList::HostObjList(_) => return Ok(()),
+        };
+
+        for host in hosts.iter() {
+            let has_addresses = !host.addresses.as_deref().unwrap_or_default().is_empty();
+            if has_addresses && !host.is_in_bailiwick(domain) {
+                return Err(Error::Invalid(format!(
+                    "host {} is outside of {} and can't have addresses",
+                    host.name, domain
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, 'de> Deserialize<'de> for HostList<'a> {
+    /// Picks the list type from the elements found under &lt;ns&gt;
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Flattened<'a> {
+            #[serde(rename = "hostObj")]
+            host_obj: Option<Vec<StringValue<'a>>>,
+            #[serde(rename = "hostAttr")]
+            host_attr: Option<Vec<HostAttr<'a>>>,
+        }
+
+        let flattened = Flattened::deserialize(deserializer)?;
+        Ok(match (flattened.host_obj, flattened.host_attr) {
+            (_, Some(hosts)) => HostList::HostAttrList(HostAttrList {
+                hosts: hosts.into(),
+            }),
+            (hosts, None) => HostList::HostObjList(HostObjList {
+                hosts: hosts.unwrap_or_default().into(),
+            }),
+        })
+    }
+}
+
 /// The &lt;contact&gt; type on domain creation and update requests
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DomainContact<'a> {
     /// The contact id
     #[serde(rename = "$value")]
     pub id: Cow<'a, str>,
-    /// The contact type attr (usually admin, billing, or tech in most registries)
+    /// The contact type attr
     #[serde(rename = "type")]
-    pub contact_type: Cow<'a, str>,
+    pub contact_type: ContactRole,
+}
+
+impl<'a> DomainContact<'a> {
+    /// Creates a contact reference with the given role
+    pub fn new(contact_type: ContactRole, id: &'a str) -> Self {
+        Self {
+            id: id.into(),
+            contact_type,
+        }
+    }
+
+    /// Checks that no role is given to more than one contact, for registries that forbid it
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn validate_unique_roles(contacts: &[DomainContact<'_>]) -> Result<(), Error> {
+        for (i, contact) in contacts.iter().enumerate() {
+            if contacts[..i]
+                .iter()
+                .any(|c| c.contact_type == contact.contact_type)
+            {
+                return Err(Error::Invalid(format!(
+                    "more than one {} contact",
+                    contact.contact_type
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The role of a domain contact, given by the 'type' attr on &lt;contact&gt; tags
+///
+/// RFC 5731 defines admin, billing and tech; registries may define their own roles.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContactRole {
+    Admin,
+    Billing,
+    Tech,
+    Custom(String),
+}
+
+impl ContactRole {
+    /// The value of the 'type' attr
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Admin => "admin",
+            Self::Billing => "billing",
+            Self::Tech => "tech",
+            Self::Custom(role) => role,
+        }
+    }
+}
+
+impl FromStr for ContactRole {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "" => return Err(Error::Invalid("empty contact role".into())),
+            "admin" => Self::Admin,
+            "billing" => Self::Billing,
+            "tech" => Self::Tech,
+            _ => Self::Custom(s.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for ContactRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ContactRole {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ContactRole {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let role = String::deserialize(deserializer)?;
+        role.parse().map_err(D::Error::custom)
+    }
 }
 
 /// The &lt;period&gt; type for registration, renewal or transfer on domain transactions
-#[derive(Serialize, Debug)]
-pub struct Period {
-    /// The interval (usually 'y' indicating years)
-    unit: String,
-    /// The length of the registration, renewal or transfer period (usually in years)
-    #[serde(rename = "$value")]
-    length: u16,
+///
+/// RFC 5731 allows periods of 1 to 99 years or months, which commands check before being sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Years(u8),
+    Months(u8),
 }
 
 impl Period {
-    /// Creates a new period in years
-    pub fn new(length: u16) -> Period {
-        Period {
-            unit: "y".to_string(),
-            length,
+    /// The longest period allowed, in either unit
+    pub const MAX: u8 = 99;
+
+    /// Creates a period in years
+    pub fn years(length: u8) -> Self {
+        Self::Years(length)
+    }
+
+    /// Creates a period in months
+    pub fn months(length: u8) -> Self {
+        Self::Months(length)
+    }
+
+    /// The value of the 'unit' attr, 'y' or 'm'
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Years(_) => "y",
+            Self::Months(_) => "m",
+        }
+    }
+
+    /// The number of years or months
+    pub fn length(&self) -> u8 {
+        match *self {
+            Self::Years(length) | Self::Months(length) => length,
+        }
+    }
+
+    /// Checks that the length is within the bounds set by RFC 5731
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self.length() {
+            1..=Self::MAX => Ok(()),
+            _ => Err(Error::Invalid(format!(
+                "period {} is out of bounds, it must be 1 to {}",
+                self,
+                Self::MAX
+            ))),
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn parse(unit: &str, length: &str) -> Result<Self, Error> {
+        let invalid = || Error::Invalid(format!("invalid period: {}{}", length, unit));
+        let length = length.trim().parse::<u8>().map_err(|_| invalid())?;
+        let period = match unit {
+            "y" => Self::Years(length),
+            "m" => Self::Months(length),
+            _ => return Err(invalid()),
+        };
+
+        period.validate()?;
+        Ok(period)
+    }
+}
+
+/// Parses periods such as "2y" or "6m", with a bare number taken as years
+impl FromStr for Period {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix(|c| c == 'y' || c == 'm') {
+            Some(length) => Self::parse(&s[length.len()..], length),
+            None => Self::parse("y", s),
         }
     }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.length(), self.unit())
+    }
+}
+
+/// The &lt;period&gt; tag as it appears in the XML
+#[derive(Serialize, Deserialize)]
+struct RawPeriod<'a> {
+    unit: Cow<'a, str>,
+    #[serde(rename = "$value")]
+    length: Cow<'a, str>,
+}
+
+impl Serialize for Period {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawPeriod {
+            unit: self.unit().into(),
+            length: self.length().to_string().into(),
+        }
+        .serialize(serializer)
+    }
+}
 
-    /// Sets the period unit ('y' for years, most commonly)
-    pub fn set_unit(&mut self, unit: &str) {
-        self.unit = unit.to_string();
+impl<'de> Deserialize<'de> for Period {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawPeriod::deserialize(deserializer)?;
+        Self::parse(&raw.unit, &raw.length).map_err(D::Error::custom)
     }
 }
 
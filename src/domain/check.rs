@@ -1,8 +1,11 @@
 //! Types for EPP domain check request
 
-use super::XMLNS;
-use crate::common::{NoExtension, StringValue};
+use std::borrow::Cow;
+
+use super::{xmlns, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
+use crate::Error;
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for DomainCheck<'a> {}
@@ -10,6 +13,15 @@ impl<'a> Transaction<NoExtension> for DomainCheck<'a> {}
 impl<'a> Command for DomainCheck<'a> {
     type Response = DomainCheckResponse;
     const COMMAND: &'static str = "check";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    #[allow(clippy::result_large_err)]
+    fn validate(&self) -> Result<(), Error> {
+        self.list
+            .domains
+            .iter()
+            .try_for_each(|name| DomainName::validate(name.as_ref()))
+    }
 }
 
 impl<'a> DomainCheck<'a> {
@@ -17,7 +29,7 @@ impl<'a> DomainCheck<'a> {
         Self {
             list: DomainList {
                 xmlns: XMLNS,
-                domains: domains.into_iter().map(|d| d.into()).collect(),
+                domains: domains.into_iter().map(DomainName::normalize).collect(),
             },
         }
     }
@@ -26,60 +38,64 @@ impl<'a> DomainCheck<'a> {
 // Request
 
 /// Type for &lt;name&gt; elements under the domain &lt;check&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainList<'a> {
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     /// XML namespace for domain commands
     pub xmlns: &'a str,
-    #[serde(rename = "domain:name")]
+    #[serde(rename = "domain:name", alias = "name")]
     /// List of domains to be checked for availability
     pub domains: Vec<StringValue<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;check&gt; command for domains
 pub struct DomainCheck<'a> {
     /// The object holding the list of domains to be checked
-    #[serde(rename = "domain:check")]
-    list: DomainList<'a>,
+    #[serde(rename = "domain:check", alias = "check")]
+    pub list: DomainList<'a>,
 }
 
 // Response
 
 /// Type that represents the &lt;name&gt; tag for domain check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainAvailable {
     /// The domain name
     #[serde(rename = "$value")]
-    pub name: StringValue<'static>,
+    pub name: Cow<'static, str>,
     /// The domain (un)availability
     #[serde(rename = "avail")]
     pub available: bool,
 }
 
 /// Type that represents the &lt;cd&gt; tag for domain check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCheckResponseDataItem {
     /// Data under the &lt;name&gt; tag
-    #[serde(rename = "name")]
+    #[serde(rename = "domain:name", alias = "name")]
     pub domain: DomainAvailable,
     /// The reason for (un)availability
+    #[serde(rename = "domain:reason", alias = "reason")]
     pub reason: Option<StringValue<'static>>,
 }
 
 /// Type that represents the &lt;chkData&gt; tag for domain check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCheckResponseData {
+    /// XML namespace for domain response data
+    #[serde(rename = "xmlns:domain")]
+    pub xmlns: String,
     /// Data under the &lt;cd&gt; tag
-    #[serde(rename = "cd")]
+    #[serde(rename = "domain:cd", alias = "cd")]
     pub domain_list: Vec<DomainCheckResponseDataItem>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for domain check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCheckResponse {
     /// Data under the &lt;chkData&gt; tag
-    #[serde(rename = "chkData")]
+    #[serde(rename = "domain:chkData", alias = "chkData")]
     pub check_data: DomainCheckResponseData,
 }
 
@@ -89,6 +105,7 @@ mod tests {
     use crate::common::NoExtension;
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
 
     #[test]
     fn command() {
@@ -101,6 +118,20 @@ mod tests {
                 .unwrap();
 
         assert_eq!(xml, serialized);
+
+        // Names are sent in their A-label form
+        let object = DomainCheck::new(vec!["EPPDEV.com", "eppdev.net."]);
+        let serialized =
+            <DomainCheck as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+        assert_eq!(xml, serialized);
+
+        let object = DomainCheck::new(vec!["Bücher.com", "exa_mple.com"]);
+        let err =
+            <DomainCheck as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{}", err);
+        assert_eq!(object.list.domains[0], "xn--bcher-kva.com".into());
     }
 
     #[test]
@@ -113,15 +144,9 @@ mod tests {
 
         assert_eq!(object.result.code, 1000);
         assert_eq!(object.result.message, SUCCESS_MSG.into());
-        assert_eq!(
-            result.check_data.domain_list[0].domain.name,
-            "eppdev.com".into()
-        );
+        assert_eq!(result.check_data.domain_list[0].domain.name, "eppdev.com");
         assert!(result.check_data.domain_list[0].domain.available);
-        assert_eq!(
-            result.check_data.domain_list[1].domain.name,
-            "eppdev.net".into()
-        );
+        assert_eq!(result.check_data.domain_list[1].domain.name, "eppdev.net");
         assert!(!result.check_data.domain_list[1].domain.available);
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
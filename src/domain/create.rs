@@ -1,8 +1,12 @@
 //! Types for EPP domain create request
 
-use super::{DomainAuthInfo, DomainContact, HostList, Period, XMLNS};
-use crate::common::{NoExtension, StringValue};
+use std::borrow::Cow;
+
+use super::{xmlns, DomainAuthInfo, DomainContact, HostList, Period, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
+use crate::Error;
+use chrono::{DateTime, Utc};
 
 use serde::{Deserialize, Serialize};
 
@@ -11,51 +15,67 @@ impl<'a> Transaction<NoExtension> for DomainCreate<'a> {}
 impl<'a> Command for DomainCreate<'a> {
     type Response = DomainCreateResponse;
     const COMMAND: &'static str = "create";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.domain.name.as_ref())?;
+        self.domain.period.validate()?;
+        if let Some(contacts) = self.domain.contacts.as_ref().filter(|_| self.unique_roles) {
+            DomainContact::validate_unique_roles(contacts)?;
+        }
+        match &self.domain.ns {
+            Some(ns) => ns.validate(self.domain.name.as_ref()),
+            None => Ok(()),
+        }
+    }
 }
 
 // Request
 
 /// Type for elements under the domain &lt;create&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCreateRequestData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     pub xmlns: &'a str,
     /// The domain name
-    #[serde(rename = "domain:name")]
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'a>,
     /// The period of registration
-    #[serde(rename = "domain:period")]
+    #[serde(rename = "domain:period", alias = "period")]
     pub period: Period,
     /// The list of nameserver hosts
     /// either of type `HostObjList` or `HostAttrList`
-    #[serde(rename = "domain:ns")]
+    #[serde(rename = "domain:ns", alias = "ns")]
     pub ns: Option<HostList<'a>>,
     /// The domain registrant
-    #[serde(rename = "domain:registrant")]
+    #[serde(rename = "domain:registrant", alias = "registrant")]
     pub registrant: Option<StringValue<'a>>,
     /// The list of contacts for the domain
-    #[serde(rename = "domain:contact")]
-    pub contacts: Option<&'a [DomainContact<'a>]>,
+    #[serde(rename = "domain:contact", alias = "contact")]
+    pub contacts: Option<Cow<'a, [DomainContact<'a>]>>,
     /// The auth info for the domain
-    #[serde(rename = "domain:authInfo")]
+    #[serde(rename = "domain:authInfo", alias = "authInfo")]
     pub auth_info: DomainAuthInfo<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;create&gt; command for domains
 pub struct DomainCreate<'a> {
     /// The data for the domain to be created with
     /// T being the type of nameserver list (`HostObjList` or `HostAttrList`)
     /// to be supplied
-    #[serde(rename = "domain:create")]
+    #[serde(rename = "domain:create", alias = "create")]
     pub domain: DomainCreateRequestData<'a>,
+    /// Whether to reject contacts sharing a role before sending the command
+    #[serde(skip)]
+    unique_roles: bool,
 }
 
 impl<'a> DomainCreate<'a> {
     pub fn new(
         name: &'a str,
-        period: u16,
+        period: Period,
         ns: Option<HostList<'a>>,
         registrant_id: Option<&'a str>,
         auth_password: &'a str,
@@ -64,50 +84,71 @@ impl<'a> DomainCreate<'a> {
         Self {
             domain: DomainCreateRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
-                period: Period::new(period),
-                ns,
+                name: DomainName::normalize(name),
+                period,
+                ns: ns.map(HostList::normalize),
                 registrant: registrant_id.map(|id| id.into()),
                 auth_info: DomainAuthInfo::new(auth_password),
-                contacts,
+                contacts: contacts.map(Into::into),
             },
+            unique_roles: false,
         }
     }
+
+    /// Sets whether each role can only be given to one contact, for registries that forbid
+    /// duplicates
+    pub fn set_unique_roles(&mut self, unique_roles: bool) {
+        self.unique_roles = unique_roles;
+    }
 }
 
 // Response
 
 /// Type that represents the &lt;chkData&gt; tag for domain create response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCreateResponseData {
     /// XML namespace for domain response data
     #[serde(rename = "xmlns:domain")]
     pub xmlns: String,
     /// The domain name
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The creation date
-    #[serde(rename = "crDate")]
-    pub created_at: StringValue<'static>,
+    #[serde(
+        rename = "domain:crDate",
+        alias = "crDate",
+        with = "crate::common::datetime"
+    )]
+    pub created_at: DateTime<Utc>,
     /// The expiry date
-    #[serde(rename = "exDate")]
-    pub expiring_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:exDate",
+        alias = "exDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub expiring_at: Option<DateTime<Utc>>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for domain create response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainCreateResponse {
     /// Data under the &lt;chkData&gt; tag
-    #[serde(rename = "creData")]
+    #[serde(rename = "domain:creData", alias = "creData")]
     pub create_data: DomainCreateResponseData,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DomainContact, DomainCreate, HostList};
+    use super::{DomainContact, DomainCreate, HostList, Period};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
     use crate::common::{HostAddr, NoExtension};
-    use crate::domain::{HostAttr, HostAttrList, HostObjList};
+    use crate::domain::{ContactRole, HostAttr, HostAttrList, HostObjList};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
@@ -115,22 +156,22 @@ mod tests {
 
         let contacts = &[
             DomainContact {
-                contact_type: "admin".into(),
+                contact_type: ContactRole::Admin,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "tech".into(),
+                contact_type: ContactRole::Tech,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "billing".into(),
+                contact_type: ContactRole::Billing,
                 id: "eppdev-contact-3".into(),
             },
         ];
 
         let object = DomainCreate::new(
             "eppdev-1.com",
-            1,
+            Period::years(1),
             None,
             Some("eppdev-contact-3"),
             "epP4uthd#v",
@@ -150,15 +191,15 @@ mod tests {
 
         let contacts = &[
             DomainContact {
-                contact_type: "admin".into(),
+                contact_type: ContactRole::Admin,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "tech".into(),
+                contact_type: ContactRole::Tech,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "billing".into(),
+                contact_type: ContactRole::Billing,
                 id: "eppdev-contact-3".into(),
             },
         ];
@@ -166,8 +207,10 @@ mod tests {
         let hosts = &["ns1.test.com".into(), "ns2.test.com".into()];
         let object = DomainCreate::new(
             "eppdev-1.com",
-            1,
-            Some(HostList::HostObjList(HostObjList { hosts })),
+            Period::years(1),
+            Some(HostList::HostObjList(HostObjList {
+                hosts: hosts.into(),
+            })),
             Some("eppdev-contact-3"),
             "epP4uthd#v",
             Some(contacts),
@@ -186,15 +229,15 @@ mod tests {
 
         let contacts = &[
             DomainContact {
-                contact_type: "admin".into(),
+                contact_type: ContactRole::Admin,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "tech".into(),
+                contact_type: ContactRole::Tech,
                 id: "eppdev-contact-3".into(),
             },
             DomainContact {
-                contact_type: "billing".into(),
+                contact_type: ContactRole::Billing,
                 id: "eppdev-contact-3".into(),
             },
         ];
@@ -205,18 +248,22 @@ mod tests {
                 addresses: None,
             },
             HostAttr {
-                name: "ns2.eppdev-1.com".into(),
+                name: "ns2.eppdev-2.com".into(),
                 addresses: Some(vec![
-                    HostAddr::new_v4("177.232.12.58"),
-                    HostAddr::new_v6("2404:6800:4001:801::200e"),
+                    HostAddr::from(Ipv4Addr::new(177, 232, 12, 58)),
+                    HostAddr::from(Ipv6Addr::new(
+                        0x2404, 0x6800, 0x4001, 0x801, 0, 0, 0, 0x200e,
+                    )),
                 ]),
             },
         ];
 
         let object = DomainCreate::new(
             "eppdev-2.com",
-            1,
-            Some(HostList::HostAttrList(HostAttrList { hosts })),
+            Period::years(1),
+            Some(HostList::HostAttrList(HostAttrList {
+                hosts: hosts.into(),
+            })),
             Some("eppdev-contact-3"),
             "epP4uthd#v",
             Some(contacts),
@@ -229,6 +276,86 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn out_of_bailiwick_glue() {
+        let hosts = &[HostAttr {
+            name: "ns1.eppdev-1.com".into(),
+            addresses: Some(vec![HostAddr::from(Ipv4Addr::new(192, 0, 2, 1))]),
+        }];
+        let ns = || {
+            Some(HostList::HostAttrList(HostAttrList {
+                hosts: hosts.into(),
+            }))
+        };
+
+        let object = DomainCreate::new(
+            "eppdev-2.com",
+            Period::years(1),
+            ns(),
+            None,
+            "epP4uthd#v",
+            None,
+        );
+        let err =
+            <DomainCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{}", err);
+
+        let object = DomainCreate::new(
+            "eppdev-1.com",
+            Period::years(1),
+            ns(),
+            None,
+            "epP4uthd#v",
+            None,
+        );
+        <DomainCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+            .unwrap();
+
+        assert!(hosts[0].is_in_bailiwick("EPPDEV-1.com."));
+        assert!(!hosts[0].is_in_bailiwick("dev-1.com"));
+
+        let idn = HostAttr {
+            name: "ns1.Bücher.com.".into(),
+            addresses: None,
+        };
+        assert!(idn.is_in_bailiwick("xn--bcher-kva.com"));
+        assert!(!idn.is_in_bailiwick("exa_mple.com"));
+    }
+
+    #[test]
+    fn duplicate_roles() {
+        let contacts = &[
+            DomainContact::new(ContactRole::Tech, "eppdev-contact-3"),
+            DomainContact::new("reseller".parse().unwrap(), "eppdev-contact-4"),
+            DomainContact::new(ContactRole::Tech, "eppdev-contact-5"),
+        ];
+        assert_eq!(
+            contacts[1].contact_type,
+            ContactRole::Custom("reseller".into())
+        );
+        assert_eq!("admin".parse::<ContactRole>().unwrap(), ContactRole::Admin);
+        assert!("".parse::<ContactRole>().is_err());
+
+        let mut object = DomainCreate::new(
+            "eppdev-1.com",
+            Period::years(1),
+            None,
+            None,
+            "epP4uthd#v",
+            Some(contacts),
+        );
+        <DomainCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+            .unwrap();
+
+        object.set_unique_roles(true);
+        let err =
+            <DomainCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap_err();
+        assert!(matches!(err, Error::Invalid(_)), "{}", err);
+        assert!(err.to_string().contains("more than one tech contact"));
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/domain/create.xml").unwrap();
@@ -242,11 +369,11 @@ mod tests {
         assert_eq!(result.create_data.name, "eppdev-2.com".into());
         assert_eq!(
             result.create_data.created_at,
-            "2021-07-25T18:11:35.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 25, 18, 11, 35).unwrap()
         );
         assert_eq!(
             *result.create_data.expiring_at.as_ref().unwrap(),
-            "2022-07-25T18:11:34.0Z".into()
+            Utc.with_ymd_and_hms(2022, 7, 25, 18, 11, 34).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
@@ -1,15 +1,21 @@
 //! Types for EPP domain delete request
 
-use super::XMLNS;
-use crate::common::{NoExtension, StringValue};
+use super::{xmlns, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use crate::Error;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for DomainDelete<'a> {}
 
 impl<'a> Command for DomainDelete<'a> {
     type Response = ();
     const COMMAND: &'static str = "delete";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.domain.name.as_ref())
+    }
 }
 
 impl<'a> DomainDelete<'a> {
@@ -17,29 +23,29 @@ impl<'a> DomainDelete<'a> {
         Self {
             domain: DomainDeleteRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
             },
         }
     }
 }
 
 /// Type for &lt;name&gt; element under the domain &lt;delete&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainDeleteRequestData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The domain to be deleted
-    #[serde(rename = "domain:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "domain:name", alias = "name")]
+    pub name: StringValue<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;delete&gt; command for domains
 pub struct DomainDelete<'a> {
     /// The data under the &lt;delete&gt; tag for domain deletion
-    #[serde(rename = "domain:delete")]
-    domain: DomainDeleteRequestData<'a>,
+    #[serde(rename = "domain:delete", alias = "delete")]
+    pub domain: DomainDeleteRequestData<'a>,
 }
 
 #[cfg(test)]
@@ -1,15 +1,27 @@
 //! Types for EPP domain info request
 
-use super::{DomainAuthInfo, DomainContact, HostAttr, XMLNS};
-use crate::common::{NoExtension, ObjectStatus, StringValue};
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use super::{xmlns, ContactRole, DomainAuthInfo, DomainContact, HostAttr, XMLNS};
+use crate::common::{unicode, DomainName, DomainStatus, NoExtension, ObjectStatus, StringValue};
 use crate::request::{Command, Transaction};
-use serde::{Deserialize, Serialize};
+use crate::Error;
+use chrono::{DateTime, Utc};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 impl<'a> Transaction<NoExtension> for DomainInfo<'a> {}
 
 impl<'a> Command for DomainInfo<'a> {
     type Response = DomainInfoResponse;
     const COMMAND: &'static str = "info";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(&self.info.domain.name)
+    }
 }
 
 impl<'a> DomainInfo<'a> {
@@ -17,123 +29,251 @@ impl<'a> DomainInfo<'a> {
         Self {
             info: DomainInfoRequestData {
                 xmlns: XMLNS,
-                domain: Domain { hosts: "all", name },
+                domain: Domain {
+                    hosts: Hosts::All,
+                    name: DomainName::normalize(name).into(),
+                },
                 auth_info: auth_password.map(|password| DomainAuthInfo {
                     password: password.into(),
                 }),
             },
         }
     }
+
+    /// Sets which hosts the response lists, all of them by default
+    pub fn set_hosts(&mut self, hosts: Hosts) {
+        self.info.domain.hosts = hosts;
+    }
 }
 
 // Request
 
+/// The 'hosts' attr on the domain &lt;name&gt; tag, selecting the hosts listed in the response
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Hosts {
+    /// `all`, both the delegated and the subordinate hosts
+    #[default]
+    All,
+    /// `del`, only the delegated hosts, the name servers under &lt;ns&gt;
+    Del,
+    /// `sub`, only the subordinate hosts, created under the domain
+    Sub,
+    /// `none`, no hosts
+    None,
+}
+
+impl Hosts {
+    /// Returns the selector as used in EPP XML
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Del => "del",
+            Self::Sub => "sub",
+            Self::None => "none",
+        }
+    }
+}
+
+impl FromStr for Hosts {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "del" => Ok(Self::Del),
+            "sub" => Ok(Self::Sub),
+            "none" => Ok(Self::None),
+            _ => Err(Error::Invalid(format!("unknown hosts selector: {}", s))),
+        }
+    }
+}
+
+impl Display for Hosts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Hosts {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Hosts {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hosts = String::deserialize(deserializer)?;
+        hosts.parse().map_err(D::Error::custom)
+    }
+}
+
 /// Type for data under the &lt;name&gt; element tag for the domain &lt;info&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Domain<'a> {
     /// The hosts attribute. Default value is "all"
-    hosts: &'a str,
+    #[serde(default)]
+    pub hosts: Hosts,
     /// The name of the domain
     #[serde(rename = "$value")]
-    name: &'a str,
+    pub name: Cow<'a, str>,
 }
 
 /// Type for &lt;name&gt; element under the domain &lt;info&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainInfoRequestData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The data for the domain to be queried
-    #[serde(rename = "domain:name")]
-    domain: Domain<'a>,
+    #[serde(rename = "domain:name", alias = "name")]
+    pub domain: Domain<'a>,
     /// The auth info for the domain
-    #[serde(rename = "domain:authInfo")]
-    auth_info: Option<DomainAuthInfo<'a>>,
+    #[serde(rename = "domain:authInfo", alias = "authInfo")]
+    pub auth_info: Option<DomainAuthInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;info&gt; command for domains
 pub struct DomainInfo<'a> {
     /// The data under the &lt;info&gt; tag for domain info
-    #[serde(rename = "domain:info")]
-    info: DomainInfoRequestData<'a>,
+    #[serde(rename = "domain:info", alias = "info")]
+    pub info: DomainInfoRequestData<'a>,
 }
 
 // Response
 
 /// The two types of ns lists, hostObj and hostAttr, that may be returned in the
 /// domain info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainNsList {
     /// List of &lt;hostObj&gt; ns elements
-    #[serde(rename = "hostObj")]
+    #[serde(rename = "domain:hostObj", alias = "hostObj")]
     pub host_obj: Option<Vec<StringValue<'static>>>,
     /// List of &lt;hostAttr&gt; ns elements
+    #[serde(rename = "domain:hostAttr", alias = "hostAttr")]
     pub host_attr: Option<Vec<HostAttr<'static>>>,
 }
 
 /// Type that represents the &lt;infData&gt; tag for domain info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainInfoResponseData {
+    /// XML namespace for domain response data
+    #[serde(rename = "xmlns:domain")]
+    pub xmlns: String,
     /// The domain name
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The domain ROID
+    #[serde(rename = "domain:roid", alias = "roid")]
     pub roid: StringValue<'static>,
     /// The list of domain statuses
-    #[serde(rename = "status")]
-    pub statuses: Option<Vec<ObjectStatus<'static>>>,
+    #[serde(rename = "domain:status", alias = "status")]
+    pub statuses: Option<Vec<ObjectStatus<'static, DomainStatus>>>,
     /// The domain registrant
+    #[serde(rename = "domain:registrant", alias = "registrant")]
     pub registrant: Option<StringValue<'static>>,
     /// The list of domain contacts
-    #[serde(rename = "contact")]
+    #[serde(rename = "domain:contact", alias = "contact")]
     pub contacts: Option<Vec<DomainContact<'static>>>,
     /// The list of domain nameservers
-    #[serde(rename = "ns")]
+    #[serde(rename = "domain:ns", alias = "ns")]
     pub ns: Option<DomainNsList>,
     /// The list of domain hosts
-    #[serde(rename = "host")]
+    #[serde(rename = "domain:host", alias = "host")]
     pub hosts: Option<Vec<StringValue<'static>>>,
     /// The epp user who owns the domain
-    #[serde(rename = "clID")]
+    #[serde(rename = "domain:clID", alias = "clID")]
     pub client_id: StringValue<'static>,
     /// The epp user who created the domain
-    #[serde(rename = "crID")]
+    #[serde(rename = "domain:crID", alias = "crID")]
     pub creator_id: Option<StringValue<'static>>,
     /// The domain creation date
-    #[serde(rename = "crDate")]
-    pub created_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:crDate",
+        alias = "crDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub created_at: Option<DateTime<Utc>>,
     /// The domain expiry date
-    #[serde(rename = "exDate")]
-    pub expiring_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:exDate",
+        alias = "exDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub expiring_at: Option<DateTime<Utc>>,
     /// The epp user who last updated the domain
-    #[serde(rename = "upID")]
+    #[serde(rename = "domain:upID", alias = "upID")]
     pub updater_id: Option<StringValue<'static>>,
     /// The domain last updated date
-    #[serde(rename = "upDate")]
-    pub updated_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:upDate",
+        alias = "upDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub updated_at: Option<DateTime<Utc>>,
     /// The domain transfer date
-    #[serde(rename = "trDate")]
-    pub transferred_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:trDate",
+        alias = "trDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub transferred_at: Option<DateTime<Utc>>,
     /// The domain auth info
-    #[serde(rename = "authInfo")]
+    #[serde(rename = "domain:authInfo", alias = "authInfo")]
     pub auth_info: Option<DomainAuthInfo<'static>>,
 }
 
+impl DomainInfoResponseData {
+    /// Returns the U-label form of the domain name, for display
+    pub fn name_unicode(&self) -> String {
+        unicode(self.name.as_ref())
+    }
+
+    /// Returns whether a client or server prohibition blocks some transform command on the domain
+    pub fn is_locked(&self) -> bool {
+        self.statuses
+            .iter()
+            .flatten()
+            .any(|s| s.status.is_prohibition())
+    }
+
+    /// Returns whether a transform command on the domain awaits completion
+    pub fn is_pending(&self) -> bool {
+        self.statuses
+            .iter()
+            .flatten()
+            .any(|s| s.status.is_pending())
+    }
+
+    /// Returns the IDs of the contacts with the given role
+    pub fn contacts_by_role(&self, role: ContactRole) -> impl Iterator<Item = &str> + '_ {
+        self.contacts
+            .iter()
+            .flatten()
+            .filter(move |c| c.contact_type == role)
+            .map(|c| c.id.as_ref())
+    }
+}
+
 /// Type that represents the &lt;resData&gt; tag for domain info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainInfoResponse {
     /// Data under the &lt;resData&gt; tag
-    #[serde(rename = "infData")]
+    #[serde(rename = "domain:infData", alias = "infData")]
     pub info_data: DomainInfoResponseData,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DomainInfo;
-    use crate::common::NoExtension;
-    use crate::request::Transaction;
+    use super::{ContactRole, DomainInfo, Hosts};
+    use crate::common::{DomainStatus, NoExtension};
+    use crate::request::{Command, Transaction};
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
@@ -148,6 +288,27 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn command_hosts() {
+        let xml = get_xml("request/domain/info_hosts.xml").unwrap();
+
+        let mut object = DomainInfo::new("eppdev.com", None);
+        object.set_hosts(Hosts::Del);
+
+        let serialized =
+            <DomainInfo as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+
+        assert_eq!(xml, serialized);
+    }
+
+    #[test]
+    fn normalized_name() {
+        let object = DomainInfo::new("Bücher.com", None);
+        assert_eq!(object.info.domain.name, "xn--bcher-kva.com");
+        assert!(<DomainInfo as Command>::validate(&object).is_ok());
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/domain/info.xml").unwrap();
@@ -157,7 +318,7 @@ mod tests {
         let result = object.res_data().unwrap();
         let auth_info = result.info_data.auth_info.as_ref().unwrap();
         let ns_list = result.info_data.ns.as_ref().unwrap();
-        let ns = (*ns_list).host_obj.as_ref().unwrap();
+        let ns = ns_list.host_obj.as_ref().unwrap();
         let hosts = result.info_data.hosts.as_ref().unwrap();
         let statuses = result.info_data.statuses.as_ref().unwrap();
         let registrant = result.info_data.registrant.as_ref().unwrap();
@@ -166,16 +327,34 @@ mod tests {
         assert_eq!(object.result.code, 1000);
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(result.info_data.name, "eppdev-1.com".into());
+        assert_eq!(result.info_data.name_unicode(), "eppdev-1.com");
         assert_eq!(result.info_data.roid, "125899511_DOMAIN_COM-VRSN".into());
-        assert_eq!(statuses[0].status, "ok".to_string());
-        assert_eq!(statuses[1].status, "clientTransferProhibited".to_string());
+        assert_eq!(statuses[0].status, DomainStatus::Ok);
+        assert_eq!(statuses[1].status, DomainStatus::ClientTransferProhibited);
+        assert!(result.info_data.is_locked());
+        assert!(!result.info_data.is_pending());
         assert_eq!(*registrant, "eppdev-contact-2".into());
         assert_eq!(contacts[0].id, "eppdev-contact-2".to_string());
-        assert_eq!(contacts[0].contact_type, "admin".to_string());
+        assert_eq!(contacts[0].contact_type, ContactRole::Admin);
         assert_eq!(contacts[1].id, "eppdev-contact-2".to_string());
-        assert_eq!(contacts[1].contact_type, "tech".to_string());
+        assert_eq!(contacts[1].contact_type, ContactRole::Tech);
         assert_eq!(contacts[2].id, "eppdev-contact-2".to_string());
-        assert_eq!(contacts[2].contact_type, "billing".to_string());
+        assert_eq!(contacts[2].contact_type, ContactRole::Billing);
+        assert_eq!(
+            result
+                .info_data
+                .contacts_by_role(ContactRole::Admin)
+                .collect::<Vec<_>>(),
+            ["eppdev-contact-2"]
+        );
+        assert_eq!(
+            result
+                .info_data
+                .contacts_by_role(ContactRole::Custom("reseller".into()))
+                .count(),
+            0
+        );
+        assert!(ns_list.host_attr.is_none());
         assert_eq!((*ns)[0], "ns1.eppdev-1.com".into());
         assert_eq!((*ns)[1], "ns2.eppdev-1.com".into());
         assert_eq!((*hosts)[0], "ns1.eppdev-1.com".into());
@@ -187,7 +366,7 @@ mod tests {
         );
         assert_eq!(
             *result.info_data.created_at.as_ref().unwrap(),
-            "2021-07-23T15:31:20.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 20).unwrap()
         );
         assert_eq!(
             *result.info_data.updater_id.as_ref().unwrap(),
@@ -195,13 +374,13 @@ mod tests {
         );
         assert_eq!(
             *result.info_data.updated_at.as_ref().unwrap(),
-            "2021-07-23T15:31:21.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 21).unwrap()
         );
         assert_eq!(
             *result.info_data.expiring_at.as_ref().unwrap(),
-            "2023-07-23T15:31:20.0Z".into()
+            Utc.with_ymd_and_hms(2023, 7, 23, 15, 31, 20).unwrap()
         );
-        assert_eq!((*auth_info).password, "epP4uthd#v".into());
+        assert_eq!(auth_info.password, "epP4uthd#v".into());
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
     }
@@ -209,6 +388,68 @@ mod tests {
     #[test]
     fn response_alt() {
         let xml = get_xml("response/domain/info_alt.xml").unwrap();
-        <DomainInfo as Transaction<NoExtension>>::deserialize_response(xml.as_str()).unwrap();
+        let object =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(xml.as_str()).unwrap();
+
+        let statuses = object
+            .res_data()
+            .unwrap()
+            .info_data
+            .statuses
+            .as_ref()
+            .unwrap();
+        assert_eq!(statuses[0].status, DomainStatus::ClientDeleteProhibited);
+        assert_eq!(statuses[2].status, DomainStatus::ClientHold);
+    }
+
+    #[test]
+    fn response_status_reason() {
+        let xml = get_xml("response/domain/info_status_reason.xml").unwrap();
+        let object =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(xml.as_str()).unwrap();
+
+        let statuses = object
+            .res_data()
+            .unwrap()
+            .info_data
+            .statuses
+            .as_ref()
+            .unwrap();
+        assert_eq!(statuses[0].status, DomainStatus::ClientDeleteProhibited);
+        assert_eq!(statuses[0].reason, None);
+        assert_eq!(statuses[2].status, DomainStatus::ClientHold);
+        assert_eq!(statuses[2].lang.as_deref(), Some("en"));
+        assert_eq!(statuses[2].reason.as_deref(), Some("Payment overdue"));
+    }
+
+    #[test]
+    fn response_idn() {
+        let xml = get_xml("response/domain/info_idn.xml").unwrap();
+        let object =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(xml.as_str()).unwrap();
+
+        let info_data = &object.res_data().unwrap().info_data;
+        assert_eq!(info_data.name, "xn--bcher-kva.com".into());
+        assert_eq!(info_data.name_unicode(), "bücher.com");
+    }
+
+    #[test]
+    fn response_host_attr() {
+        let xml = get_xml("response/domain/info_host_attr.xml").unwrap();
+        let object =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(xml.as_str()).unwrap();
+
+        let ns_list = object.res_data().unwrap().info_data.ns.as_ref().unwrap();
+        let hosts = ns_list.host_attr.as_ref().unwrap();
+        assert!(ns_list.host_obj.is_none());
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].name, "ns1.eppdev-1.com".into());
+        let addresses = hosts[0].addresses.as_ref().unwrap();
+        assert_eq!(addresses[0].ip_version(), "v4");
+        assert_eq!(addresses[0].to_string(), "192.0.2.1");
+        assert_eq!(addresses[1].ip_version(), "v6");
+        assert_eq!(addresses[1].to_string(), "2001:db8::1");
+        assert_eq!(hosts[1].name, "ns.example.net".into());
+        assert!(hosts[1].addresses.is_none());
     }
 }
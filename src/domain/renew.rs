@@ -1,9 +1,10 @@
 //! Types for EPP domain renew request
 
-use super::{Period, XMLNS};
-use crate::common::{NoExtension, StringValue};
+use super::{xmlns, Period, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
-use chrono::NaiveDate;
+use crate::Error;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for DomainRenew<'a> {}
@@ -11,17 +12,23 @@ impl<'a> Transaction<NoExtension> for DomainRenew<'a> {}
 impl<'a> Command for DomainRenew<'a> {
     type Response = DomainRenewResponse;
     const COMMAND: &'static str = "renew";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.domain.name.as_ref())?;
+        self.domain.period.validate()
+    }
 }
 
 impl<'a> DomainRenew<'a> {
-    pub fn new(name: &'a str, current_expiry_date: NaiveDate, years: u16) -> Self {
+    pub fn new(name: &'a str, current_expiry_date: NaiveDate, period: Period) -> Self {
         let exp_date_str = current_expiry_date.format("%Y-%m-%d").to_string().into();
         Self {
             domain: DomainRenewRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
                 current_expiry_date: exp_date_str,
-                period: Period::new(years),
+                period,
             },
         }
     }
@@ -30,64 +37,74 @@ impl<'a> DomainRenew<'a> {
 // Request
 
 /// Type for data under the domain &lt;renew&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainRenewRequestData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The name of the domain to be renewed
-    #[serde(rename = "domain:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "domain:name", alias = "name")]
+    pub name: StringValue<'a>,
     /// The current expiry date of the domain in 'Y-m-d' format
-    #[serde(rename = "domain:curExpDate")]
-    current_expiry_date: StringValue<'a>,
+    #[serde(rename = "domain:curExpDate", alias = "curExpDate")]
+    pub current_expiry_date: StringValue<'a>,
     /// The period of renewal
-    #[serde(rename = "domain:period")]
-    period: Period,
+    #[serde(rename = "domain:period", alias = "period")]
+    pub period: Period,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;renew&gt; command for domains
 pub struct DomainRenew<'a> {
     /// The data under the &lt;renew&gt; tag for the domain renewal
-    #[serde(rename = "domain:renew")]
-    domain: DomainRenewRequestData<'a>,
+    #[serde(rename = "domain:renew", alias = "renew")]
+    pub domain: DomainRenewRequestData<'a>,
 }
 
 // Response
 
 /// Type that represents the &lt;renData&gt; tag for domain renew response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainRenewResponseData {
+    /// XML namespace for domain response data
+    #[serde(rename = "xmlns:domain")]
+    pub xmlns: String,
     /// The name of the domain
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The new expiry date after renewal
-    #[serde(rename = "exDate")]
-    pub expiring_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:exDate",
+        alias = "exDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub expiring_at: Option<DateTime<Utc>>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for domain renew response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainRenewResponse {
     /// Data under the &lt;renData&gt; tag
-    #[serde(rename = "renData")]
+    #[serde(rename = "domain:renData", alias = "renData")]
     pub renew_data: DomainRenewResponseData,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DomainRenew;
+    use super::{DomainRenew, Period};
     use crate::common::NoExtension;
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
-    use chrono::NaiveDate;
+    use crate::Error;
+    use chrono::{NaiveDate, TimeZone, Utc};
 
     #[test]
     fn command() {
         let xml = get_xml("request/domain/renew.xml").unwrap();
 
-        let exp_date = NaiveDate::from_ymd(2022, 7, 23);
-        let object = DomainRenew::new("eppdev.com", exp_date, 1);
+        let exp_date = NaiveDate::from_ymd_opt(2022, 7, 23).unwrap();
+        let object = DomainRenew::new("eppdev.com", exp_date, Period::years(1));
 
         let serialized =
             <DomainRenew as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -96,6 +113,48 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn command_months() {
+        let xml = get_xml("request/domain/renew_months.xml").unwrap();
+
+        let exp_date = NaiveDate::from_ymd_opt(2022, 7, 23).unwrap();
+        let object = DomainRenew::new("eppdev.com", exp_date, Period::months(6));
+
+        let serialized =
+            <DomainRenew as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+
+        assert_eq!(xml, serialized);
+    }
+
+    #[test]
+    fn periods() {
+        let exp_date = NaiveDate::from_ymd_opt(2022, 7, 23).unwrap();
+        for period in [Period::years(0), Period::years(100), Period::months(120)].iter() {
+            let object = DomainRenew::new("eppdev.com", exp_date, *period);
+            let err =
+                <DomainRenew as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                    .unwrap_err();
+            assert!(matches!(err, Error::Invalid(_)), "{}", err);
+        }
+
+        assert_eq!("2".parse::<Period>().unwrap(), Period::years(2));
+        assert_eq!("99y".parse::<Period>().unwrap(), Period::years(99));
+        assert_eq!("6m".parse::<Period>().unwrap(), Period::months(6));
+        assert_eq!(Period::months(6).to_string(), "6m");
+        for s in ["", "0", "100y", "6w", "y", "-1m"].iter() {
+            assert!(s.parse::<Period>().is_err(), "{}", s);
+        }
+
+        let parse = |xml: &str| quick_xml::de::from_str::<Period>(xml);
+        assert_eq!(
+            parse(r#"<period unit="m">18</period>"#).unwrap(),
+            Period::months(18)
+        );
+        assert!(parse(r#"<period unit="y">100</period>"#).is_err());
+        assert!(parse(r#"<period unit="d">10</period>"#).is_err());
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/domain/renew.xml").unwrap();
@@ -109,7 +168,7 @@ mod tests {
         assert_eq!(result.renew_data.name, "eppdev-1.com".into());
         assert_eq!(
             *result.renew_data.expiring_at.as_ref().unwrap(),
-            "2024-07-23T15:31:20.0Z".into()
+            Utc.with_ymd_and_hms(2024, 7, 23, 15, 31, 20).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
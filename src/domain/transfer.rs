@@ -1,8 +1,12 @@
 //! Types for EPP domain transfer request
 
-use super::{DomainAuthInfo, Period, XMLNS};
-use crate::common::{NoExtension, StringValue};
+use std::borrow::Cow;
+
+use super::{xmlns, DomainAuthInfo, Period, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue, TransferRecord, TransferStatus};
 use crate::request::{Command, Transaction};
+use crate::Error;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for DomainTransfer<'a> {}
@@ -10,14 +14,23 @@ impl<'a> Transaction<NoExtension> for DomainTransfer<'a> {}
 impl<'a> Command for DomainTransfer<'a> {
     type Response = DomainTransferResponse;
     const COMMAND: &'static str = "transfer";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.domain.name.as_ref())?;
+        match &self.domain.period {
+            Some(period) => period.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'a> DomainTransfer<'a> {
-    pub fn new(name: &'a str, years: Option<u16>, auth_password: &'a str) -> Self {
+    pub fn new(name: &'a str, period: Option<Period>, auth_password: &'a str) -> Self {
         Self::build(
             "request",
             name,
-            years.map(Period::new),
+            period,
             Some(DomainAuthInfo::new(auth_password)),
         )
     }
@@ -50,10 +63,10 @@ impl<'a> DomainTransfer<'a> {
         auth_info: Option<DomainAuthInfo<'a>>,
     ) -> Self {
         Self {
-            operation,
+            operation: operation.into(),
             domain: DomainTransferReqData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
                 period,
                 auth_info,
             },
@@ -64,83 +77,118 @@ impl<'a> DomainTransfer<'a> {
 // Request
 
 /// Type for elements under the domain &lt;transfer&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainTransferReqData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The name of the domain under transfer
-    #[serde(rename = "domain:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "domain:name", alias = "name")]
+    pub name: StringValue<'a>,
     /// The period of renewal upon a successful transfer
     /// Only applicable in case of a transfer request
-    #[serde(rename = "domain:period")]
-    period: Option<Period>,
+    #[serde(rename = "domain:period", alias = "period")]
+    pub period: Option<Period>,
     /// The authInfo for the domain under transfer
     /// Only applicable to domain transfer and domain transfer query requests
-    #[serde(rename = "domain:authInfo")]
-    auth_info: Option<DomainAuthInfo<'a>>,
+    #[serde(rename = "domain:authInfo", alias = "authInfo")]
+    pub auth_info: Option<DomainAuthInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;transfer&gt; command for domains
 pub struct DomainTransfer<'a> {
     /// The transfer operation to perform indicated by the 'op' attr
     /// The values are one of transfer or query
     #[serde(rename = "op")]
-    operation: &'a str,
+    pub operation: Cow<'a, str>,
     /// The data under the &lt;transfer&gt; tag in the transfer request
-    #[serde(rename = "domain:transfer")]
-    domain: DomainTransferReqData<'a>,
+    #[serde(rename = "domain:transfer", alias = "transfer")]
+    pub domain: DomainTransferReqData<'a>,
 }
 
 // Response
 
 /// Type that represents the &lt;trnData&gt; tag for domain transfer response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainTransferResponseData {
+    /// XML namespace for domain response data
+    #[serde(rename = "xmlns:domain")]
+    pub xmlns: String,
     /// The domain name
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The domain transfer status
-    #[serde(rename = "trStatus")]
-    pub transfer_status: StringValue<'static>,
+    #[serde(
+        rename = "domain:trStatus",
+        alias = "trStatus",
+        with = "crate::common::element"
+    )]
+    pub transfer_status: TransferStatus,
     /// The epp user who requested the transfer
-    #[serde(rename = "reID")]
+    #[serde(rename = "domain:reID", alias = "reID")]
     pub requester_id: StringValue<'static>,
-    /// The transfer rquest date
-    #[serde(rename = "reDate")]
-    pub requested_at: StringValue<'static>,
+    /// The transfer request date
+    #[serde(
+        rename = "domain:reDate",
+        alias = "reDate",
+        with = "crate::common::datetime"
+    )]
+    pub requested_at: DateTime<Utc>,
     /// The epp user who should acknowledge the transfer request
-    #[serde(rename = "acID")]
+    #[serde(rename = "domain:acID", alias = "acID")]
     pub ack_id: StringValue<'static>,
-    /// THe date by which the acknowledgment should be made
-    #[serde(rename = "acDate")]
-    pub ack_by: StringValue<'static>,
+    /// The date by which the acknowledgment should be made, or was made
+    #[serde(
+        rename = "domain:acDate",
+        alias = "acDate",
+        with = "crate::common::datetime"
+    )]
+    pub ack_by: DateTime<Utc>,
     /// The domain expiry date
-    #[serde(rename = "exDate")]
-    pub expiring_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "domain:exDate",
+        alias = "exDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub expiring_at: Option<DateTime<Utc>>,
+}
+
+impl DomainTransferResponseData {
+    /// Returns the state of the transfer
+    pub fn transfer(&self) -> TransferRecord {
+        TransferRecord {
+            status: self.transfer_status.clone(),
+            requested_by: self.requester_id.to_string(),
+            requested_at: self.requested_at,
+            action_by: self.ack_id.to_string(),
+            action_at: self.ack_by,
+        }
+    }
 }
 
 /// Type that represents the &lt;resData&gt; tag for domain transfer response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainTransferResponse {
     /// Data under the &lt;trnData&gt; tag
-    #[serde(rename = "trnData")]
+    #[serde(rename = "domain:trnData", alias = "trnData")]
     pub transfer_data: DomainTransferResponseData,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::DomainTransfer;
-    use crate::common::NoExtension;
+    use super::{DomainTransfer, Period};
+    use crate::common::{NoExtension, TransferStatus};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use chrono::{Duration, TimeZone, Utc};
 
     #[test]
     fn request_command() {
         let xml = get_xml("request/domain/transfer_request.xml").unwrap();
 
-        let object = DomainTransfer::new("testing.com", Some(1), "epP4uthd#v");
+        let object = DomainTransfer::new("testing.com", Some(Period::years(1)), "epP4uthd#v");
 
         let serialized =
             <DomainTransfer as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -216,20 +264,38 @@ mod tests {
             "Command completed successfully; action pending".into()
         );
         assert_eq!(result.transfer_data.name, "eppdev-transfer.com".into());
-        assert_eq!(result.transfer_data.transfer_status, "pending".into());
+        assert_eq!(
+            result.transfer_data.transfer_status,
+            TransferStatus::Pending
+        );
         assert_eq!(result.transfer_data.requester_id, "eppdev".into());
         assert_eq!(
             result.transfer_data.requested_at,
-            "2021-07-23T15:31:21.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 21).unwrap()
         );
         assert_eq!(result.transfer_data.ack_id, "ClientY".into());
-        assert_eq!(result.transfer_data.ack_by, "2021-07-28T15:31:21.0Z".into());
+        assert_eq!(
+            result.transfer_data.ack_by,
+            Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap()
+        );
         assert_eq!(
             *result.transfer_data.expiring_at.as_ref().unwrap(),
-            "2022-07-02T14:53:19.0Z".into()
+            Utc.with_ymd_and_hms(2022, 7, 2, 14, 53, 19).unwrap()
         );
         assert_eq!(*object.tr_ids.client_tr_id.as_ref().unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
+
+        let transfer = result.transfer_data.transfer();
+        let deadline = Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap();
+        assert_eq!(transfer.requested_by, "eppdev");
+        assert_eq!(transfer.deadline(), Some(deadline));
+        assert_eq!(transfer.resolved_at(), None);
+        let now = deadline - Duration::days(2);
+        assert_eq!(transfer.remaining(now), Some(Duration::days(2)));
+        assert!(!transfer.is_overdue(now));
+        let now = deadline + Duration::hours(1);
+        assert_eq!(transfer.remaining(now), Some(Duration::zero()));
+        assert!(transfer.is_overdue(now));
     }
 
     #[test]
@@ -283,17 +349,23 @@ mod tests {
         assert_eq!(object.result.code, 1000);
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(result.transfer_data.name, "eppdev-transfer.com".into());
-        assert_eq!(result.transfer_data.transfer_status, "pending".into());
+        assert_eq!(
+            result.transfer_data.transfer_status,
+            TransferStatus::Pending
+        );
         assert_eq!(result.transfer_data.requester_id, "eppdev".into());
         assert_eq!(
             result.transfer_data.requested_at,
-            "2021-07-23T15:31:21.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 21).unwrap()
         );
         assert_eq!(result.transfer_data.ack_id, "ClientY".into());
-        assert_eq!(result.transfer_data.ack_by, "2021-07-28T15:31:21.0Z".into());
+        assert_eq!(
+            result.transfer_data.ack_by,
+            Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap()
+        );
         assert_eq!(
             *result.transfer_data.expiring_at.as_ref().unwrap(),
-            "2022-07-02T14:53:19.0Z".into()
+            Utc.with_ymd_and_hms(2022, 7, 2, 14, 53, 19).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
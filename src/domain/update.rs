@@ -1,18 +1,42 @@
 //! Types for EPP domain check request
 //!
-use super::{DomainAuthInfo, DomainContact, HostList, XMLNS};
+use std::borrow::Cow;
+
+use super::{xmlns, DomainAuthInfo, DomainContact, HostList, XMLNS};
 use crate::{
-    common::{NoExtension, ObjectStatus, StringValue},
+    common::{DomainName, DomainStatus, NoExtension, ObjectStatus, StringValue},
     request::{Command, Transaction},
+    Error,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for DomainUpdate<'a> {}
 
 impl<'a> Command for DomainUpdate<'a> {
     type Response = ();
     const COMMAND: &'static str = "update";
+    const OBJECT: Option<&'static str> = Some("domain");
+
+    fn validate(&self) -> Result<(), Error> {
+        let name = self.domain.name.as_ref();
+        DomainName::validate(name)?;
+        let added_contacts = self
+            .domain
+            .add
+            .as_ref()
+            .and_then(|add| add.contacts.as_ref());
+        if let Some(contacts) = added_contacts.filter(|_| self.unique_roles) {
+            DomainContact::validate_unique_roles(contacts)?;
+        }
+        if let Some(ns) = self.domain.add.as_ref().and_then(|add| add.ns.as_ref()) {
+            ns.validate(name)?;
+        }
+        match self.domain.remove.as_ref().and_then(|rem| rem.ns.as_ref()) {
+            Some(ns) => ns.names().try_for_each(DomainName::validate),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'a> DomainUpdate<'a> {
@@ -20,14 +44,21 @@ impl<'a> DomainUpdate<'a> {
         Self {
             domain: DomainUpdateRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
                 add: None,
                 remove: None,
                 change_info: None,
             },
+            unique_roles: false,
         }
     }
 
+    /// Sets whether each role can only be given to one of the added contacts, for registries
+    /// that forbid duplicates
+    pub fn set_unique_roles(&mut self, unique_roles: bool) {
+        self.unique_roles = unique_roles;
+    }
+
     /// Sets the data for the &lt;chg&gt; tag
     pub fn info(&mut self, info: DomainChangeInfo<'a>) {
         self.domain.change_info = Some(info);
@@ -35,74 +66,87 @@ impl<'a> DomainUpdate<'a> {
 
     /// Sets the data for the &lt;add&gt; tag
     pub fn add(&mut self, add: DomainAddRemove<'a>) {
-        self.domain.add = Some(add);
+        self.domain.add = Some(add.normalize());
     }
 
     /// Sets the data for the &lt;rem&gt; tag
     pub fn remove(&mut self, remove: DomainAddRemove<'a>) {
-        self.domain.remove = Some(remove);
+        self.domain.remove = Some(remove.normalize());
     }
 }
 
 /// Type for elements under the &lt;chg&gt; tag for domain update
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainChangeInfo<'a> {
     /// The new registrant contact for the domain
-    #[serde(rename = "domain:registrant")]
+    #[serde(rename = "domain:registrant", alias = "registrant")]
     pub registrant: Option<StringValue<'a>>,
     /// The new auth info for the domain
-    #[serde(rename = "domain:authInfo")]
+    #[serde(rename = "domain:authInfo", alias = "authInfo")]
     pub auth_info: Option<DomainAuthInfo<'a>>,
 }
 
 /// Type for elements under the &lt;add&gt; and &lt;rem&gt; tags for domain update
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainAddRemove<'a> {
     /// The list of nameservers to add or remove
     /// Type T can be either a `HostObjList` or `HostAttrList`
-    #[serde(rename = "domain:ns")]
+    #[serde(rename = "domain:ns", alias = "ns")]
     pub ns: Option<HostList<'a>>,
     /// The list of contacts to add to or remove from the domain
-    #[serde(rename = "domain:contact")]
-    pub contacts: Option<&'a [DomainContact<'a>]>,
+    #[serde(rename = "domain:contact", alias = "contact")]
+    pub contacts: Option<Cow<'a, [DomainContact<'a>]>>,
     /// The list of statuses to add to or remove from the domain
-    #[serde(rename = "domain:status")]
-    pub statuses: Option<&'a [ObjectStatus<'a>]>,
+    #[serde(rename = "domain:status", alias = "status")]
+    pub statuses: Option<Cow<'a, [ObjectStatus<'a, DomainStatus>]>>,
+}
+
+impl DomainAddRemove<'_> {
+    fn normalize(self) -> Self {
+        Self {
+            ns: self.ns.map(HostList::normalize),
+            ..self
+        }
+    }
 }
 
 /// Type for elements under the &lt;update&gt; tag for domain update
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DomainUpdateRequestData<'a> {
     /// XML namespace for domain commands
-    #[serde(rename = "xmlns:domain")]
+    #[serde(rename = "xmlns:domain", skip_deserializing, default = "xmlns")]
     pub xmlns: &'a str,
     /// The name of the domain to update
-    #[serde(rename = "domain:name")]
+    #[serde(rename = "domain:name", alias = "name")]
     pub name: StringValue<'a>,
     /// `DomainAddRemove` Object containing the list of elements to be added
     /// to the domain
-    #[serde(rename = "domain:add")]
+    #[serde(rename = "domain:add", alias = "add")]
     pub add: Option<DomainAddRemove<'a>>,
     /// `DomainAddRemove` Object containing the list of elements to be removed
     /// from the domain
-    #[serde(rename = "domain:rem")]
+    #[serde(rename = "domain:rem", alias = "rem")]
     pub remove: Option<DomainAddRemove<'a>>,
     /// The data under the &lt;chg&gt; tag for domain update
-    #[serde(rename = "domain:chg")]
+    #[serde(rename = "domain:chg", alias = "chg")]
     pub change_info: Option<DomainChangeInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;update&gt; command for domains
 pub struct DomainUpdate<'a> {
-    #[serde(rename = "domain:update")]
+    #[serde(rename = "domain:update", alias = "update")]
     pub domain: DomainUpdateRequestData<'a>,
+    /// Whether to reject added contacts sharing a role before sending the command
+    #[serde(skip)]
+    unique_roles: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::{DomainAddRemove, DomainAuthInfo, DomainChangeInfo, DomainContact, DomainUpdate};
-    use crate::common::{NoExtension, ObjectStatus};
+    use crate::common::{DomainStatus, NoExtension, ObjectStatus};
+    use crate::domain::{ContactRole, HostList, HostObjList};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
 
@@ -112,24 +156,22 @@ mod tests {
 
         let mut object = DomainUpdate::new("eppdev.com");
 
-        let statuses = &[ObjectStatus {
-            status: "clientDeleteProhibited".into(),
-        }];
+        let statuses = &[ObjectStatus::new(DomainStatus::ClientDeleteProhibited)];
 
         let add = DomainAddRemove {
             ns: None,
             contacts: None,
-            statuses: Some(statuses),
+            statuses: Some(statuses.into()),
         };
 
         let contacts = &[DomainContact {
-            contact_type: "billing".into(),
+            contact_type: ContactRole::Billing,
             id: "eppdev-contact-2".into(),
         }];
 
         let remove = DomainAddRemove {
             ns: None,
-            contacts: Some(contacts),
+            contacts: Some(contacts.into()),
             statuses: None,
         };
 
@@ -149,6 +191,26 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    fn normalized_names() {
+        let hosts = &["NS1.eppdev.com.".into(), "ns1.Bücher.com".into()];
+        let mut object = DomainUpdate::new("EPPDEV.com");
+        object.add(DomainAddRemove {
+            ns: Some(HostList::HostObjList(HostObjList {
+                hosts: hosts.into(),
+            })),
+            contacts: None,
+            statuses: None,
+        });
+
+        let serialized =
+            <DomainUpdate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
+                .unwrap();
+        assert!(serialized.contains("<domain:name>eppdev.com</domain:name>"));
+        assert!(serialized.contains("<domain:hostObj>ns1.eppdev.com</domain:hostObj>"));
+        assert!(serialized.contains("<domain:hostObj>ns1.xn--bcher-kva.com</domain:hostObj>"));
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/domain/update.xml").unwrap();
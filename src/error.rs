@@ -13,8 +13,16 @@ use crate::response::ResponseStatus;
 #[derive(Debug)]
 pub enum Error {
     Io(std::io::Error),
+    /// The registry's response carried an error result
+    ///
+    /// The status is held by value so callers can match on it directly. That makes `Error`
+    /// larger than clippy's `result_large_err` lint likes, so functions returning it allow the
+    /// lint one by one.
     Command(ResponseStatus),
     Xml(Box<dyn StdError + Send + Sync>),
+    /// A command that breaks a constraint of the EPP RFCs, found before it was sent, or an
+    /// invalid client setting
+    Invalid(String),
     Other(Box<dyn StdError + Send + Sync>),
 }
 
@@ -28,6 +36,7 @@ impl Display for Error {
                 write!(f, "command error: {}", e.result.message)
             }
             Error::Xml(e) => write!(f, "(de)serialization error: {}", e),
+            Error::Invalid(e) => write!(f, "invalid command: {}", e),
             Error::Other(e) => write!(f, "error: {}", e),
         }
     }
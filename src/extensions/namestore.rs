@@ -8,7 +8,7 @@ use crate::{
     common::StringValue,
     contact::{
         check::ContactCheck, create::ContactCreate, delete::ContactDelete, info::ContactInfo,
-        update::ContactUpdate,
+        transfer::ContactTransfer, update::ContactUpdate,
     },
     domain::{
         check::DomainCheck, create::DomainCreate, delete::DomainDelete, info::DomainInfo,
@@ -29,6 +29,7 @@ impl<'a> Transaction<NameStore<'a>> for ContactCheck<'a> {}
 impl<'a> Transaction<NameStore<'a>> for ContactCreate<'a> {}
 impl<'a> Transaction<NameStore<'a>> for ContactDelete<'a> {}
 impl<'a> Transaction<NameStore<'a>> for ContactInfo<'a> {}
+impl<'a> Transaction<NameStore<'a>> for ContactTransfer<'a> {}
 impl<'a> Transaction<NameStore<'a>> for ContactUpdate<'a> {}
 
 // Domain
@@ -51,7 +52,7 @@ impl<'a> Transaction<NameStore<'a>> for HostUpdate<'a> {}
 
 impl<'a> NameStore<'a> {
     /// Create a new RGP restore report request
-    pub fn new(subproduct: &str) -> NameStore {
+    pub fn new(subproduct: &str) -> NameStore<'a> {
         NameStore {
             data: NameStoreData {
                 xmlns: XMLNS.into(),
@@ -5,6 +5,11 @@ pub mod request;
 
 pub const XMLNS: &str = "urn:ietf:params:xml:ns:rgp-1.0";
 
+/// The namespace restored on deserialized rgp extensions
+pub(crate) fn xmlns() -> &'static str {
+    XMLNS
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Update<T> {
     #[serde(
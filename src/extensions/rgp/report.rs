@@ -4,9 +4,11 @@ use crate::common::{NoExtension, StringValue};
 use crate::domain::update::DomainUpdate;
 use crate::request::{Extension, Transaction};
 use chrono::{DateTime, SecondsFormat, Utc};
-use serde::Serialize;
+use std::borrow::Cow;
 
-use super::{Update, XMLNS};
+use serde::{Deserialize, Serialize};
+
+use super::{xmlns, Update, XMLNS};
 
 impl<'a> Transaction<Update<RgpRestoreReport<'a>>> for DomainUpdate<'a> {}
 
@@ -26,7 +28,7 @@ impl<'a> RgpRestoreReport<'a> {
         Self {
             xmlns: XMLNS,
             restore: RgpRestoreReportSection {
-                op: "report",
+                op: "report".into(),
                 report: RgpRestoreReportSectionData {
                     pre_data: pre_data.into(),
                     post_data: post_data.into(),
@@ -50,50 +52,50 @@ impl<'a> Extension for Update<RgpRestoreReport<'a>> {
 }
 
 /// Type corresponding to the &lt;report&gt; section in the EPP rgp restore extension
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RgpRestoreReportSectionData<'a> {
     /// The pre-delete registration date
-    #[serde(rename = "rgp:preData")]
-    pre_data: StringValue<'a>,
+    #[serde(rename = "rgp:preData", alias = "preData")]
+    pub pre_data: StringValue<'a>,
     /// The post-delete registration date
-    #[serde(rename = "rgp:postData")]
-    post_data: StringValue<'a>,
+    #[serde(rename = "rgp:postData", alias = "postData")]
+    pub post_data: StringValue<'a>,
     /// The domain deletion date
-    #[serde(rename = "rgp:delTime")]
-    deleted_at: StringValue<'a>,
+    #[serde(rename = "rgp:delTime", alias = "delTime")]
+    pub deleted_at: StringValue<'a>,
     /// The domain restore request date
-    #[serde(rename = "rgp:resTime")]
-    restored_at: StringValue<'a>,
+    #[serde(rename = "rgp:resTime", alias = "resTime")]
+    pub restored_at: StringValue<'a>,
     /// The reason for domain restoration
-    #[serde(rename = "rgp:resReason")]
-    restore_reason: StringValue<'a>,
+    #[serde(rename = "rgp:resReason", alias = "resReason")]
+    pub restore_reason: StringValue<'a>,
     /// The registrar's statements on the domain restoration
-    #[serde(rename = "rgp:statement")]
-    statements: Vec<StringValue<'a>>,
+    #[serde(rename = "rgp:statement", alias = "statement")]
+    pub statements: Vec<StringValue<'a>>,
     /// Other remarks for domain restoration
-    #[serde(rename = "rgp:other")]
-    other: StringValue<'a>,
+    #[serde(rename = "rgp:other", alias = "other")]
+    pub other: StringValue<'a>,
 }
 
 /// Type corresponding to the &lt;restore&gt; section in the rgp restore extension
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RgpRestoreReportSection<'a> {
     /// The value of the op attribute for the &lt;restore&gt; tag
-    op: &'a str,
+    pub op: Cow<'a, str>,
     /// Data for the &lt;report&gt; tag
-    #[serde(rename = "rgp:report")]
-    report: RgpRestoreReportSectionData<'a>,
+    #[serde(rename = "rgp:report", alias = "report")]
+    pub report: RgpRestoreReportSectionData<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;check&gt; command for domains
 pub struct RgpRestoreReport<'a> {
     /// XML namespace for the RGP restore extension
-    #[serde(rename = "xmlns:rgp")]
+    #[serde(rename = "xmlns:rgp", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The object holding the list of domains to be checked
-    #[serde(rename = "rgp:restore")]
-    restore: RgpRestoreReportSection<'a>,
+    #[serde(rename = "rgp:restore", alias = "restore")]
+    pub restore: RgpRestoreReportSection<'a>,
 }
 
 #[cfg(test)]
@@ -5,9 +5,11 @@ use crate::{
     request::{Extension, Transaction},
 };
 
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 
-use super::{Update, XMLNS};
+use super::{xmlns, Update, XMLNS};
 
 impl<'a> Transaction<Update<RgpRestoreRequest<'a>>> for DomainUpdate<'a> {}
 
@@ -20,28 +22,30 @@ impl<'a> Extension for Update<RgpRestoreRequest<'a>> {
 // Request
 
 /// Type corresponding to the &lt;restore&gt; tag for an rgp restore request
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RgpRestoreRequestData<'a> {
     /// The value of the op attribute in the &lt;restore&gt; tag
-    pub op: &'a str,
+    pub op: Cow<'a, str>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;check&gt; command for domains
 pub struct RgpRestoreRequest<'a> {
     /// XML namespace for the RGP restore extension
-    #[serde(rename = "xmlns:rgp")]
+    #[serde(rename = "xmlns:rgp", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The object holding the list of domains to be checked
-    #[serde(rename = "rgp:restore")]
-    restore: RgpRestoreRequestData<'a>,
+    #[serde(rename = "rgp:restore", alias = "restore")]
+    pub restore: RgpRestoreRequestData<'a>,
 }
 
 impl Default for RgpRestoreRequest<'static> {
     fn default() -> Self {
         Self {
             xmlns: XMLNS,
-            restore: RgpRestoreRequestData { op: "request" },
+            restore: RgpRestoreRequestData {
+                op: "request".into(),
+            },
         }
     }
 }
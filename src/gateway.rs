@@ -0,0 +1,569 @@
+//! An HTTP gateway translating JSON requests into EPP commands
+//!
+//! Web frontends usually speak JSON over HTTP rather than EPP. A [`Gateway`] serves a small REST
+//! API and translates every request into an EPP command sent through a [`Pool`] of registry
+//! sessions:
+//!
+//! | Request                            | Command                                              |
+//! |------------------------------------|------------------------------------------------------|
+//! | `GET /domains/{name}/availability` | [`DomainCheck`]                                      |
+//! | `GET /domains/{name}`              | [`DomainInfo`], with the password from `X-Auth-Info` |
+//! | `POST /domains`                    | [`DomainCreate`], from a [`CreateDomain`] body       |
+//! | `POST /domains/{name}/renew`       | [`DomainRenew`], from a [`RenewDomain`] body         |
+//! | `DELETE /domains/{name}`           | [`DomainDelete`]                                     |
+//!
+//! Responses are JSON objects built from the response data. Error results from the registry are
+//! answered with the HTTP status [`http_status()`] maps their result code to, and a body such as
+//! `{"error": {"code": 2303, "message": "Object does not exist"}}`; errors found by the gateway
+//! itself, such as malformed bodies, have no `code`.
+//!
+//! Only domains are exposed; contacts and hosts are out of scope for the gateway and have to be
+//! managed over EPP, e.g. with the `epp` tool.
+//!
+//! The gateway acts with the full rights of the registrar, so every request has to carry one of
+//! the tokens set with [`Gateway::token()`] in an `Authorization: Bearer <token>` header, or is
+//! answered with 401. A gateway without tokens accepts all requests and must only be reachable
+//! from trusted hosts; `epp-gateway` listens on localhost unless configured otherwise, and
+//! refuses to listen on other addresses without tokens.
+//!
+//! The `epp-gateway` binary, built with the `gateway` feature, runs a gateway configured by a TOML
+//! file. See [`GatewayConfig`] for its format.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::config::RegistryConfig;
+//! use epp_client::gateway::Gateway;
+//! use epp_client::pool::Pool;
+//! use tokio::net::TcpListener;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//! let registry = RegistryConfig {
+//!     name: "registry_name".into(),
+//!     host: "epp.example.com".into(),
+//!     port: 700,
+//!     username: "username".into(),
+//!     password: "password".into(),
+//!     extensions: Vec::new(),
+//!     certificate: None,
+//!     key: None,
+//! };
+//!
+//! let pool = Pool::connect(&registry, 2, 16).await.unwrap();
+//! let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
+//! Gateway::new(pool).token("secret-token").run(listener).await.unwrap();
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+use crate::common::StringValue;
+use crate::config::RegistryConfig;
+use crate::domain::{
+    ContactRole, DomainCheck, DomainContact, DomainCreate, DomainDelete, DomainInfo, DomainRenew,
+    HostList, HostObjList, Period,
+};
+use crate::error::Error;
+use crate::json::{Json, ToJson};
+use crate::pool::Pool;
+use crate::response::EppResult;
+
+/// The header carrying the domain password for `GET /domains/{name}`
+///
+/// Passwords are kept out of URLs, which end up in access logs.
+pub const AUTH_INFO: &str = "x-auth-info";
+
+/// The configuration of the `epp-gateway` binary
+///
+/// ```toml
+/// listen = "127.0.0.1:8080"
+/// sessions = 2
+/// tokens = ["secret-token"]
+///
+/// [registry]
+/// name = "example"
+/// host = "epp.example.com"
+/// username = "registrar"
+/// password = "secret"
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct GatewayConfig {
+    /// The address to accept HTTP connections on, `127.0.0.1:8080` by default
+    #[serde(default = "listen")]
+    pub listen: SocketAddr,
+    /// The bearer tokens accepted from HTTP clients
+    ///
+    /// Required unless `listen` is a loopback address.
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    /// The number of registry sessions to open
+    #[serde(default = "crate::pool::default_sessions")]
+    pub sessions: usize,
+    /// The number of commands each registry session queues before requests have to wait, at
+    /// least 1
+    #[serde(default = "crate::pool::default_queue_depth")]
+    pub queue_depth: usize,
+    /// The registry to send commands to
+    pub registry: RegistryConfig,
+}
+
+/// The body of `POST /domains`
+///
+/// ```json
+/// {
+///     "name": "eppdev.com",
+///     "period": 2,
+///     "registrant": "eppdev-1",
+///     "contacts": [{"type": "tech", "id": "eppdev-2"}],
+///     "ns": ["ns1.eppdev.net"],
+///     "auth": "epP4uthd#v"
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CreateDomain {
+    /// The domain to register
+    pub name: String,
+    /// The registration period in years
+    #[serde(default = "period")]
+    pub period: u8,
+    /// The ID of the registrant contact
+    pub registrant: Option<String>,
+    /// The other contacts of the domain
+    #[serde(default)]
+    pub contacts: Vec<ContactRef>,
+    /// The host names of the domain's name servers
+    #[serde(default)]
+    pub ns: Vec<String>,
+    /// The authorization password for the domain
+    pub auth: String,
+}
+
+/// A contact of a domain, such as `{"type": "admin", "id": "eppdev-1"}`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ContactRef {
+    /// The contact type: `admin`, `tech`, `billing` or a role defined by the registry
+    #[serde(rename = "type")]
+    pub contact_type: ContactRole,
+    /// The contact's ID
+    pub id: String,
+}
+
+/// The body of `POST /domains/{name}/renew`, such as `{"expiry": "2022-07-23", "period": 1}`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RenewDomain {
+    /// The current expiry date of the domain
+    pub expiry: NaiveDate,
+    /// The renewal period in years
+    #[serde(default = "period")]
+    pub period: u8,
+}
+
+fn period() -> u8 {
+    1
+}
+
+fn listen() -> SocketAddr {
+    (Ipv4Addr::LOCALHOST, 8080).into()
+}
+
+/// A JSON response and its HTTP status
+type Reply = (StatusCode, Value);
+
+/// Serves a REST API over HTTP, sending the corresponding EPP commands through a [`Pool`]
+#[derive(Debug)]
+pub struct Gateway {
+    pool: Pool,
+    tokens: Vec<String>,
+    prefix: String,
+    client_tr_id: AtomicU64,
+}
+
+impl Gateway {
+    /// Creates a gateway sending commands through `pool`
+    pub fn new(pool: Pool) -> Self {
+        Self {
+            pool,
+            tokens: Vec::new(),
+            prefix: format!("gateway-{}", Utc::now().timestamp()),
+            client_tr_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Accepts requests authorized with the bearer token `token`
+    ///
+    /// Once a token has been added, requests without one of the added tokens are rejected.
+    pub fn token(mut self, token: &str) -> Self {
+        self.tokens.push(token.to_owned());
+        self
+    }
+
+    /// The pool commands are sent through
+    pub fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    /// Accepts HTTP/1 connections on `listener`, serving each of them on its own task
+    pub async fn run(self, listener: TcpListener) -> Result<(), Error> {
+        info!("HTTP gateway listening on {}", listener.local_addr()?);
+        let gateway = Arc::new(self);
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let gateway = gateway.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let gateway = gateway.clone();
+                    async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+                });
+
+                let connection =
+                    http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+                if let Err(e) = connection.await {
+                    warn!("HTTP connection from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    /// Answers a single HTTP request
+    pub async fn handle(&self, request: hyper::Request<Incoming>) -> hyper::Response<Full<Bytes>> {
+        let (parts, body) = request.into_parts();
+        let (status, json) = if !self.authorized(&parts.headers) {
+            failure(StatusCode::UNAUTHORIZED, "unauthorized")
+        } else {
+            match body.collect().await {
+                Ok(body) => {
+                    self.call(&parts.method, &parts.uri, &parts.headers, &body.to_bytes())
+                        .await
+                }
+                Err(e) => failure(StatusCode::BAD_REQUEST, &e.to_string()),
+            }
+        };
+
+        debug!(
+            "{} {} answered with {}",
+            parts.method,
+            parts.uri.path(),
+            status
+        );
+        let mut response = hyper::Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json");
+        if status == StatusCode::UNAUTHORIZED {
+            response = response.header(WWW_AUTHENTICATE, "Bearer");
+        }
+
+        response
+            .body(Full::new(Bytes::from(json.to_string())))
+            .expect("responses with valid headers always build")
+    }
+
+    /// Returns `true` if `headers` carry one of the gateway's tokens, or it has none
+    pub fn authorized(&self, headers: &HeaderMap) -> bool {
+        if self.tokens.is_empty() {
+            return true;
+        }
+
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match token {
+            Some(token) => self
+                .tokens
+                .iter()
+                .any(|expected| constant_time_eq(expected.as_bytes(), token.trim().as_bytes())),
+            None => false,
+        }
+    }
+
+    /// Answers the request for `uri` with the given method, headers and body
+    ///
+    /// Doesn't check authorization; see [`Gateway::authorized()`].
+    pub async fn call(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> (StatusCode, Value) {
+        let segments = uri
+            .path()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        let result = match (method, segments.as_slice()) {
+            (&Method::POST, ["domains"]) => self.create(body).await,
+            (&Method::GET, ["domains", name]) => match headers.get(AUTH_INFO) {
+                None => self.info(name, None).await,
+                Some(auth) => match auth.to_str() {
+                    Ok(auth) => self.info(name, Some(auth)).await,
+                    Err(_) => Ok(failure(
+                        StatusCode::BAD_REQUEST,
+                        "invalid X-Auth-Info header",
+                    )),
+                },
+            },
+            (&Method::DELETE, ["domains", name]) => self.delete(name).await,
+            (&Method::GET, ["domains", name, "availability"]) => self.check(name).await,
+            (&Method::POST, ["domains", name, "renew"]) => self.renew(name, body).await,
+            (_, ["domains"])
+            | (_, ["domains", _])
+            | (_, ["domains", _, "availability"])
+            | (_, ["domains", _, "renew"]) => Ok(failure(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "method not allowed",
+            )),
+            _ => Ok(failure(StatusCode::NOT_FOUND, "not found")),
+        };
+
+        result.unwrap_or_else(|e| error_reply(&e))
+    }
+
+    async fn check(&self, name: &str) -> Result<Reply, Error> {
+        let check = DomainCheck::new(vec![name]);
+        let response = self.pool.transact(&check, &self.tr_id()).await?;
+        let item = response
+            .res_data
+            .and_then(|data| data.check_data.domain_list.into_iter().next());
+
+        Ok(match item {
+            Some(item) => success(&response.result, to_json(&item)),
+            None => missing_data(),
+        })
+    }
+
+    async fn info(&self, name: &str, auth: Option<&str>) -> Result<Reply, Error> {
+        let info = DomainInfo::new(name, auth);
+        let response = self.pool.transact(&info, &self.tr_id()).await?;
+        Ok(match response.res_data {
+            Some(data) => success(&response.result, to_json(&data)),
+            None => missing_data(),
+        })
+    }
+
+    async fn create(&self, body: &[u8]) -> Result<Reply, Error> {
+        let request = match parse::<CreateDomain>(body) {
+            Ok(request) => request,
+            Err(reply) => return Ok(reply),
+        };
+
+        let contacts = request
+            .contacts
+            .iter()
+            .map(|contact| DomainContact {
+                contact_type: contact.contact_type.clone(),
+                id: contact.id.as_str().into(),
+            })
+            .collect::<Vec<_>>();
+        let ns = request
+            .ns
+            .iter()
+            .map(|host| host.as_str().into())
+            .collect::<Vec<StringValue>>();
+
+        let create = DomainCreate::new(
+            &request.name,
+            Period::years(request.period),
+            if ns.is_empty() {
+                None
+            } else {
+                Some(HostList::HostObjList(HostObjList { hosts: ns.into() }))
+            },
+            request.registrant.as_deref(),
+            &request.auth,
+            if contacts.is_empty() {
+                None
+            } else {
+                Some(&contacts)
+            },
+        );
+
+        let response = self.pool.transact(&create, &self.tr_id()).await?;
+        Ok(match response.res_data {
+            Some(data) => {
+                let json = to_json(&data);
+                match response.result.code {
+                    1000 => (StatusCode::CREATED, json),
+                    _ => success(&response.result, json),
+                }
+            }
+            None => success(&response.result, json!({ "name": request.name })),
+        })
+    }
+
+    async fn renew(&self, name: &str, body: &[u8]) -> Result<Reply, Error> {
+        let request = match parse::<RenewDomain>(body) {
+            Ok(request) => request,
+            Err(reply) => return Ok(reply),
+        };
+
+        let renew = DomainRenew::new(name, request.expiry, Period::years(request.period));
+        let response = self.pool.transact(&renew, &self.tr_id()).await?;
+        Ok(match response.res_data {
+            Some(data) => success(&response.result, to_json(&data)),
+            None => success(&response.result, json!({ "name": name })),
+        })
+    }
+
+    async fn delete(&self, name: &str) -> Result<Reply, Error> {
+        let delete = DomainDelete::new(name);
+        let response = self.pool.transact(&delete, &self.tr_id()).await?;
+        Ok(success(&response.result, json!({ "name": name })))
+    }
+
+    fn tr_id(&self) -> String {
+        let n = self.client_tr_id.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{}-{}", self.prefix, n)
+    }
+}
+
+/// Returns the HTTP status for the EPP result `code`
+///
+/// Successful results map to 200, or 202 for results pending action by the registry. Errors map
+/// by their cause: 400 for malformed commands, 403 for authorization failures, 404 for missing
+/// objects, 409 for conflicts with the state of an object, 422 for policy violations, 501 for
+/// unimplemented commands, and 502 or 503 for failures of the registry.
+pub fn http_status(code: u16) -> StatusCode {
+    match code {
+        1001 => StatusCode::ACCEPTED,
+        1000..=1999 => StatusCode::OK,
+        2001 | 2003 | 2004 | 2005 => StatusCode::BAD_REQUEST,
+        2101 | 2102 | 2103 | 2307 => StatusCode::NOT_IMPLEMENTED,
+        2104 => StatusCode::PAYMENT_REQUIRED,
+        2200..=2202 => StatusCode::FORBIDDEN,
+        2303 => StatusCode::NOT_FOUND,
+        2105 | 2106 | 2301 | 2302 | 2304 | 2305 => StatusCode::CONFLICT,
+        2306 | 2308 => StatusCode::UNPROCESSABLE_ENTITY,
+        2400 | 2501 | 2502 => StatusCode::BAD_GATEWAY,
+        2500 => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Replies to a successful command with `json`
+fn success(result: &EppResult, json: Value) -> Reply {
+    (http_status(result.code), json)
+}
+
+/// Replies to a failure of the gateway itself
+fn failure(status: StatusCode, message: &str) -> Reply {
+    (status, json!({ "error": { "message": message } }))
+}
+
+/// Replies to a command that failed, with the registry's result if there is one
+fn error_reply(error: &Error) -> Reply {
+    match error {
+        Error::Command(status) => {
+            let result = &status.result;
+            (
+                http_status(result.code),
+                json!({ "error": to_json(result) }),
+            )
+        }
+        Error::Io(e) => failure(StatusCode::SERVICE_UNAVAILABLE, &e.to_string()),
+        Error::Xml(e) => failure(StatusCode::BAD_GATEWAY, &e.to_string()),
+        Error::Invalid(e) => failure(StatusCode::BAD_REQUEST, e),
+        Error::Other(e) => failure(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn missing_data() -> Reply {
+    failure(StatusCode::BAD_GATEWAY, "response contains no data")
+}
+
+/// Parses a JSON request body, or replies with 400 if it's malformed
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, Reply> {
+    serde_json::from_slice(body).map_err(|e| failure(StatusCode::BAD_REQUEST, &e.to_string()))
+}
+
+/// Returns the JSON representation of `value`
+fn to_json<T: ToJson + ?Sized>(value: &T) -> Value {
+    serde_json::to_value(Json(value)).expect("JSON representations have string keys")
+}
+
+/// Compares two byte strings in time independent of where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use super::{http_status, ContactRole, CreateDomain, GatewayConfig, RenewDomain};
+
+    #[test]
+    fn statuses() {
+        assert_eq!(http_status(1000), StatusCode::OK);
+        assert_eq!(http_status(1001), StatusCode::ACCEPTED);
+        assert_eq!(http_status(2005), StatusCode::BAD_REQUEST);
+        assert_eq!(http_status(2202), StatusCode::FORBIDDEN);
+        assert_eq!(http_status(2302), StatusCode::CONFLICT);
+        assert_eq!(http_status(2303), StatusCode::NOT_FOUND);
+        assert_eq!(http_status(2306), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(http_status(2400), StatusCode::BAD_GATEWAY);
+        assert_eq!(http_status(2002), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn bodies() {
+        let create: CreateDomain = serde_json::from_str(
+            r#"{"name": "eppdev.com", "contacts": [{"type": "tech", "id": "c1"}], "auth": "pw"}"#,
+        )
+        .unwrap();
+        assert_eq!(create.period, 1);
+        assert_eq!(create.contacts[0].contact_type, ContactRole::Tech);
+        assert!(create.ns.is_empty());
+        assert!(serde_json::from_str::<CreateDomain>(
+            r#"{"name": "eppdev.com", "contacts": [{"type": "", "id": "c1"}], "auth": "pw"}"#,
+        )
+        .is_err());
+
+        let renew: RenewDomain = serde_json::from_str(r#"{"expiry": "2022-07-23"}"#).unwrap();
+        assert_eq!(renew.expiry.to_string(), "2022-07-23");
+        assert!(serde_json::from_str::<RenewDomain>(r#"{"expiry": "tomorrow"}"#).is_err());
+        assert!(serde_json::from_str::<CreateDomain>(r#"{"name": "eppdev.com"}"#).is_err());
+    }
+
+    #[test]
+    fn config() {
+        let config: GatewayConfig = toml::from_str(
+            r#"
+            [registry]
+            name = "example"
+            host = "epp.example.com"
+            username = "registrar"
+            password = "secret"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.sessions, 1);
+        assert_eq!(config.queue_depth, 16);
+        assert_eq!(config.listen, "127.0.0.1:8080".parse().unwrap());
+        assert!(config.tokens.is_empty());
+        assert_eq!(config.registry.port, 700);
+    }
+}
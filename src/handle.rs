@@ -0,0 +1,264 @@
+//! A cloneable handle to a registry connection owned by a background task
+//!
+//! [`EppClient::transact()`](crate::EppClient::transact) takes `&mut self`, so sharing a single
+//! client between tasks requires external locking. An [`EppHandle`] instead hands the connection
+//! to a background task and submits commands to it over a bounded channel. Handles are cheap to
+//! clone, `Send + Sync`, and every command gets its own response delivered back to the caller.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::net::ToSocketAddrs;
+//!
+//! use epp_client::EppClient;
+//! use epp_client::domain::DomainCheck;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! // Create an instance of EppClient and move it to a background task
+//! let host = "example.com";
+//! let addr = (host, 7000).to_socket_addrs().unwrap().next().unwrap();
+//! let client = EppClient::connect("registry_name".to_string(), addr, host, None).await.unwrap();
+//! let handle = client.into_handle(16).unwrap();
+//!
+//! // Clones of the handle can be moved to other tasks
+//! let other = handle.clone();
+//! tokio::spawn(async move {
+//!     let domain_check = DomainCheck::new(vec!["eppdev.com", "eppdev.net"]);
+//!     other.transact(&domain_check, "transaction-id").await.unwrap();
+//! });
+//!
+//! // Close the connection once all queued commands have been sent
+//! handle.shutdown().await.unwrap();
+//! }
+//! ```
+
+use std::io;
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+
+use crate::client::RequestData;
+use crate::connection::EppConnection;
+use crate::error::Error;
+use crate::hello::{Greeting, GreetingDocument, HelloDocument};
+use crate::middleware::{self, Middleware, Reply, Request};
+use crate::request::{Command, Extension, Transaction};
+use crate::response::Response;
+use crate::xml::EppXml;
+
+/// A cloneable, `Send + Sync` handle to a registry connection running on a background task
+///
+/// Commands submitted through any clone of the handle are queued and sent to the registry one
+/// at a time, in the order they were received. Once the queue holds `queue_depth` commands,
+/// further submissions wait until a slot frees up.
+#[derive(Clone, Debug)]
+pub struct EppHandle {
+    registry: Arc<str>,
+    greeting: Arc<str>,
+    sender: mpsc::Sender<Message>,
+}
+
+impl EppHandle {
+    /// Moves `connection` to a new background task and returns a handle to it
+    ///
+    /// Must be called from within a tokio runtime.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn spawn<IO>(
+        connection: EppConnection<IO>,
+        middleware: Vec<Arc<dyn Middleware>>,
+        queue_depth: usize,
+    ) -> Result<Self, Error>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        check_queue_depth(queue_depth)?;
+        let (sender, receiver) = mpsc::channel(queue_depth);
+        let handle = Self {
+            registry: connection.registry.as_str().into(),
+            greeting: connection.greeting.as_str().into(),
+            sender,
+        };
+
+        tokio::spawn(run(connection, middleware, receiver));
+        Ok(handle)
+    }
+
+    /// Executes an EPP Hello call and returns the response as a `Greeting`
+    pub async fn hello(&self) -> Result<Greeting, Error> {
+        let hello_xml = HelloDocument::default().serialize()?;
+
+        let response = self.transact_xml(&hello_xml).await?;
+
+        Ok(GreetingDocument::deserialize(&response)?.data)
+    }
+
+    /// Queues an EPP command for the registry and waits for its response
+    pub async fn transact<'a, C, E>(
+        &self,
+        data: impl Into<RequestData<'a, C, E>> + 'a,
+        id: &str,
+    ) -> Result<Response<C::Response, E::Response>, Error>
+    where
+        C: Transaction<E> + Command + 'a,
+        E: Extension + 'a,
+    {
+        let data = data.into();
+        let request = Request {
+            registry: self.registry.to_string(),
+            command: C::COMMAND,
+            object: C::OBJECT,
+            client_tr_id: id.to_owned(),
+            xml: <C as Transaction<E>>::serialize_request(data.command, data.extension, id)?,
+        };
+
+        let (response, receiver) = oneshot::channel();
+        self.send(Message::Command { request, response }).await?;
+        let reply = receiver.await.map_err(|_| self.closed())??;
+
+        C::deserialize_response(&reply.xml)
+    }
+
+    /// Queues raw EPP XML for the registry and returns the raw EPP XML response to it.
+    /// Raw XML bypasses any middleware registered on the client
+    pub async fn transact_xml(&self, xml: &str) -> Result<String, Error> {
+        let (response, receiver) = oneshot::channel();
+        let message = Message::Transact {
+            xml: xml.to_owned(),
+            response,
+        };
+
+        self.send(message).await?;
+        receiver.await.map_err(|_| self.closed())?
+    }
+
+    /// Returns the greeting received on establishment of the connection in raw xml form
+    pub fn xml_greeting(&self) -> String {
+        String::from(&*self.greeting)
+    }
+
+    /// Returns the greeting received on establishment of the connection as a `Greeting`
+    #[allow(clippy::result_large_err)]
+    pub fn greeting(&self) -> Result<Greeting, Error> {
+        GreetingDocument::deserialize(&self.greeting).map(|obj| obj.data)
+    }
+
+    /// The name of the registry the session is connected to
+    pub(crate) fn registry(&self) -> &str {
+        &self.registry
+    }
+
+    /// Returns `true` once the background task has stopped accepting commands
+    ///
+    /// This happens after a shutdown, once all handles but this one have been dropped, or when
+    /// the connection to the registry failed with an I/O error.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Signals the background task to close the connection
+    ///
+    /// Commands queued before the shutdown signal are still sent to the registry; commands
+    /// submitted afterwards through any clone of the handle fail with a `NotConnected` error.
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        let (response, receiver) = oneshot::channel();
+        self.send(Message::Shutdown { response }).await?;
+        receiver.await.map_err(|_| self.closed())?
+    }
+
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        self.sender.send(message).await.map_err(|_| self.closed())
+    }
+
+    fn closed(&self) -> Error {
+        io::Error::new(
+            io::ErrorKind::NotConnected,
+            format!("{}: connection closed", self.registry),
+        )
+        .into()
+    }
+}
+
+/// Messages sent from an `EppHandle` to the background task
+#[derive(Debug)]
+enum Message {
+    Command {
+        request: Request,
+        response: oneshot::Sender<Result<Reply, Error>>,
+    },
+    Transact {
+        xml: String,
+        response: oneshot::Sender<Result<String, Error>>,
+    },
+    Shutdown {
+        response: oneshot::Sender<Result<(), Error>>,
+    },
+}
+
+/// Sends queued commands to the registry until shutdown is requested, all handles are dropped
+/// or the connection fails
+async fn run<IO: AsyncRead + AsyncWrite + Unpin + Send>(
+    mut connection: EppConnection<IO>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    mut receiver: mpsc::Receiver<Message>,
+) {
+    while let Some(message) = receiver.recv().await {
+        let failed = match message {
+            Message::Command { request, response } => {
+                let result = middleware::dispatch(&middleware, &mut connection, &request).await;
+                let failed = io_error(&result);
+                if response.send(result).is_err() {
+                    debug!("{}: response receiver dropped", connection.registry);
+                }
+                failed
+            }
+            Message::Transact { xml, response } => {
+                let result = connection.transact(&xml).await;
+                let failed = io_error(&result);
+                if response.send(result).is_err() {
+                    debug!("{}: response receiver dropped", connection.registry);
+                }
+                failed
+            }
+            Message::Shutdown { response } => {
+                receiver.close();
+                let result = connection.shutdown().await;
+                let _ = response.send(result);
+                return;
+            }
+        };
+
+        // The session can't be trusted after a broken exchange; the commands still queued fail
+        // with a `NotConnected` error once the receiver is dropped
+        if failed {
+            warn!(
+                "{}: Connection failed, closing session",
+                connection.registry
+            );
+            receiver.close();
+            return;
+        }
+    }
+
+    info!("{}: All handles dropped", connection.registry);
+    if let Err(e) = connection.shutdown().await {
+        debug!("{}: Failed to close connection: {}", connection.registry, e);
+    }
+}
+
+/// Checks that a handle can queue `queue_depth` commands, which has to be at least one
+#[allow(clippy::result_large_err)]
+pub(crate) fn check_queue_depth(queue_depth: usize) -> Result<(), Error> {
+    if queue_depth == 0 {
+        return Err(Error::Invalid("queue depth must be at least 1".into()));
+    }
+
+    Ok(())
+}
+
+fn io_error<T>(result: &Result<T, Error>) -> bool {
+    matches!(result, Err(Error::Io(_)))
+}
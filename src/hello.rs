@@ -1,9 +1,12 @@
 use std::fmt::Debug;
 
-use serde::{Deserialize, Deserializer, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::common::{Options, ServiceExtension, Services, StringValue, EPP_XMLNS};
+use crate::request::{EPP_LANG, EPP_VERSION};
 use crate::xml::EppXml;
+use crate::{contact, domain, host};
 
 // Request
 
@@ -31,14 +34,14 @@ impl EppXml for HelloDocument {}
 // Response
 
 /// Type for data within the <svcMenu> section of an EPP greeting
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ServiceMenu {
     pub options: Options<'static>,
     pub services: Services<'static>,
 }
 
-/// Simplified service menu type for deserialization to `ServiceMenu` type from EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+/// Simplified service menu type for (de)serialization of the `ServiceMenu` type to and from EPP greeting XML
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 struct FlattenedServiceMenu {
     pub version: StringValue<'static>,
     pub lang: StringValue<'static>,
@@ -48,7 +51,7 @@ struct FlattenedServiceMenu {
     pub svc_ext: Option<ServiceExtension<'static>>,
 }
 
-impl<'a, 'de: 'a> Deserialize<'de> for ServiceMenu {
+impl<'de> Deserialize<'de> for ServiceMenu {
     /// Deserializes the <svcMenu> data to the `ServiceMenu` type
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -71,32 +74,48 @@ impl<'a, 'de: 'a> Deserialize<'de> for ServiceMenu {
     }
 }
 
+impl Serialize for ServiceMenu {
+    /// Serializes the `ServiceMenu` type to flat <svcMenu> data
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        FlattenedServiceMenu {
+            version: self.options.version.clone(),
+            lang: self.options.lang.clone(),
+            obj_uris: self.services.obj_uris.clone(),
+            svc_ext: self.services.svc_ext.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
 /// Type corresponding to <all> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct All;
 
 /// Type corresponding to <none> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct NoAccess;
 
 /// Type corresponding to <null> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Null;
 
 /// Type corresponding to <personal> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Personal;
 
 /// Type corresponding to <personalAndOther> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct PersonalAndOther;
 
 /// Type corresponding to <other> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Other;
 
 /// Type corresponding to possible <retention> type values
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub enum AccessType {
     /// Data for the <all> tag
     #[serde(rename = "all")]
@@ -118,15 +137,42 @@ pub enum AccessType {
     Other(Other),
 }
 
+impl Serialize for AccessType {
+    /// Serializes the access type to an empty tag
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (index, variant) = match self {
+            AccessType::All(_) => (0, "all"),
+            AccessType::NoAccess(_) => (1, "none"),
+            AccessType::Null(_) => (2, "null"),
+            AccessType::Personal(_) => (3, "personal"),
+            AccessType::PersonalAndOther(_) => (4, "personalAndOther"),
+            AccessType::Other(_) => (5, "other"),
+        };
+        serializer.serialize_unit_variant("AccessType", index, variant)
+    }
+}
+
 /// Type corresponding to <access> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Access {
     #[serde(flatten)]
     pub ty: AccessType,
 }
 
+impl Serialize for Access {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("access", &self.ty)
+    }
+}
+
 /// Type corresponding to possible <purpose> type values
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum PurposeType {
     /// Data for the <admin> tag
     #[serde(rename = "admin")]
@@ -143,14 +189,14 @@ pub enum PurposeType {
 }
 
 /// Type corresponding to <purpose> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Purpose {
     #[serde(rename = "$value")]
     pub purpose: Vec<PurposeType>,
 }
 
 /// Type corresponding to possible <purpose> type values
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum RecipientType {
     /// Data for the <other> tag
     #[serde(rename = "other")]
@@ -170,34 +216,34 @@ pub enum RecipientType {
 }
 
 /// Type corresponding to <recipeint> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Recipient {
     #[serde(rename = "$value")]
     pub recipient: Vec<RecipientType>,
 }
 
 /// Type corresponding to <business> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Business;
 
 /// Type corresponding to <indefinite> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Indefinite;
 
 /// Type corresponding to <legal> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Legal;
 
 /// Type corresponding to <none> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct No;
 
 /// Type corresponding to <stated> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Stated;
 
 /// Type corresponding to possible <retention> type values
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub enum RetentionType {
     /// Data for the <business> tag
     #[serde(rename = "business")]
@@ -216,15 +262,41 @@ pub enum RetentionType {
     Stated(Stated),
 }
 
+impl Serialize for RetentionType {
+    /// Serializes the retention type to an empty tag
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (index, variant) = match self {
+            RetentionType::Business(_) => (0, "business"),
+            RetentionType::Indefinite(_) => (1, "indefinite"),
+            RetentionType::Legal(_) => (2, "legal"),
+            RetentionType::No(_) => (3, "none"),
+            RetentionType::Stated(_) => (4, "stated"),
+        };
+        serializer.serialize_unit_variant("RetentionType", index, variant)
+    }
+}
+
 /// Type corresponding to <retention> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Retention {
     #[serde(flatten)]
     pub ty: RetentionType,
 }
 
+impl Serialize for Retention {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("retention", &self.ty)
+    }
+}
+
 /// Type corresponding to <statement> in the EPP greeting XML (pending more compliant implementation)
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Statement {
     /// Data for the <purpose> tag
     pub purpose: Purpose,
@@ -235,21 +307,21 @@ pub struct Statement {
 }
 
 /// Type corresponding to <absolute> value in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Absolute {
     #[serde(rename = "$value")]
     pub absolute: StringValue<'static>,
 }
 
 /// Type corresponding to <relative> value in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Relative {
     #[serde(rename = "$value")]
     pub relative: StringValue<'static>,
 }
 
 /// Type corresponding to possible <expiry> type values
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub enum ExpiryType {
     /// Data for the <absolute> tag
     #[serde(rename = "absolute")]
@@ -259,15 +331,47 @@ pub enum ExpiryType {
     Relative(Relative),
 }
 
+impl Serialize for ExpiryType {
+    /// Serializes the expiry type to a tag containing its value
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ExpiryType::Absolute(absolute) => serializer.serialize_newtype_variant(
+                "ExpiryType",
+                0,
+                "absolute",
+                &absolute.absolute.to_string(),
+            ),
+            ExpiryType::Relative(relative) => serializer.serialize_newtype_variant(
+                "ExpiryType",
+                1,
+                "relative",
+                &relative.relative.to_string(),
+            ),
+        }
+    }
+}
+
 /// Type corresponding to <expiry> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct Expiry {
     #[serde(flatten)]
     pub ty: ExpiryType,
 }
 
+impl Serialize for Expiry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct("expiry", &self.ty)
+    }
+}
+
 /// Type corresponding to <dcp> in the EPP greeting XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Dcp {
     /// Data for the <access> tag
     pub access: Access,
@@ -277,16 +381,16 @@ pub struct Dcp {
     pub expiry: Option<Expiry>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 /// Type corresponding to the <greeting> tag in the EPP greeting XML
 pub struct Greeting {
     /// The service ID
-    #[serde(rename = "svID")]
+    #[serde(rename = "svID", serialize_with = "element")]
     pub service_id: String,
     /// The date from the EPP server
-    #[serde(rename = "svDate")]
-    pub service_date: String,
+    #[serde(rename = "svDate", with = "crate::common::datetime")]
+    pub service_date: DateTime<Utc>,
     /// Data under the <svcMenu> element
     #[serde(rename = "svcMenu")]
     pub svc_menu: ServiceMenu,
@@ -294,17 +398,150 @@ pub struct Greeting {
     pub dcp: Dcp,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl Greeting {
+    /// Returns a builder for the greeting of a server with the given service ID
+    pub fn builder(service_id: &str) -> GreetingBuilder {
+        GreetingBuilder {
+            service_id: service_id.to_owned(),
+            version: EPP_VERSION.to_owned(),
+            lang: EPP_LANG.to_owned(),
+            obj_uris: vec![
+                host::XMLNS.to_owned(),
+                contact::XMLNS.to_owned(),
+                domain::XMLNS.to_owned(),
+            ],
+            ext_uris: Vec::new(),
+            dcp: None,
+        }
+    }
+}
+
+/// Serializes a string field as an element rather than an attribute
+fn element<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_newtype_struct("element", value)
+}
+
+/// Builds the `Greeting` sent by an EPP server
+///
+/// The greeting announces the host, contact and domain object services unless configured
+/// otherwise, and a data collection policy granting access to all data for administrative and
+/// provisioning purposes.
+#[derive(Clone, Debug)]
+pub struct GreetingBuilder {
+    service_id: String,
+    version: String,
+    lang: String,
+    obj_uris: Vec<String>,
+    ext_uris: Vec<String>,
+    dcp: Option<Dcp>,
+}
+
+impl GreetingBuilder {
+    /// Sets the protocol version, `1.0` by default
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = version.to_owned();
+        self
+    }
+
+    /// Sets the language, `en` by default
+    pub fn lang(mut self, lang: &str) -> Self {
+        self.lang = lang.to_owned();
+        self
+    }
+
+    /// Replaces the announced object services
+    pub fn objects(mut self, uris: &[&str]) -> Self {
+        self.obj_uris = uris.iter().map(|&uri| uri.to_owned()).collect();
+        self
+    }
+
+    /// Announces an extension service, e.g. `rgp::XMLNS`
+    pub fn extension(mut self, uri: &str) -> Self {
+        self.ext_uris.push(uri.to_owned());
+        self
+    }
+
+    /// Sets the data collection policy
+    pub fn dcp(mut self, dcp: Dcp) -> Self {
+        self.dcp = Some(dcp);
+        self
+    }
+
+    /// Builds the greeting, dated now
+    pub fn build(self) -> Greeting {
+        let ext_uris = if self.ext_uris.is_empty() {
+            None
+        } else {
+            Some(ServiceExtension {
+                ext_uris: Some(self.ext_uris.into_iter().map(Into::into).collect()),
+            })
+        };
+
+        Greeting {
+            service_id: self.service_id,
+            service_date: Utc::now(),
+            svc_menu: ServiceMenu {
+                options: Options {
+                    version: self.version.into(),
+                    lang: self.lang.into(),
+                },
+                services: Services {
+                    obj_uris: self.obj_uris.into_iter().map(Into::into).collect(),
+                    svc_ext: ext_uris,
+                },
+            },
+            dcp: self.dcp.unwrap_or(Dcp {
+                access: Access {
+                    ty: AccessType::All(All),
+                },
+                statement: vec![Statement {
+                    purpose: Purpose {
+                        purpose: vec![PurposeType::Admin, PurposeType::Prov],
+                    },
+                    recipient: Recipient {
+                        recipient: vec![RecipientType::Ours, RecipientType::Public],
+                    },
+                    retention: Retention {
+                        ty: RetentionType::Stated(Stated),
+                    },
+                }],
+                expiry: None,
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename = "epp")]
 pub struct GreetingDocument {
+    #[serde(skip_deserializing, default = "epp_xmlns")]
+    xmlns: &'static str,
     #[serde(rename = "greeting")]
     pub data: Greeting,
 }
 
+impl GreetingDocument {
+    pub fn new(data: Greeting) -> Self {
+        Self {
+            xmlns: EPP_XMLNS,
+            data,
+        }
+    }
+}
+
+fn epp_xmlns() -> &'static str {
+    EPP_XMLNS
+}
+
 impl EppXml for GreetingDocument {}
 
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeZone, Utc};
+
     use super::{ExpiryType, GreetingDocument, HelloDocument, Relative};
     use crate::tests::get_xml;
     use crate::xml::EppXml;
@@ -323,7 +560,10 @@ mod tests {
         let object = GreetingDocument::deserialize(xml.as_str()).unwrap();
 
         assert_eq!(object.data.service_id, "ISPAPI EPP Server");
-        assert_eq!(object.data.service_date, "2021-07-25T14:51:17.0Z");
+        assert_eq!(
+            object.data.service_date,
+            Utc.with_ymd_and_hms(2021, 7, 25, 14, 51, 17).unwrap()
+        );
         assert_eq!(object.data.svc_menu.options.version, "1.0".into());
         assert_eq!(object.data.svc_menu.options.lang, "en".into());
         assert_eq!(object.data.svc_menu.services.obj_uris.len(), 4);
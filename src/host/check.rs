@@ -1,10 +1,12 @@
 //! Types for EPP host check request
 
+use std::borrow::Cow;
 use std::fmt::Debug;
 
-use super::XMLNS;
-use crate::common::{NoExtension, StringValue};
+use super::{xmlns, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
+use crate::Error;
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for HostCheck<'a> {}
@@ -12,11 +14,20 @@ impl<'a> Transaction<NoExtension> for HostCheck<'a> {}
 impl<'a> Command for HostCheck<'a> {
     type Response = HostCheckResponse;
     const COMMAND: &'static str = "check";
+    const OBJECT: Option<&'static str> = Some("host");
+
+    #[allow(clippy::result_large_err)]
+    fn validate(&self) -> Result<(), Error> {
+        self.list
+            .hosts
+            .iter()
+            .try_for_each(|name| DomainName::validate(name.as_ref()))
+    }
 }
 
 impl<'a> HostCheck<'a> {
     pub fn new(hosts: &[&'a str]) -> Self {
-        let hosts = hosts.iter().map(|&d| d.into()).collect();
+        let hosts = hosts.iter().map(|&d| DomainName::normalize(d)).collect();
 
         Self {
             list: HostList {
@@ -30,60 +41,64 @@ impl<'a> HostCheck<'a> {
 // Request
 
 /// Type for data under the host &lt;check&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostList<'a> {
     /// XML namespace for host commands
-    #[serde(rename = "xmlns:host")]
+    #[serde(rename = "xmlns:host", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// List of hosts to be checked for availability
-    #[serde(rename = "host:name")]
+    #[serde(rename = "host:name", alias = "name")]
     pub hosts: Vec<StringValue<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;check&gt; command for hosts
 pub struct HostCheck<'a> {
     /// The instance holding the list of hosts to be checked
-    #[serde(rename = "host:check")]
-    list: HostList<'a>,
+    #[serde(rename = "host:check", alias = "check")]
+    pub list: HostList<'a>,
 }
 
 // Response
 
 /// Type that represents the &lt;name&gt; tag for host check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostAvailable {
     /// The host name
     #[serde(rename = "$value")]
-    pub name: StringValue<'static>,
+    pub name: Cow<'static, str>,
     /// The host (un)availability
     #[serde(rename = "avail")]
     pub available: u16,
 }
 
 /// Type that represents the &lt;cd&gt; tag for host check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCheckDataItem {
     /// Data under the &lt;name&gt; tag
-    #[serde(rename = "name")]
+    #[serde(rename = "host:name", alias = "name")]
     pub host: HostAvailable,
     /// The reason for (un)availability
+    #[serde(rename = "host:reason", alias = "reason")]
     pub reason: Option<StringValue<'static>>,
 }
 
 /// Type that represents the &lt;chkData&gt; tag for host check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCheckData {
+    /// XML namespace for host response data
+    #[serde(rename = "xmlns:host")]
+    pub xmlns: String,
     /// Data under the &lt;cd&gt; tag
-    #[serde(rename = "cd")]
+    #[serde(rename = "host:cd", alias = "cd")]
     pub host_list: Vec<HostCheckDataItem>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for host check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCheckResponse {
     /// Data under the &lt;chkData&gt; tag
-    #[serde(rename = "chkData")]
+    #[serde(rename = "host:chkData", alias = "chkData")]
     pub check_data: HostCheckData,
 }
 
@@ -119,13 +134,10 @@ mod tests {
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(
             result.check_data.host_list[0].host.name,
-            "host1.eppdev-1.com".into()
+            "host1.eppdev-1.com"
         );
         assert_eq!(result.check_data.host_list[0].host.available, 1);
-        assert_eq!(
-            result.check_data.host_list[1].host.name,
-            "ns1.testing.com".into()
-        );
+        assert_eq!(result.check_data.host_list[1].host.name, "ns1.testing.com");
         assert_eq!(result.check_data.host_list[1].host.available, 0);
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
@@ -1,8 +1,12 @@
 //! Types for EPP host create request
 
-use super::XMLNS;
-use crate::common::{HostAddr, NoExtension, StringValue};
+use std::borrow::Cow;
+
+use super::{validate_glue, xmlns, XMLNS};
+use crate::common::{DomainName, HostAddr, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
+use crate::Error;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for HostCreate<'a> {}
@@ -10,6 +14,13 @@ impl<'a> Transaction<NoExtension> for HostCreate<'a> {}
 impl<'a> Command for HostCreate<'a> {
     type Response = HostCreateResponse;
     const COMMAND: &'static str = "create";
+    const OBJECT: Option<&'static str> = Some("host");
+
+    fn validate(&self) -> Result<(), Error> {
+        let name = self.host.name.as_ref();
+        DomainName::validate(name)?;
+        validate_glue(name, self.host.addresses.as_deref(), self.glue_zones)
+    }
 }
 
 impl<'a> HostCreate<'a> {
@@ -17,74 +28,103 @@ impl<'a> HostCreate<'a> {
         Self {
             host: HostCreateRequestData {
                 xmlns: XMLNS,
-                name: host.into(),
-                addresses,
+                name: DomainName::normalize(host),
+                addresses: addresses.map(Into::into),
             },
+            glue_zones: &[],
         }
     }
+
+    /// Sets the zones the host may have addresses in, such as its superordinate domain or the
+    /// registry's TLDs
+    ///
+    /// Addresses are glue and RFC 5732 only allows them for hosts within a zone the server is
+    /// authoritative for, so a host given addresses has to lie within one of `zones`.
+    pub fn glue_zones(mut self, zones: &'a [&'a str]) -> Self {
+        self.glue_zones = zones;
+        self
+    }
 }
 
 // Request
 
 /// Type for data under the host &lt;create&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCreateRequestData<'a> {
     /// XML namespace for host commands
-    #[serde(rename = "xmlns:host")]
+    #[serde(rename = "xmlns:host", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The name of the host to be created
-    #[serde(rename = "host:name")]
+    #[serde(rename = "host:name", alias = "name")]
     pub name: StringValue<'a>,
     /// The list of IP addresses for the host
-    #[serde(rename = "host:addr")]
-    pub addresses: Option<&'a [HostAddr<'a>]>,
+    #[serde(rename = "host:addr", alias = "addr")]
+    pub addresses: Option<Cow<'a, [HostAddr]>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;create&gt; command for hosts
 pub struct HostCreate<'a> {
     /// The instance holding the data for the host to be created
-    #[serde(rename = "host:create")]
-    host: HostCreateRequestData<'a>,
+    #[serde(rename = "host:create", alias = "create")]
+    pub host: HostCreateRequestData<'a>,
+    /// The zones the host may have addresses in, checked before the command is sent
+    #[serde(skip)]
+    pub glue_zones: &'a [&'a str],
 }
 
 // Response
 
 /// Type that represents the &lt;creData&gt; tag for host create response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCreateData {
+    /// XML namespace for host response data
+    #[serde(rename = "xmlns:host")]
+    pub xmlns: String,
     /// The host name
+    #[serde(rename = "host:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The host creation date
-    #[serde(rename = "crDate")]
-    pub created_at: StringValue<'static>,
+    #[serde(
+        rename = "host:crDate",
+        alias = "crDate",
+        with = "crate::common::datetime"
+    )]
+    pub created_at: DateTime<Utc>,
 }
 
 /// Type that represents the &lt;resData&gt; tag for host check response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostCreateResponse {
     /// Data under the &lt;creData&gt; tag
-    #[serde(rename = "creData")]
+    #[serde(rename = "host:creData", alias = "creData")]
     pub create_data: HostCreateData,
 }
 
 #[cfg(test)]
 mod tests {
     use super::HostCreate;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
     use crate::common::{HostAddr, NoExtension};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
         let xml = get_xml("request/host/create.xml").unwrap();
 
         let addresses = &[
-            HostAddr::new("v4", "29.245.122.14"),
-            HostAddr::new("v6", "2404:6800:4001:801::200e"),
+            HostAddr::from(Ipv4Addr::new(29, 245, 122, 14)),
+            HostAddr::from(Ipv6Addr::new(
+                0x2404, 0x6800, 0x4001, 0x801, 0, 0, 0, 0x200e,
+            )),
         ];
 
-        let object = HostCreate::new("host1.eppdev-1.com", Some(addresses));
+        let object =
+            HostCreate::new("host1.eppdev-1.com", Some(addresses)).glue_zones(&["eppdev-1.com"]);
 
         let serialized =
             <HostCreate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -93,6 +133,36 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn out_of_bailiwick_glue() {
+        let addresses = &[HostAddr::from(Ipv4Addr::new(29, 245, 122, 14))];
+        let serialize = |object: &HostCreate| {
+            <HostCreate as Transaction<NoExtension>>::serialize_request(object, None, CLTRID)
+        };
+
+        let object = HostCreate::new("ns1.eppdev.net", Some(addresses));
+        assert!(matches!(serialize(&object), Err(Error::Invalid(_))));
+
+        let object = object.glue_zones(&["eppdev.com", "eppdev.org"]);
+        match serialize(&object) {
+            Err(Error::Invalid(e)) => assert_eq!(
+                e,
+                "host ns1.eppdev.net is outside of eppdev.com, eppdev.org and can't have addresses"
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Zones are compared label by label, and may also be TLDs
+        let object = HostCreate::new("ns1.noteppdev.com", Some(addresses));
+        assert!(serialize(&object.glue_zones(&["eppdev.com"])).is_err());
+        let object = HostCreate::new("ns1.eppdev.com", Some(addresses));
+        assert!(serialize(&object.glue_zones(&["com"])).is_ok());
+
+        // Hosts without addresses need no zones
+        assert!(serialize(&HostCreate::new("ns1.eppdev.net", None)).is_ok());
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/host/create.xml").unwrap();
@@ -106,7 +176,7 @@ mod tests {
         assert_eq!(result.create_data.name, "host2.eppdev-1.com".into());
         assert_eq!(
             result.create_data.created_at,
-            "2021-07-26T05:28:55.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 26, 5, 28, 55).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
@@ -1,15 +1,21 @@
 //! Types for EPP host delete request
 
-use super::XMLNS;
-use crate::common::{NoExtension, StringValue};
+use super::{xmlns, XMLNS};
+use crate::common::{DomainName, NoExtension, StringValue};
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use crate::Error;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for HostDelete<'a> {}
 
 impl<'a> Command for HostDelete<'a> {
     type Response = ();
     const COMMAND: &'static str = "delete";
+    const OBJECT: Option<&'static str> = Some("host");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.host.name.as_ref())
+    }
 }
 
 impl<'a> HostDelete<'a> {
@@ -17,29 +23,29 @@ impl<'a> HostDelete<'a> {
         Self {
             host: HostDeleteRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
             },
         }
     }
 }
 
 /// Type for data under the host &lt;delete&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostDeleteRequestData<'a> {
     /// XML namespace for host commands
-    #[serde(rename = "xmlns:host")]
+    #[serde(rename = "xmlns:host", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The host to be deleted
-    #[serde(rename = "host:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "host:name", alias = "name")]
+    pub name: StringValue<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;delete&gt; command for hosts
 pub struct HostDelete<'a> {
     /// The instance holding the data for the host to be deleted
-    #[serde(rename = "host:delete")]
-    host: HostDeleteRequestData<'a>,
+    #[serde(rename = "host:delete", alias = "delete")]
+    pub host: HostDeleteRequestData<'a>,
 }
 
 #[cfg(test)]
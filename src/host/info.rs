@@ -1,8 +1,12 @@
 //! Types for EPP host info request
 
-use super::XMLNS;
-use crate::common::{HostAddr, NoExtension, ObjectStatus, StringValue};
+use super::{xmlns, XMLNS};
+use crate::common::{
+    unicode, DomainName, HostAddr, HostStatus, NoExtension, ObjectStatus, StringValue,
+};
 use crate::request::{Command, Transaction};
+use crate::Error;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for HostInfo<'a> {}
@@ -10,6 +14,11 @@ impl<'a> Transaction<NoExtension> for HostInfo<'a> {}
 impl<'a> Command for HostInfo<'a> {
     type Response = HostInfoResponse;
     const COMMAND: &'static str = "info";
+    const OBJECT: Option<&'static str> = Some("host");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.info.name.as_ref())
+    }
 }
 
 impl<'a> HostInfo<'a> {
@@ -17,7 +26,7 @@ impl<'a> HostInfo<'a> {
         Self {
             info: HostInfoRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
             },
         }
     }
@@ -26,73 +35,112 @@ impl<'a> HostInfo<'a> {
 // Request
 
 /// Type for data under the host &lt;info&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostInfoRequestData<'a> {
     /// XML namespace for host commands
-    #[serde(rename = "xmlns:host")]
+    #[serde(rename = "xmlns:host", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The name of the host to be queried
-    #[serde(rename = "host:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "host:name", alias = "name")]
+    pub name: StringValue<'a>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;info&gt; command for hosts
 pub struct HostInfo<'a> {
     /// The instance holding the data for the host query
-    #[serde(rename = "host:info")]
-    info: HostInfoRequestData<'a>,
+    #[serde(rename = "host:info", alias = "info")]
+    pub info: HostInfoRequestData<'a>,
 }
 
 // Response
 
 /// Type that represents the &lt;infData&gt; tag for host info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostInfoResponseData {
+    /// XML namespace for host response data
+    #[serde(rename = "xmlns:host")]
+    pub xmlns: String,
     /// The host name
+    #[serde(rename = "host:name", alias = "name")]
     pub name: StringValue<'static>,
     /// The host ROID
+    #[serde(rename = "host:roid", alias = "roid")]
     pub roid: StringValue<'static>,
     /// The list of host statuses
-    #[serde(rename = "status")]
-    pub statuses: Vec<ObjectStatus<'static>>,
+    #[serde(rename = "host:status", alias = "status")]
+    pub statuses: Vec<ObjectStatus<'static, HostStatus>>,
     /// The list of host IP addresses
-    #[serde(rename = "addr")]
-    pub addresses: Vec<HostAddr<'static>>,
+    #[serde(rename = "host:addr", alias = "addr")]
+    pub addresses: Vec<HostAddr>,
     /// The epp user to whom the host belongs
-    #[serde(rename = "clID")]
+    #[serde(rename = "host:clID", alias = "clID")]
     pub client_id: StringValue<'static>,
     /// THe epp user that created the host
-    #[serde(rename = "crID")]
+    #[serde(rename = "host:crID", alias = "crID")]
     pub creator_id: StringValue<'static>,
     /// The host creation date
-    #[serde(rename = "crDate")]
-    pub created_at: StringValue<'static>,
+    #[serde(
+        rename = "host:crDate",
+        alias = "crDate",
+        with = "crate::common::datetime"
+    )]
+    pub created_at: DateTime<Utc>,
     /// The epp user that last updated the host
-    #[serde(rename = "upID")]
+    #[serde(rename = "host:upID", alias = "upID")]
     pub updater_id: Option<StringValue<'static>>,
     /// The host last update date
-    #[serde(rename = "upDate")]
-    pub updated_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "host:upDate",
+        alias = "upDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub updated_at: Option<DateTime<Utc>>,
     /// The host transfer date
-    #[serde(rename = "trDate")]
-    pub transferred_at: Option<StringValue<'static>>,
+    #[serde(
+        rename = "host:trDate",
+        alias = "trDate",
+        default,
+        with = "crate::common::datetime::option"
+    )]
+    pub transferred_at: Option<DateTime<Utc>>,
+}
+
+impl HostInfoResponseData {
+    /// Returns the U-label form of the host name, for display
+    pub fn name_unicode(&self) -> String {
+        unicode(self.name.as_ref())
+    }
+
+    /// Returns whether a client or server prohibition blocks some transform command on the host
+    pub fn is_locked(&self) -> bool {
+        self.statuses.iter().any(|s| s.status.is_prohibition())
+    }
+
+    /// Returns whether a transform command on the host awaits completion
+    pub fn is_pending(&self) -> bool {
+        self.statuses.iter().any(|s| s.status.is_pending())
+    }
 }
 
 /// Type that represents the &lt;resData&gt; tag for host info response
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostInfoResponse {
     /// Data under the &lt;infData&gt; tag
-    #[serde(rename = "infData")]
+    #[serde(rename = "host:infData", alias = "infData")]
     pub info_data: HostInfoResponseData,
 }
 
 #[cfg(test)]
 mod tests {
     use super::HostInfo;
-    use crate::common::NoExtension;
+    use std::net::IpAddr;
+
+    use crate::common::{HostStatus, NoExtension};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
@@ -119,33 +167,29 @@ mod tests {
         assert_eq!(object.result.message, SUCCESS_MSG.into());
         assert_eq!(result.info_data.name, "host2.eppdev-1.com".into());
         assert_eq!(result.info_data.roid, "UNDEF-ROID".into());
-        assert_eq!(result.info_data.statuses[0].status, "ok".to_string());
-        assert_eq!(
-            *(result.info_data.addresses[0].ip_version.as_ref().unwrap()),
-            "v4".to_string()
-        );
+        assert_eq!(result.info_data.statuses[0].status, HostStatus::Ok);
+        assert!(!result.info_data.is_locked());
         assert_eq!(
             result.info_data.addresses[0].address,
-            "29.245.122.14".to_string()
-        );
-        assert_eq!(
-            *(result.info_data.addresses[1].ip_version.as_ref().unwrap()),
-            "v6".to_string()
+            IpAddr::from([29, 245, 122, 14])
         );
         assert_eq!(
             result.info_data.addresses[1].address,
-            "2404:6800:4001:0801:0000:0000:0000:200e".to_string()
+            IpAddr::from([0x2404, 0x6800, 0x4001, 0x801, 0, 0, 0, 0x200e])
         );
         assert_eq!(result.info_data.client_id, "eppdev".into());
         assert_eq!(result.info_data.creator_id, "creator".into());
-        assert_eq!(result.info_data.created_at, "2021-07-26T05:28:55.0Z".into());
+        assert_eq!(
+            result.info_data.created_at,
+            Utc.with_ymd_and_hms(2021, 7, 26, 5, 28, 55).unwrap()
+        );
         assert_eq!(
             *(result.info_data.updater_id.as_ref().unwrap()),
             "creator".into()
         );
         assert_eq!(
             *(result.info_data.updated_at.as_ref().unwrap()),
-            "2021-07-26T05:28:55.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 26, 5, 28, 55).unwrap()
         );
         assert_eq!(object.tr_ids.client_tr_id.unwrap(), CLTRID.into());
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
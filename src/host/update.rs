@@ -1,15 +1,38 @@
 //! Types for EPP host update request
 
-use super::XMLNS;
-use crate::common::{HostAddr, NoExtension, ObjectStatus, StringValue};
+use std::borrow::Cow;
+
+use super::{validate_glue, xmlns, XMLNS};
+use crate::common::{DomainName, HostAddr, HostStatus, NoExtension, ObjectStatus, StringValue};
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use crate::Error;
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for HostUpdate<'a> {}
 
 impl<'a> Command for HostUpdate<'a> {
     type Response = ();
     const COMMAND: &'static str = "update";
+    const OBJECT: Option<&'static str> = Some("host");
+
+    fn validate(&self) -> Result<(), Error> {
+        DomainName::validate(self.host.name.as_ref())?;
+        if let Some(info) = &self.host.change_info {
+            DomainName::validate(info.name.as_ref())?;
+        }
+
+        // Added addresses belong to the host under its new name, if it's renamed
+        let name = match &self.host.change_info {
+            Some(info) => info.name.as_ref(),
+            None => self.host.name.as_ref(),
+        };
+        let added = self
+            .host
+            .add
+            .as_ref()
+            .and_then(|add| add.addresses.as_deref());
+        validate_glue(name, added, self.glue_zones)
+    }
 }
 
 impl<'a> HostUpdate<'a> {
@@ -17,16 +40,29 @@ impl<'a> HostUpdate<'a> {
         Self {
             host: HostUpdateRequestData {
                 xmlns: XMLNS,
-                name: name.into(),
+                name: DomainName::normalize(name),
                 add: None,
                 remove: None,
                 change_info: None,
             },
+            glue_zones: &[],
         }
     }
 
+    /// Sets the zones the host may have addresses in, such as its superordinate domain or the
+    /// registry's TLDs
+    ///
+    /// Addresses added to a host have to lie within one of `zones`, see
+    /// [`HostCreate::glue_zones()`](super::HostCreate::glue_zones).
+    pub fn glue_zones(&mut self, zones: &'a [&'a str]) {
+        self.glue_zones = zones;
+    }
+
     /// Sets the data for the &lt;chg&gt; element of the host update
-    pub fn info(&mut self, info: HostChangeInfo<'a>) {
+    pub fn info(&mut self, mut info: HostChangeInfo<'a>) {
+        if let Some(name) = DomainName::converted(info.name.as_ref()) {
+            info.name = name.into();
+        }
         self.host.change_info = Some(info);
     }
 
@@ -42,77 +78,83 @@ impl<'a> HostUpdate<'a> {
 }
 
 /// Type for data under the &lt;chg&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostChangeInfo<'a> {
     /// The new name for the host
-    #[serde(rename = "host:name")]
+    #[serde(rename = "host:name", alias = "name")]
     pub name: StringValue<'a>,
 }
 
 /// Type for data under the &lt;add&gt; and &lt;rem&gt; tags
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostAddRemove<'a> {
     /// The IP addresses to be added to or removed from the host
-    #[serde(rename = "host:addr")]
-    pub addresses: Option<&'a [HostAddr<'a>]>,
+    #[serde(rename = "host:addr", alias = "addr")]
+    pub addresses: Option<Cow<'a, [HostAddr]>>,
     /// The statuses to be added to or removed from the host
-    #[serde(rename = "host:status")]
-    pub statuses: Option<&'a [ObjectStatus<'a>]>,
+    #[serde(rename = "host:status", alias = "status")]
+    pub statuses: Option<Cow<'a, [ObjectStatus<'a, HostStatus>]>>,
 }
 
 /// Type for data under the host &lt;update&gt; tag
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct HostUpdateRequestData<'a> {
     /// XML namespace for host commands
-    #[serde(rename = "xmlns:host")]
+    #[serde(rename = "xmlns:host", skip_deserializing, default = "xmlns")]
     xmlns: &'a str,
     /// The name of the host
-    #[serde(rename = "host:name")]
-    name: StringValue<'a>,
+    #[serde(rename = "host:name", alias = "name")]
+    pub name: StringValue<'a>,
     /// The IP addresses and statuses to be added to the host
-    #[serde(rename = "host:add")]
-    add: Option<HostAddRemove<'a>>,
+    #[serde(rename = "host:add", alias = "add")]
+    pub add: Option<HostAddRemove<'a>>,
     /// The IP addresses and statuses to be removed from the host
-    #[serde(rename = "host:rem")]
-    remove: Option<HostAddRemove<'a>>,
+    #[serde(rename = "host:rem", alias = "rem")]
+    pub remove: Option<HostAddRemove<'a>>,
     /// The host details that need to be updated
-    #[serde(rename = "host:chg")]
-    change_info: Option<HostChangeInfo<'a>>,
+    #[serde(rename = "host:chg", alias = "chg")]
+    pub change_info: Option<HostChangeInfo<'a>>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;update&gt; command for hosts
 pub struct HostUpdate<'a> {
     /// The instance holding the data for the host to be updated
-    #[serde(rename = "host:update")]
-    host: HostUpdateRequestData<'a>,
+    #[serde(rename = "host:update", alias = "update")]
+    pub host: HostUpdateRequestData<'a>,
+    /// The zones the host may have addresses in, checked before the command is sent
+    #[serde(skip)]
+    pub glue_zones: &'a [&'a str],
 }
 
 #[cfg(test)]
 mod tests {
     use super::{HostAddRemove, HostChangeInfo, HostUpdate};
-    use crate::common::{HostAddr, NoExtension, ObjectStatus};
+    use std::net::Ipv6Addr;
+
+    use crate::common::{HostAddr, HostStatus, NoExtension, ObjectStatus};
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SUCCESS_MSG, SVTRID};
+    use crate::Error;
 
     #[test]
     fn command() {
         let xml = get_xml("request/host/update.xml").unwrap();
 
-        let addr = &[HostAddr::new("v6", "2404:6800:4001:801::200e")];
+        let addr = &[HostAddr::from(Ipv6Addr::new(
+            0x2404, 0x6800, 0x4001, 0x801, 0, 0, 0, 0x200e,
+        ))];
 
         let add = HostAddRemove {
-            addresses: Some(addr),
+            addresses: Some(addr.into()),
             statuses: None,
         };
 
-        let statuses = &[ObjectStatus {
-            status: "clientDeleteProhibited".into(),
-        }];
+        let statuses = &[ObjectStatus::new(HostStatus::ClientDeleteProhibited)];
 
         let remove = HostAddRemove {
             addresses: None,
-            statuses: Some(statuses),
+            statuses: Some(statuses.into()),
         };
 
         let mut object = HostUpdate::new("host1.eppdev-1.com");
@@ -122,6 +164,7 @@ mod tests {
         object.info(HostChangeInfo {
             name: "host2.eppdev-1.com".into(),
         });
+        object.glue_zones(&["eppdev-1.com"]);
 
         let serialized =
             <HostUpdate as Transaction<NoExtension>>::serialize_request(&object, None, CLTRID)
@@ -130,6 +173,44 @@ mod tests {
         assert_eq!(xml, serialized);
     }
 
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn out_of_bailiwick_glue() {
+        let addr = &[HostAddr::from(Ipv6Addr::new(
+            0x2404, 0x6800, 0x4001, 0x801, 0, 0, 0, 0x200e,
+        ))];
+        let add = || HostAddRemove {
+            addresses: Some(addr.into()),
+            statuses: None,
+        };
+        let serialize = |object: &HostUpdate| {
+            <HostUpdate as Transaction<NoExtension>>::serialize_request(object, None, CLTRID)
+        };
+
+        let mut object = HostUpdate::new("ns1.eppdev.com");
+        object.add(add());
+        assert!(matches!(serialize(&object), Err(Error::Invalid(_))));
+        object.glue_zones(&["eppdev.com"]);
+        assert!(serialize(&object).is_ok());
+
+        // Renaming the host out of the zone leaves the added addresses outside of it
+        object.info(HostChangeInfo {
+            name: "NS1.eppdev.net.".into(),
+        });
+        match serialize(&object) {
+            Err(Error::Invalid(e)) => assert_eq!(
+                e,
+                "host ns1.eppdev.net is outside of eppdev.com and can't have addresses"
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Removing addresses needs no zones
+        let mut object = HostUpdate::new("ns1.eppdev.net");
+        object.remove(add());
+        assert!(serialize(&object).is_ok());
+    }
+
     #[test]
     fn response() {
         let xml = get_xml("response/host/update.xml").unwrap();
@@ -0,0 +1,1031 @@
+//! JSON representations of responses and greetings
+//!
+//! The `Serialize` implementations of the response types produce EPP XML, with namespaced
+//! element names and the `$value` and `xmlns` fields the XML serializer needs. To store or
+//! forward responses as JSON, wrap them in [`Json`] instead: it serializes any type implementing
+//! [`ToJson`] to a stable JSON shape with snake_case field names, no XML artefacts, and the
+//! wrapper elements of the XML (such as `<domain:infData>`) left out.
+//!
+//! Fields missing from the response are serialized as `null` rather than skipped, so every
+//! object of a type has the same keys.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::domain::DomainInfo;
+//! use epp_client::json::Json;
+//! # async fn example(client: &mut epp_client::EppClient<tokio::net::TcpStream>) {
+//!
+//! let info = DomainInfo::new("eppdev.com", None);
+//! let response = client.transact(&info, "transaction-id").await.unwrap();
+//!
+//! // {"result": {"code": 1000, ...}, "data": {"name": "eppdev.com", ...}, ...}
+//! let json = serde_json::to_string(&Json(&response)).unwrap();
+//! # }
+//! ```
+
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use serde::ser::{SerializeSeq, Serializer};
+use serde::Serialize;
+
+use crate::common::{NoExtension, ObjectStatus, StringValue};
+use crate::contact::check::{ContactCheckResponse, ContactCheckResponseDataItem};
+use crate::contact::create::ContactCreateResponse;
+use crate::contact::info::{ContactInfoData, ContactInfoResponse};
+use crate::contact::transfer::{ContactTransferResponse, ContactTransferResponseData};
+use crate::contact::{Address, Disclose, DiscloseType, Phone, PostalInfo};
+use crate::domain::check::{DomainCheckResponse, DomainCheckResponseDataItem};
+use crate::domain::create::DomainCreateResponse;
+use crate::domain::info::{DomainInfoResponse, DomainInfoResponseData};
+use crate::domain::renew::DomainRenewResponse;
+use crate::domain::transfer::{DomainTransferResponse, DomainTransferResponseData};
+use crate::domain::{DomainContact, HostAttr};
+use crate::extensions::namestore::NameStore;
+use crate::extensions::rgp::request::RgpRequestResponse;
+use crate::extensions::rgp::Update;
+use crate::hello::{AccessType, Dcp, ExpiryType, Greeting, RetentionType, Statement};
+use crate::host::check::{HostCheckDataItem, HostCheckResponse};
+use crate::host::create::HostCreateResponse;
+use crate::host::info::{HostInfoResponse, HostInfoResponseData};
+use crate::message::poll::{MessageData, MessagePollResponse};
+use crate::response::{EppResult, MessageQueue, Response, ResponseStatus};
+
+/// Serializes the wrapped value to its JSON representation
+#[derive(Debug)]
+pub struct Json<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ToJson + ?Sized> Serialize for Json<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.to_json(serializer)
+    }
+}
+
+/// Types with a JSON representation, serialized through [`Json`]
+pub trait ToJson {
+    /// Serializes `self` to its JSON representation
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => value.to_json(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for item in self {
+            seq.serialize_element(&Json(item))?;
+        }
+        seq.end()
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().to_json(serializer)
+    }
+}
+
+impl ToJson for () {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_none()
+    }
+}
+
+impl ToJson for String {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl ToJson for NoExtension {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_none()
+    }
+}
+
+/// Serializes a list of statuses as their names
+fn statuses<S: Display>(statuses: &[ObjectStatus<S>]) -> Vec<String> {
+    statuses.iter().map(|s| s.status.to_string()).collect()
+}
+
+// Responses
+
+impl<D: ToJson, E: ToJson> ToJson for Response<D, E> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(bound = "")]
+        struct View<'a, D: ToJson, E: ToJson> {
+            result: Json<'a, EppResult>,
+            message_queue: Option<Json<'a, MessageQueue>>,
+            data: Option<Json<'a, D>>,
+            extension: Option<Json<'a, E>>,
+            client_tr_id: Option<&'a StringValue<'static>>,
+            server_tr_id: &'a StringValue<'static>,
+        }
+
+        View {
+            result: Json(&self.result),
+            message_queue: self.message_queue.as_ref().map(Json),
+            data: self.res_data.as_ref().map(Json),
+            extension: self.extension.as_ref().map(Json),
+            client_tr_id: self.tr_ids.client_tr_id.as_ref(),
+            server_tr_id: &self.tr_ids.server_tr_id,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for ResponseStatus {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            result: Json<'a, EppResult>,
+            client_tr_id: Option<&'a StringValue<'static>>,
+            server_tr_id: &'a StringValue<'static>,
+        }
+
+        View {
+            result: Json(&self.result),
+            client_tr_id: self.tr_ids.client_tr_id.as_ref(),
+            server_tr_id: &self.tr_ids.server_tr_id,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for EppResult {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            code: u16,
+            message: &'a StringValue<'static>,
+            reason: Option<&'a StringValue<'static>>,
+        }
+
+        View {
+            code: self.code,
+            message: &self.message,
+            reason: self.ext_value.as_ref().map(|ext| &ext.reason),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for MessageQueue {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            count: u32,
+            id: &'a str,
+            date: Option<&'a DateTime<Utc>>,
+            message: Option<&'a StringValue<'static>>,
+        }
+
+        View {
+            count: self.count,
+            id: &self.id,
+            date: self.date.as_ref(),
+            message: self.message.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Greeting
+
+impl ToJson for Greeting {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            server_id: &'a str,
+            server_date: &'a DateTime<Utc>,
+            version: &'a StringValue<'static>,
+            lang: &'a StringValue<'static>,
+            objects: &'a [StringValue<'static>],
+            extensions: &'a [StringValue<'static>],
+            dcp: Json<'a, Dcp>,
+        }
+
+        let services = &self.svc_menu.services;
+        let extensions = services.svc_ext.as_ref();
+        View {
+            server_id: &self.service_id,
+            server_date: &self.service_date,
+            version: &self.svc_menu.options.version,
+            lang: &self.svc_menu.options.lang,
+            objects: &services.obj_uris,
+            extensions: extensions
+                .and_then(|ext| ext.ext_uris.as_deref())
+                .unwrap_or_default(),
+            dcp: Json(&self.dcp),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for Dcp {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            access: &'static str,
+            statements: Json<'a, [Statement]>,
+            expiry: Option<Expiry<'a>>,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Expiry<'a> {
+            Absolute(&'a StringValue<'static>),
+            Relative(&'a StringValue<'static>),
+        }
+
+        let access = match self.access.ty {
+            AccessType::All(_) => "all",
+            AccessType::NoAccess(_) => "none",
+            AccessType::Null(_) => "null",
+            AccessType::Personal(_) => "personal",
+            AccessType::PersonalAndOther(_) => "personal_and_other",
+            AccessType::Other(_) => "other",
+        };
+
+        let expiry = self.expiry.as_ref().map(|expiry| match &expiry.ty {
+            ExpiryType::Absolute(absolute) => Expiry::Absolute(&absolute.absolute),
+            ExpiryType::Relative(relative) => Expiry::Relative(&relative.relative),
+        });
+
+        View {
+            access,
+            statements: Json(&self.statement),
+            expiry,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for Statement {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            purposes: &'a [crate::hello::PurposeType],
+            recipients: &'a [crate::hello::RecipientType],
+            retention: &'static str,
+        }
+
+        let retention = match self.retention.ty {
+            RetentionType::Business(_) => "business",
+            RetentionType::Indefinite(_) => "indefinite",
+            RetentionType::Legal(_) => "legal",
+            RetentionType::No(_) => "none",
+            RetentionType::Stated(_) => "stated",
+        };
+
+        View {
+            purposes: &self.purpose.purpose,
+            recipients: &self.recipient.recipient,
+            retention,
+        }
+        .serialize(serializer)
+    }
+}
+
+// Domains
+
+impl ToJson for DomainCheckResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_data.domain_list.to_json(serializer)
+    }
+}
+
+impl ToJson for DomainCheckResponseDataItem {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a str,
+            available: bool,
+            reason: Option<&'a StringValue<'static>>,
+        }
+
+        View {
+            name: &self.domain.name,
+            available: self.domain.available,
+            reason: self.reason.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for DomainCreateResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            created_at: &'a DateTime<Utc>,
+            expires_at: Option<&'a DateTime<Utc>>,
+        }
+
+        let data = &self.create_data;
+        View {
+            name: &data.name,
+            created_at: &data.created_at,
+            expires_at: data.expiring_at.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for DomainInfoResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.info_data.to_json(serializer)
+    }
+}
+
+impl ToJson for DomainInfoResponseData {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            roid: &'a StringValue<'static>,
+            statuses: Vec<String>,
+            registrant: Option<&'a StringValue<'static>>,
+            contacts: Json<'a, [DomainContact<'static>]>,
+            ns: Vec<NameServer<'a>>,
+            hosts: &'a [StringValue<'static>],
+            client_id: &'a StringValue<'static>,
+            creator_id: Option<&'a StringValue<'static>>,
+            created_at: Option<&'a DateTime<Utc>>,
+            expires_at: Option<&'a DateTime<Utc>>,
+            updater_id: Option<&'a StringValue<'static>>,
+            updated_at: Option<&'a DateTime<Utc>>,
+            transferred_at: Option<&'a DateTime<Utc>>,
+            auth: Option<&'a StringValue<'static>>,
+        }
+
+        #[derive(Serialize)]
+        struct NameServer<'a> {
+            name: &'a StringValue<'static>,
+            addresses: Vec<&'a IpAddr>,
+        }
+
+        let mut ns = Vec::new();
+        if let Some(list) = &self.ns {
+            for name in list.host_obj.iter().flatten() {
+                ns.push(NameServer {
+                    name,
+                    addresses: Vec::new(),
+                });
+            }
+            for host in list.host_attr.iter().flatten() {
+                ns.push(NameServer {
+                    name: &host.name,
+                    addresses: addresses(host),
+                });
+            }
+        }
+
+        View {
+            name: &self.name,
+            roid: &self.roid,
+            statuses: statuses(self.statuses.as_deref().unwrap_or_default()),
+            registrant: self.registrant.as_ref(),
+            contacts: Json(self.contacts.as_deref().unwrap_or_default()),
+            ns,
+            hosts: self.hosts.as_deref().unwrap_or_default(),
+            client_id: &self.client_id,
+            creator_id: self.creator_id.as_ref(),
+            created_at: self.created_at.as_ref(),
+            expires_at: self.expiring_at.as_ref(),
+            updater_id: self.updater_id.as_ref(),
+            updated_at: self.updated_at.as_ref(),
+            transferred_at: self.transferred_at.as_ref(),
+            auth: self.auth_info.as_ref().map(|auth| &auth.password),
+        }
+        .serialize(serializer)
+    }
+}
+
+fn addresses<'a>(host: &'a HostAttr<'static>) -> Vec<&'a IpAddr> {
+    let addresses = host.addresses.iter().flatten();
+    addresses.map(|addr| &addr.address).collect()
+}
+
+impl ToJson for DomainContact<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            #[serde(rename = "type")]
+            contact_type: &'a str,
+            id: &'a str,
+        }
+
+        View {
+            contact_type: self.contact_type.as_str(),
+            id: &self.id,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for DomainRenewResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            expires_at: Option<&'a DateTime<Utc>>,
+        }
+
+        View {
+            name: &self.renew_data.name,
+            expires_at: self.renew_data.expiring_at.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for DomainTransferResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.transfer_data.to_json(serializer)
+    }
+}
+
+impl ToJson for DomainTransferResponseData {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            status: &'a str,
+            requested_by: &'a StringValue<'static>,
+            requested_at: &'a DateTime<Utc>,
+            action_by: &'a StringValue<'static>,
+            action_at: &'a DateTime<Utc>,
+            deadline: Option<DateTime<Utc>>,
+            expires_at: Option<&'a DateTime<Utc>>,
+        }
+
+        View {
+            name: &self.name,
+            status: self.transfer_status.as_str(),
+            requested_by: &self.requester_id,
+            requested_at: &self.requested_at,
+            action_by: &self.ack_id,
+            action_at: &self.ack_by,
+            deadline: self.transfer().deadline(),
+            expires_at: self.expiring_at.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Contacts
+
+impl ToJson for ContactCheckResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_data.contact_list.to_json(serializer)
+    }
+}
+
+impl ToJson for ContactCheckResponseDataItem {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            id: &'a str,
+            available: bool,
+            reason: Option<&'a StringValue<'static>>,
+        }
+
+        View {
+            id: &self.contact.id,
+            available: self.contact.available == 1,
+            reason: self.reason.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for ContactCreateResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            id: &'a StringValue<'static>,
+            created_at: &'a DateTime<Utc>,
+        }
+
+        View {
+            id: &self.create_data.id,
+            created_at: &self.create_data.created_at,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for ContactInfoResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.info_data.to_json(serializer)
+    }
+}
+
+impl ToJson for ContactInfoData<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a, 'c> {
+            id: &'a StringValue<'c>,
+            roid: &'a StringValue<'c>,
+            statuses: Vec<String>,
+            postal_info: Json<'a, [PostalInfo<'c>]>,
+            voice: Json<'a, Phone<'c>>,
+            fax: Option<Json<'a, Phone<'c>>>,
+            email: &'a StringValue<'c>,
+            client_id: &'a StringValue<'c>,
+            creator_id: &'a StringValue<'c>,
+            created_at: &'a DateTime<Utc>,
+            updater_id: Option<&'a StringValue<'c>>,
+            updated_at: Option<&'a DateTime<Utc>>,
+            transferred_at: Option<&'a DateTime<Utc>>,
+            auth: Option<&'a str>,
+            disclose: Option<Json<'a, Disclose>>,
+        }
+
+        View {
+            id: &self.id,
+            roid: &self.roid,
+            statuses: statuses(&self.statuses),
+            postal_info: Json(&self.postal_info),
+            voice: Json(&self.voice),
+            fax: self.fax.as_ref().map(Json),
+            email: &self.email,
+            client_id: &self.client_id,
+            creator_id: &self.creator_id,
+            created_at: &self.created_at,
+            updater_id: self.updater_id.as_ref(),
+            updated_at: self.updated_at.as_ref(),
+            transferred_at: self.transferred_at.as_ref(),
+            auth: self.auth_info.as_ref().map(|auth| &*auth.password.value),
+            disclose: self.disclose.as_ref().map(Json),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for Disclose {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View {
+            flag: bool,
+            name: Vec<&'static str>,
+            organization: Vec<&'static str>,
+            address: Vec<&'static str>,
+            voice: bool,
+            fax: bool,
+            email: bool,
+        }
+
+        fn types(types: &[DiscloseType]) -> Vec<&'static str> {
+            types.iter().map(|t| t.info_type.as_str()).collect()
+        }
+
+        View {
+            flag: self.flag,
+            name: types(&self.name),
+            organization: types(&self.organization),
+            address: types(&self.address),
+            voice: self.voice.is_some(),
+            fax: self.fax.is_some(),
+            email: self.email.is_some(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for PostalInfo<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a, 'c> {
+            #[serde(rename = "type")]
+            info_type: &'static str,
+            name: &'a StringValue<'c>,
+            organization: &'a StringValue<'c>,
+            address: Json<'a, Address<'c>>,
+        }
+
+        View {
+            info_type: self.info_type.as_str(),
+            name: &self.name,
+            organization: &self.organization,
+            address: Json(&self.address),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for Address<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a, 'c> {
+            street: &'a [StringValue<'c>],
+            city: &'a StringValue<'c>,
+            province: &'a StringValue<'c>,
+            postal_code: &'a StringValue<'c>,
+            country: &'static str,
+        }
+
+        View {
+            street: &self.street,
+            city: &self.city,
+            province: &self.province,
+            postal_code: &self.postal_code,
+            country: self.country.alpha2,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for Phone<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            number: &'a str,
+            extension: Option<&'a str>,
+        }
+
+        View {
+            number: &self.number,
+            extension: self.extension.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for ContactTransferResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.transfer_data.to_json(serializer)
+    }
+}
+
+impl ToJson for ContactTransferResponseData {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            id: &'a StringValue<'static>,
+            status: &'a str,
+            requested_by: &'a StringValue<'static>,
+            requested_at: &'a DateTime<Utc>,
+            action_by: &'a StringValue<'static>,
+            action_at: &'a DateTime<Utc>,
+            deadline: Option<DateTime<Utc>>,
+        }
+
+        View {
+            id: &self.id,
+            status: self.transfer_status.as_str(),
+            requested_by: &self.requester_id,
+            requested_at: &self.requested_at,
+            action_by: &self.ack_id,
+            action_at: &self.ack_by,
+            deadline: self.transfer().deadline(),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Hosts
+
+impl ToJson for HostCheckResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_data.host_list.to_json(serializer)
+    }
+}
+
+impl ToJson for HostCheckDataItem {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a str,
+            available: bool,
+            reason: Option<&'a StringValue<'static>>,
+        }
+
+        View {
+            name: &self.host.name,
+            available: self.host.available == 1,
+            reason: self.reason.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for HostCreateResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            created_at: &'a DateTime<Utc>,
+        }
+
+        View {
+            name: &self.create_data.name,
+            created_at: &self.create_data.created_at,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for HostInfoResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.info_data.to_json(serializer)
+    }
+}
+
+impl ToJson for HostInfoResponseData {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            name: &'a StringValue<'static>,
+            roid: &'a StringValue<'static>,
+            statuses: Vec<String>,
+            addresses: Vec<&'a IpAddr>,
+            client_id: &'a StringValue<'static>,
+            creator_id: &'a StringValue<'static>,
+            created_at: &'a DateTime<Utc>,
+            updater_id: Option<&'a StringValue<'static>>,
+            updated_at: Option<&'a DateTime<Utc>>,
+            transferred_at: Option<&'a DateTime<Utc>>,
+        }
+
+        View {
+            name: &self.name,
+            roid: &self.roid,
+            statuses: statuses(&self.statuses),
+            addresses: self.addresses.iter().map(|addr| &addr.address).collect(),
+            client_id: &self.client_id,
+            creator_id: &self.creator_id,
+            created_at: &self.created_at,
+            updater_id: self.updater_id.as_ref(),
+            updated_at: self.updated_at.as_ref(),
+            transferred_at: self.transferred_at.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Messages
+
+impl ToJson for MessagePollResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(tag = "type", content = "data", rename_all = "snake_case")]
+        enum View<'a> {
+            DomainTransfer(Json<'a, DomainTransferResponseData>),
+            ContactTransfer(Json<'a, ContactTransferResponseData>),
+            HostInfo(Json<'a, HostInfoResponseData>),
+        }
+
+        match &self.message_data {
+            MessageData::DomainTransfer(data) => View::DomainTransfer(Json(data)),
+            MessageData::ContactTransfer(data) => View::ContactTransfer(Json(data)),
+            MessageData::HostInfo(data) => View::HostInfo(Json(data)),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Extensions
+
+impl<T: ToJson> ToJson for Update<T> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.to_json(serializer)
+    }
+}
+
+impl ToJson for RgpRequestResponse {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            statuses: Vec<&'a str>,
+        }
+
+        View {
+            statuses: self.rgp_status.iter().map(|s| s.status.as_str()).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl ToJson for NameStore<'_> {
+    fn to_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a, 'c> {
+            subproduct: &'a StringValue<'c>,
+        }
+
+        View {
+            subproduct: &self.data.subproduct,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{Json, ToJson};
+    use crate::common::NoExtension;
+    use crate::contact::{ContactInfo, ContactTransfer};
+    use crate::domain::{DomainCheck, DomainInfo, DomainTransfer};
+    use crate::extensions::namestore::NameStore;
+    use crate::extensions::rgp::request::RgpRestoreRequest;
+    use crate::extensions::rgp::Update;
+    use crate::hello::{Greeting, GreetingDocument};
+    use crate::host::HostInfo;
+    use crate::message::MessagePoll;
+    use crate::request::Transaction;
+    use crate::response::ResultDocument;
+    use crate::tests::get_xml;
+    use crate::xml::EppXml;
+
+    fn to_json<T: ToJson + ?Sized>(value: &T) -> Value {
+        serde_json::to_value(Json(value)).unwrap()
+    }
+
+    /// Returns whether `value` contains any XML artefacts in its keys
+    fn has_artefacts(value: &Value) -> bool {
+        match value {
+            Value::Object(map) => map.iter().any(|(key, value)| {
+                key.contains(':') || key.contains('$') || key == "xmlns" || has_artefacts(value)
+            }),
+            Value::Array(items) => items.iter().any(has_artefacts),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn domain_info() {
+        let xml = get_xml("response/domain/info.xml").unwrap();
+        let response =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(&response);
+
+        assert_eq!(
+            json["result"],
+            json!({"code": 1000, "message": "Command completed successfully", "reason": null})
+        );
+        assert_eq!(json["message_queue"], Value::Null);
+        assert_eq!(json["extension"], Value::Null);
+        assert_eq!(json["client_tr_id"], json!("cltrid:1626454866"));
+        assert_eq!(json["server_tr_id"], json!("RO-6879-1627224678242975"));
+
+        let data = &json["data"];
+        assert_eq!(data["name"], json!("eppdev-1.com"));
+        assert_eq!(data["statuses"], json!(["ok", "clientTransferProhibited"]));
+        assert_eq!(
+            data["contacts"][0],
+            json!({"type": "admin", "id": "eppdev-contact-2"})
+        );
+        assert_eq!(
+            data["ns"],
+            json!([
+                {"name": "ns1.eppdev-1.com", "addresses": []},
+                {"name": "ns2.eppdev-1.com", "addresses": []},
+            ])
+        );
+        assert_eq!(data["created_at"], json!("2021-07-23T15:31:20Z"));
+        assert_eq!(data["auth"], json!("epP4uthd#v"));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn domain_check() {
+        let xml = get_xml("response/domain/check.xml").unwrap();
+        let response =
+            <DomainCheck as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        assert_eq!(
+            to_json(response.res_data().unwrap()),
+            json!([
+                {"name": "eppdev.com", "available": true, "reason": null},
+                {"name": "eppdev.net", "available": false, "reason": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn domain_transfer() {
+        let xml = get_xml("response/domain/transfer_query.xml").unwrap();
+        let response =
+            <DomainTransfer as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(response.res_data().unwrap());
+        assert_eq!(json["status"], json!("pending"));
+        assert_eq!(json["requested_by"], json!("eppdev"));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn contact_transfer() {
+        let xml = get_xml("response/contact/transfer_request.xml").unwrap();
+        let response =
+            <ContactTransfer as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(response.res_data().unwrap());
+        assert_eq!(json["id"], json!("eppdev-contact-3"));
+        assert_eq!(json["deadline"], json!("2021-07-28T15:31:21Z"));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn contact_info() {
+        let xml = get_xml("response/contact/info.xml").unwrap();
+        let response =
+            <ContactInfo as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(response.res_data().unwrap());
+
+        assert_eq!(json["id"], json!("eppdev-contact-3"));
+        assert_eq!(json["postal_info"][0]["type"], json!("loc"));
+        assert_eq!(json["postal_info"][0]["address"]["country"], json!("FR"));
+        assert_eq!(json["postal_info"][1]["type"], json!("int"));
+        assert_eq!(
+            json["voice"],
+            json!({"number": "+33.47237942", "extension": "123"})
+        );
+        assert_eq!(json["disclose"]["flag"], json!(false));
+        assert_eq!(json["disclose"]["address"], json!(["int"]));
+        assert_eq!(json["disclose"]["fax"], json!(false));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn host_info() {
+        let xml = get_xml("response/host/info.xml").unwrap();
+        let response = <HostInfo as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(response.res_data().unwrap());
+
+        assert_eq!(json["name"], json!("host2.eppdev-1.com"));
+        assert_eq!(json["addresses"][0], json!("29.245.122.14"));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn message_poll() {
+        let xml = get_xml("response/message/poll_domain_transfer.xml").unwrap();
+        let response =
+            <MessagePoll as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let json = to_json(&response);
+
+        assert_eq!(json["result"]["code"], json!(1301));
+        assert_eq!(json["message_queue"]["count"], json!(5));
+        assert_eq!(json["message_queue"]["id"], json!("12345"));
+        assert_eq!(json["data"]["type"], json!("domain_transfer"));
+        assert_eq!(json["data"]["data"]["name"], json!("eppdev-transfer.com"));
+        assert!(!has_artefacts(&json));
+    }
+
+    #[test]
+    fn extensions() {
+        let xml = get_xml("response/extensions/rgp_restore.xml").unwrap();
+        let response = <crate::domain::DomainUpdate as Transaction<
+            Update<RgpRestoreRequest>,
+        >>::deserialize_response(&xml)
+        .unwrap();
+        assert_eq!(
+            to_json(response.extension.as_ref().unwrap()),
+            json!({"statuses": ["pendingRestore"]})
+        );
+
+        let xml = get_xml("response/extensions/namestore.xml").unwrap();
+        let response = <DomainCheck as Transaction<NameStore>>::deserialize_response(&xml).unwrap();
+        assert_eq!(
+            to_json(response.extension.as_ref().unwrap()),
+            json!({"subproduct": "com"})
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let xml = get_xml("response/error.xml").unwrap();
+        let status = ResultDocument::deserialize(&xml).unwrap().data;
+        let json = to_json(&status);
+        assert_eq!(json["result"]["code"], json!(2303));
+        assert_eq!(json["result"]["reason"], json!("545 Object not found"));
+    }
+
+    #[test]
+    fn greeting() {
+        let xml = get_xml("response/greeting.xml").unwrap();
+        let greeting: Greeting = GreetingDocument::deserialize(&xml).unwrap().data;
+        let json = to_json(&greeting);
+
+        assert_eq!(json["server_id"], json!("ISPAPI EPP Server"));
+        assert_eq!(json["version"], json!("1.0"));
+        assert_eq!(json["dcp"]["access"], json!("all"));
+        assert_eq!(
+            json["dcp"]["statements"][0],
+            json!({"purposes": ["admin", "prov"], "recipients": ["ours", "public"], "retention": "stated"})
+        );
+        assert!(!has_artefacts(&json));
+    }
+}
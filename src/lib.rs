@@ -20,6 +20,7 @@
 //! - Contact Info - [`EppContactInfo`](epp/request/contact/info/type.EppContactInfo.html)
 //! - Contact Update - [`EppContactUpdate`](epp/request/contact/update/type.EppContactUpdate.html)
 //! - Contact Delete - [`EppContactDelete`](epp/request/contact/delete/type.EppContactDelete.html)
+//! - Contact Transfer - [`ContactTransfer`](contact/transfer/struct.ContactTransfer.html)
 //!
 //! - Host Check - [`EppHostCheck`](epp/request/host/check/type.EppHostCheck.html)
 //! - Host Create - [`EppHostCreate`](epp/request/host/create/type.EppHostCreate.html)
@@ -89,15 +90,32 @@
 
 pub mod client;
 pub mod common;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod connection;
 pub mod contact;
 pub mod domain;
 mod error;
+#[cfg(feature = "gateway")]
+pub mod gateway;
+pub mod handle;
 pub mod hello;
+pub mod json;
 pub mod login;
 pub mod logout;
+pub mod metrics;
+pub mod middleware;
+#[cfg(feature = "config")]
+pub mod pool;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+pub mod ratelimit;
+pub mod recording;
 pub mod request;
 pub mod response;
+pub mod server;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod xml;
 
 pub mod extensions {
@@ -123,6 +141,46 @@ pub mod host {
     pub use update::HostUpdate;
 
     pub const XMLNS: &str = "urn:ietf:params:xml:ns:host-1.0";
+
+    /// The namespace restored on deserialized host commands
+    pub(crate) fn xmlns() -> &'static str {
+        XMLNS
+    }
+
+    /// Checks that a host given glue `addresses` lies within one of `zones`, as RFC 5732 only
+    /// allows addresses for hosts in a zone the server is authoritative for
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn validate_glue(
+        name: &str,
+        addresses: Option<&[crate::common::HostAddr]>,
+        zones: &[&str],
+    ) -> Result<(), crate::Error> {
+        use crate::common::DomainName;
+
+        if addresses.unwrap_or_default().is_empty() {
+            return Ok(());
+        }
+
+        let host = DomainName::new(name)?;
+        for zone in zones {
+            if host.is_within(&DomainName::new(zone)?) {
+                return Ok(());
+            }
+        }
+
+        Err(crate::Error::Invalid(if zones.is_empty() {
+            format!(
+                "host {} has addresses, but no glue zones to check them against",
+                name
+            )
+        } else {
+            format!(
+                "host {} is outside of {} and can't have addresses",
+                name,
+                zones.join(", ")
+            )
+        }))
+    }
 }
 
 pub mod message {
@@ -135,6 +193,7 @@ pub mod message {
 
 pub use client::EppClient;
 pub use error::Error;
+pub use handle::EppHandle;
 
 #[cfg(test)]
 pub mod tests;
@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     common::{NoExtension, Options, ServiceExtension, Services, StringValue},
@@ -10,20 +10,20 @@ use crate::{
 
 impl<'a> Transaction<NoExtension> for Login<'a> {}
 
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// Type corresponding to the &lt;login&gt; tag in an EPP XML login request
 pub struct Login<'a> {
     /// The username to use for the login
     #[serde(rename(serialize = "clID", deserialize = "clID"))]
-    username: StringValue<'a>,
+    pub username: StringValue<'a>,
     /// The password to use for the login
     #[serde(rename = "pw", default)]
-    password: StringValue<'a>,
+    pub password: StringValue<'a>,
     /// Data under the <options> tag
-    options: Options<'a>,
+    pub options: Options<'a>,
     /// Data under the <svcs> tag
     #[serde(rename = "svcs")]
-    services: Services<'a>,
+    pub services: Services<'a>,
 }
 
 impl<'a> Login<'a> {
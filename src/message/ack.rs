@@ -2,7 +2,9 @@
 
 use crate::common::NoExtension;
 use crate::request::{Command, Transaction};
-use serde::Serialize;
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
 
 impl<'a> Transaction<NoExtension> for MessageAck<'a> {}
 
@@ -11,21 +13,21 @@ impl<'a> Command for MessageAck<'a> {
     const COMMAND: &'static str = "poll";
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;poll&gt; command for message ack
 pub struct MessageAck<'a> {
     /// The type of operation to perform
     /// The value is "ack" for message acknowledgement
-    op: &'a str,
+    pub op: Cow<'a, str>,
     /// The ID of the message to be acknowledged
     #[serde(rename = "msgID")]
-    message_id: String,
+    pub message_id: String,
 }
 
 impl<'a> MessageAck<'a> {
     pub fn new(message_id: u32) -> Self {
         Self {
-            op: "ack",
+            op: "ack".into(),
             message_id: message_id.to_string(),
         }
     }
@@ -1,8 +1,11 @@
+use std::borrow::Cow;
+
 use crate::common::NoExtension;
+use crate::contact::transfer::ContactTransferResponseData;
 use crate::domain::transfer::DomainTransferResponseData;
 use crate::host::info::HostInfoResponseData;
 use crate::request::{Command, Transaction};
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 
 impl<'a> Transaction<NoExtension> for MessagePoll<'a> {}
 
@@ -13,17 +16,17 @@ impl<'a> Command for MessagePoll<'a> {
 
 // Request
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 /// Type for EPP XML &lt;poll&gt; command for message poll
 pub struct MessagePoll<'a> {
     /// The type of operation to perform
     /// The value is "req" for message polling
-    op: &'a str,
+    pub op: Cow<'a, str>,
 }
 
 impl Default for MessagePoll<'static> {
     fn default() -> Self {
-        Self { op: "req" }
+        Self { op: "req".into() }
     }
 }
 
@@ -35,6 +38,9 @@ pub enum MessageData {
     /// Data under the &lt;domain:trnData&gt; tag
     #[serde(rename = "domain:trnData")]
     DomainTransfer(DomainTransferResponseData),
+    /// Data under the &lt;contact:trnData&gt; tag
+    #[serde(rename = "contact:trnData")]
+    ContactTransfer(ContactTransferResponseData),
     /// Data under the &lt;host:infData&gt; tag
     #[serde(rename = "host:infData")]
     HostInfo(HostInfoResponseData),
@@ -48,12 +54,30 @@ pub struct MessagePollResponse {
     pub message_data: MessageData,
 }
 
+impl Serialize for MessagePollResponse {
+    /// Serializes the message data under a tag named after its type
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("resData", 1)?;
+        match &self.message_data {
+            MessageData::DomainTransfer(data) => state.serialize_field("domain:trnData", data)?,
+            MessageData::ContactTransfer(data) => state.serialize_field("contact:trnData", data)?,
+            MessageData::HostInfo(data) => state.serialize_field("host:infData", data)?,
+        }
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::MessagePoll;
+    use crate::common::{HostStatus, TransferStatus};
     use crate::message::poll::MessageData;
     use crate::request::Transaction;
     use crate::tests::{get_xml, CLTRID, SVTRID};
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn command() {
@@ -83,7 +107,7 @@ mod tests {
         assert_eq!(msg.id, "12345".to_string());
         assert_eq!(
             *(msg.date.as_ref().unwrap()),
-            "2021-07-23T19:12:43.0Z".into()
+            Utc.with_ymd_and_hms(2021, 7, 23, 19, 12, 43).unwrap()
         );
         assert_eq!(
             *(msg.message.as_ref().unwrap()),
@@ -92,14 +116,20 @@ mod tests {
 
         if let MessageData::DomainTransfer(tr) = &result.message_data {
             assert_eq!(tr.name, "eppdev-transfer.com".into());
-            assert_eq!(tr.transfer_status, "pending".into());
+            assert_eq!(tr.transfer_status, TransferStatus::Pending);
             assert_eq!(tr.requester_id, "eppdev".into());
-            assert_eq!(tr.requested_at, "2021-07-23T15:31:21.0Z".into());
+            assert_eq!(
+                tr.requested_at,
+                Utc.with_ymd_and_hms(2021, 7, 23, 15, 31, 21).unwrap()
+            );
             assert_eq!(tr.ack_id, "ClientY".into());
-            assert_eq!(tr.ack_by, "2021-07-28T15:31:21.0Z".into());
+            assert_eq!(
+                tr.ack_by,
+                Utc.with_ymd_and_hms(2021, 7, 28, 15, 31, 21).unwrap()
+            );
             assert_eq!(
                 *tr.expiring_at.as_ref().unwrap(),
-                "2022-07-02T14:53:19.0Z".into()
+                Utc.with_ymd_and_hms(2022, 7, 2, 14, 53, 19).unwrap()
             );
         } else {
             panic!("Wrong type");
@@ -109,6 +139,22 @@ mod tests {
         assert_eq!(object.tr_ids.server_tr_id, SVTRID.into());
     }
 
+    #[test]
+    fn contact_transfer_response() {
+        let xml = get_xml("response/message/poll_contact_transfer.xml").unwrap();
+        let object = MessagePoll::deserialize_response(xml.as_str()).unwrap();
+
+        let result = object.res_data().unwrap();
+
+        if let MessageData::ContactTransfer(tr) = &result.message_data {
+            assert_eq!(tr.id, "eppdev-contact-3".into());
+            assert_eq!(tr.transfer_status, TransferStatus::Pending);
+            assert_eq!(tr.ack_id, "ClientY".into());
+        } else {
+            panic!("Wrong type");
+        }
+    }
+
     #[test]
     fn host_info_response() {
         let xml = get_xml("response/message/poll_host_info.xml").unwrap();
@@ -124,7 +170,10 @@ mod tests {
         );
         assert_eq!(msg.count, 4);
         assert_eq!(msg.id, "12345".to_string());
-        assert_eq!(*(msg.date.as_ref().unwrap()), "2022-01-02T11:30:45Z".into());
+        assert_eq!(
+            *(msg.date.as_ref().unwrap()),
+            Utc.with_ymd_and_hms(2022, 1, 2, 11, 30, 45).unwrap()
+        );
         assert_eq!(
             *(msg.message.as_ref().unwrap()),
             "Unused objects policy".into()
@@ -134,13 +183,19 @@ mod tests {
             assert_eq!(host.name, "ns.test.com".into());
 
             assert_eq!(host.roid, "1234".into());
-            assert!(host.statuses.iter().any(|s| s.status == "ok"));
-            assert!(host.addresses.iter().any(|a| a.address == *"1.1.1.1"));
+            assert!(host.statuses.iter().any(|s| s.status == HostStatus::Ok));
+            assert!(host.addresses.iter().any(|a| a.to_string() == "1.1.1.1"));
             assert_eq!(host.client_id, "1234".into());
             assert_eq!(host.creator_id, "user".into());
-            assert_eq!(host.created_at, "2021-12-01T22:40:48Z".into());
+            assert_eq!(
+                host.created_at,
+                Utc.with_ymd_and_hms(2021, 12, 1, 22, 40, 48).unwrap()
+            );
             assert_eq!(host.updater_id, Some("user".into()));
-            assert_eq!(host.updated_at, Some("2021-12-01T22:40:48Z".into()));
+            assert_eq!(
+                host.updated_at,
+                Some(Utc.with_ymd_and_hms(2021, 12, 1, 22, 40, 48).unwrap())
+            );
         } else {
             panic!("Wrong type");
         }
@@ -166,7 +221,7 @@ mod tests {
         assert_eq!(msg.id, "12346".to_string());
         assert_eq!(
             *(msg.date.as_ref().unwrap()),
-            "2000-06-08T22:10:00.0Z".into()
+            Utc.with_ymd_and_hms(2000, 6, 8, 22, 10, 0).unwrap()
         );
         assert_eq!(
             *(msg.message.as_ref().unwrap()),
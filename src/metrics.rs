@@ -0,0 +1,392 @@
+//! Metrics for registry connections and the transactions made over them
+//!
+//! A [`Recorder`] attached through [`EppClient::with_recorder()`](crate::EppClient::with_recorder)
+//! is notified of every transaction, the bytes written to and read from the connection, and the
+//! lifetime of the session. [`PrometheusRecorder`] aggregates these events and renders them in the
+//! Prometheus text exposition format.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::net::ToSocketAddrs;
+//! use std::sync::Arc;
+//!
+//! use epp_client::EppClient;
+//! use epp_client::metrics::PrometheusRecorder;
+//! use tokio::net::TcpListener;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! let recorder = Arc::new(PrometheusRecorder::default());
+//!
+//! // Expose the metrics on http://127.0.0.1:9100/metrics
+//! let listener = TcpListener::bind("127.0.0.1:9100").await.unwrap();
+//! tokio::spawn(recorder.clone().serve(listener));
+//!
+//! let host = "example.com";
+//! let addr = (host, 7000).to_socket_addrs().unwrap().next().unwrap();
+//! let mut client = EppClient::connect("registry_name".to_string(), addr, host, None).await.unwrap();
+//! client.with_recorder(recorder);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::debug;
+
+/// Receives metric events from registry connections
+///
+/// All methods have empty default implementations, so a recorder only needs to implement the
+/// events it is interested in.
+pub trait Recorder: Send + Sync {
+    /// A command completed after `latency`, with the registry's result `code`, or `None` if no
+    /// valid response was received
+    fn transaction(&self, registry: &str, command: &str, code: Option<u16>, latency: Duration) {
+        let _ = (registry, command, code, latency);
+    }
+
+    /// `bytes` were written to the connection, including the frame header
+    fn bytes_sent(&self, registry: &str, bytes: usize) {
+        let _ = (registry, bytes);
+    }
+
+    /// `bytes` were read from the connection, including the frame header
+    fn bytes_received(&self, registry: &str, bytes: usize) {
+        let _ = (registry, bytes);
+    }
+
+    /// A command was held back for `waited` by client-side rate limiting before being sent
+    fn throttled(&self, registry: &str, command: &str, waited: Duration) {
+        let _ = (registry, command, waited);
+    }
+
+    /// A session with the registry was established
+    fn session_opened(&self, registry: &str) {
+        let _ = registry;
+    }
+
+    /// A session with the registry was closed or dropped
+    fn session_closed(&self, registry: &str) {
+        let _ = registry;
+    }
+
+    /// A session with the registry was re-established after the previous one was lost
+    ///
+    /// A [`Pool`](crate::pool::Pool) given a recorder through
+    /// [`Pool::with_recorder()`](crate::pool::Pool::with_recorder) reports the sessions it
+    /// replaces; callers reconnecting by other means should report it here.
+    fn reconnect(&self, registry: &str) {
+        let _ = registry;
+    }
+}
+
+/// Upper bounds, in seconds, of the transaction latency histogram buckets
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A [`Recorder`] aggregating metrics for exposition in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct PrometheusRecorder {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    latency: BTreeMap<(String, String), Histogram>,
+    results: BTreeMap<(String, String, String), u64>,
+    throttled: BTreeMap<(String, String), f64>,
+    bytes_sent: BTreeMap<String, u64>,
+    bytes_received: BTreeMap<String, u64>,
+    sessions: BTreeMap<String, i64>,
+    reconnects: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS.iter()) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+impl PrometheusRecorder {
+    /// Renders the current state of all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "epp_transaction_duration_seconds",
+            "histogram",
+            "Latency of EPP transactions",
+        );
+        for ((registry, command), histogram) in &state.latency {
+            let labels = format!(
+                "registry=\"{}\",command=\"{}\"",
+                escape(registry),
+                escape(command)
+            );
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS.iter()) {
+                let _ = writeln!(
+                    out,
+                    "epp_transaction_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "epp_transaction_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "epp_transaction_duration_seconds_sum{{{}}} {}",
+                labels, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "epp_transaction_duration_seconds_count{{{}}} {}",
+                labels, histogram.count
+            );
+        }
+
+        header(
+            &mut out,
+            "epp_responses_total",
+            "counter",
+            "EPP responses by result code",
+        );
+        for ((registry, command, code), count) in &state.results {
+            let _ = writeln!(
+                out,
+                "epp_responses_total{{registry=\"{}\",command=\"{}\",code=\"{}\"}} {}",
+                escape(registry),
+                escape(command),
+                code,
+                count
+            );
+        }
+
+        header(
+            &mut out,
+            "epp_throttled_seconds_total",
+            "counter",
+            "Time commands spent waiting for client-side rate limits",
+        );
+        for ((registry, command), seconds) in &state.throttled {
+            let _ = writeln!(
+                out,
+                "epp_throttled_seconds_total{{registry=\"{}\",command=\"{}\"}} {}",
+                escape(registry),
+                escape(command),
+                seconds
+            );
+        }
+
+        let per_registry = [
+            (
+                "epp_bytes_sent_total",
+                "counter",
+                "Bytes written to registry connections",
+                &state.bytes_sent,
+            ),
+            (
+                "epp_bytes_received_total",
+                "counter",
+                "Bytes read from registry connections",
+                &state.bytes_received,
+            ),
+            (
+                "epp_reconnects_total",
+                "counter",
+                "Sessions re-established after being lost",
+                &state.reconnects,
+            ),
+        ];
+
+        for (name, kind, help, values) in per_registry.iter() {
+            header(&mut out, name, kind, help);
+            for (registry, value) in values.iter() {
+                let _ = writeln!(
+                    out,
+                    "{}{{registry=\"{}\"}} {}",
+                    name,
+                    escape(registry),
+                    value
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "epp_active_sessions",
+            "gauge",
+            "Currently open registry sessions",
+        );
+        for (registry, value) in &state.sessions {
+            let _ = writeln!(
+                out,
+                "epp_active_sessions{{registry=\"{}\"}} {}",
+                escape(registry),
+                value
+            );
+        }
+
+        out
+    }
+
+    /// Serves the rendered metrics over HTTP to every client connecting to `listener`
+    ///
+    /// The request itself is not inspected, so any path can be scraped.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (mut stream, peer) = listener.accept().await?;
+            let body = self.render();
+
+            tokio::spawn(async move {
+                // Only the start of the request is read; its contents are ignored.
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+
+                if let Err(e) = stream.write_all(response.as_bytes()).await {
+                    debug!("Failed to serve metrics to {}: {}", peer, e);
+                }
+                let _ = stream.shutdown().await;
+            });
+        }
+    }
+}
+
+impl Recorder for PrometheusRecorder {
+    fn transaction(&self, registry: &str, command: &str, code: Option<u16>, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .latency
+            .entry((registry.to_owned(), command.to_owned()))
+            .or_default()
+            .observe(latency.as_secs_f64());
+
+        let code = match code {
+            Some(code) => code.to_string(),
+            None => "error".to_owned(),
+        };
+        *state
+            .results
+            .entry((registry.to_owned(), command.to_owned(), code))
+            .or_default() += 1;
+    }
+
+    fn throttled(&self, registry: &str, command: &str, waited: Duration) {
+        let mut state = self.state.lock().unwrap();
+        *state
+            .throttled
+            .entry((registry.to_owned(), command.to_owned()))
+            .or_default() += waited.as_secs_f64();
+    }
+
+    fn bytes_sent(&self, registry: &str, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        *state.bytes_sent.entry(registry.to_owned()).or_default() += bytes as u64;
+    }
+
+    fn bytes_received(&self, registry: &str, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        *state.bytes_received.entry(registry.to_owned()).or_default() += bytes as u64;
+    }
+
+    fn session_opened(&self, registry: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.sessions.entry(registry.to_owned()).or_default() += 1;
+    }
+
+    fn session_closed(&self, registry: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.sessions.entry(registry.to_owned()).or_default() -= 1;
+    }
+
+    fn reconnect(&self, registry: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state.reconnects.entry(registry.to_owned()).or_default() += 1;
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escapes a label value for the Prometheus text format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{PrometheusRecorder, Recorder};
+
+    #[test]
+    fn render() {
+        let recorder = PrometheusRecorder::default();
+        recorder.session_opened("test");
+        recorder.transaction("test", "check", Some(1000), Duration::from_millis(20));
+        recorder.transaction("test", "check", Some(2303), Duration::from_millis(300));
+        recorder.transaction("test", "create", None, Duration::from_secs(20));
+        recorder.bytes_sent("test", 100);
+        recorder.bytes_received("test", 250);
+        recorder.reconnect("test");
+        recorder.throttled("test", "check", Duration::from_millis(1500));
+
+        let rendered = recorder.render();
+
+        for line in &[
+            "# TYPE epp_transaction_duration_seconds histogram",
+            "epp_transaction_duration_seconds_bucket{registry=\"test\",command=\"check\",le=\"0.01\"} 0",
+            "epp_transaction_duration_seconds_bucket{registry=\"test\",command=\"check\",le=\"0.025\"} 1",
+            "epp_transaction_duration_seconds_bucket{registry=\"test\",command=\"check\",le=\"0.5\"} 2",
+            "epp_transaction_duration_seconds_bucket{registry=\"test\",command=\"create\",le=\"10\"} 0",
+            "epp_transaction_duration_seconds_bucket{registry=\"test\",command=\"create\",le=\"+Inf\"} 1",
+            "epp_transaction_duration_seconds_count{registry=\"test\",command=\"check\"} 2",
+            "epp_responses_total{registry=\"test\",command=\"check\",code=\"1000\"} 1",
+            "epp_responses_total{registry=\"test\",command=\"check\",code=\"2303\"} 1",
+            "epp_responses_total{registry=\"test\",command=\"create\",code=\"error\"} 1",
+            "epp_throttled_seconds_total{registry=\"test\",command=\"check\"} 1.5",
+            "epp_bytes_sent_total{registry=\"test\"} 100",
+            "epp_bytes_received_total{registry=\"test\"} 250",
+            "epp_reconnects_total{registry=\"test\"} 1",
+            "epp_active_sessions{registry=\"test\"} 1",
+        ] {
+            assert!(rendered.lines().any(|l| l == *line), "missing {}", line);
+        }
+    }
+}
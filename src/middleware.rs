@@ -0,0 +1,218 @@
+//! Middleware layers wrapping the request/response exchange with the registry
+//!
+//! Layers registered through [`EppClient::with_middleware()`](crate::EppClient::with_middleware)
+//! see every command sent through `transact()` after it has been serialized, and the result
+//! returned by the registry before it is deserialized into the command's response type. A layer
+//! can inspect or rewrite the request, short-circuit the exchange by returning its own reply, or
+//! call the rest of the chain any number of times to retry.
+//!
+//! ## Example
+//!
+//! ```
+//! use epp_client::middleware::{BoxFuture, Middleware, Next, Reply, Request};
+//! use epp_client::Error;
+//!
+//! /// Logs the result code of every command sent to the registry
+//! struct Audit;
+//!
+//! impl Middleware for Audit {
+//!     fn call<'a>(&'a self, request: Request, mut next: Next<'a>) -> BoxFuture<'a, Result<Reply, Error>> {
+//!         Box::pin(async move {
+//!             let reply = next.run(&request).await?;
+//!             println!("{} {}: {}", request.command, request.client_tr_id, reply.status.result.code);
+//!             Ok(reply)
+//!         })
+//!     }
+//! }
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tracing::{debug, field, info_span, Instrument};
+
+use crate::connection::EppConnection;
+use crate::error::Error;
+use crate::metrics::Recorder;
+use crate::response::{ResponseStatus, ResultDocument};
+use crate::xml::EppXml;
+
+/// A boxed future as returned by [`Middleware::call()`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A serialized EPP command on its way to the registry
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    /// The name of the registry the command is sent to
+    pub registry: String,
+    /// The command name, as given by [`Command::COMMAND`](crate::request::Command::COMMAND)
+    pub command: &'static str,
+    /// The object type, as given by [`Command::OBJECT`](crate::request::Command::OBJECT)
+    pub object: Option<&'static str>,
+    /// The client transaction ID the command was submitted with
+    pub client_tr_id: String,
+    /// The serialized EPP XML document
+    pub xml: String,
+}
+
+/// The registry's reply to a [`Request`]
+#[derive(Debug)]
+pub struct Reply {
+    /// The raw EPP XML response
+    pub xml: String,
+    /// The result and transaction IDs parsed from the response
+    pub status: ResponseStatus,
+}
+
+impl Reply {
+    /// Parses the result and transaction IDs from a raw EPP XML response
+    #[allow(clippy::result_large_err)]
+    pub fn new(xml: String) -> Result<Self, Error> {
+        let status = ResultDocument::deserialize(&xml)?.data;
+        Ok(Self { xml, status })
+    }
+}
+
+/// A layer wrapping the exchange of a command with the registry
+pub trait Middleware: Send + Sync {
+    /// Handles `request`, usually by passing it on to `next` and returning the result
+    fn call<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Reply, Error>>;
+}
+
+impl<M: Middleware + ?Sized> Middleware for Arc<M> {
+    fn call<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Reply, Error>> {
+        (**self).call(request, next)
+    }
+}
+
+/// The remainder of the middleware chain, ending at the registry connection
+pub struct Next<'a> {
+    layers: &'a [Arc<dyn Middleware>],
+    transport: &'a mut (dyn Transport + Send),
+}
+
+impl<'a> Next<'a> {
+    /// Passes `request` through the remaining layers to the registry and returns its reply
+    ///
+    /// May be called more than once to retry a request.
+    pub async fn run(&mut self, request: &Request) -> Result<Reply, Error> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => {
+                let next = Next {
+                    layers,
+                    transport: &mut *self.transport,
+                };
+                layer.call(request.clone(), next).await
+            }
+            None => Reply::new(self.transport.transact(&request.xml).await?),
+        }
+    }
+}
+
+/// Sends `request` through `layers` to `connection`
+///
+/// The exchange runs inside a `transaction` span carrying the registry, command, object type and
+/// client transaction ID; the server transaction ID, result code and latency are recorded on the
+/// span once the reply has been received.
+pub(crate) async fn dispatch<IO>(
+    layers: &[Arc<dyn Middleware>],
+    connection: &mut EppConnection<IO>,
+    request: &Request,
+) -> Result<Reply, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    let recorder = connection.recorder.clone();
+    let mut next = Next {
+        layers,
+        transport: &mut *connection,
+    };
+
+    instrument(recorder, request, next.run(request)).await
+}
+
+/// A type-erased [`dispatch()`], for callers that can't name the `Send` bound on `IO` themselves
+pub(crate) type Dispatch<IO> = for<'a> fn(
+    &'a [Arc<dyn Middleware>],
+    &'a mut EppConnection<IO>,
+    &'a Request,
+) -> BoxFuture<'a, Result<Reply, Error>>;
+
+pub(crate) fn dispatch_boxed<'a, IO>(
+    layers: &'a [Arc<dyn Middleware>],
+    connection: &'a mut EppConnection<IO>,
+    request: &'a Request,
+) -> BoxFuture<'a, Result<Reply, Error>>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    Box::pin(dispatch(layers, connection, request))
+}
+
+/// Sends `request` straight to `connection`, with the same instrumentation as [`dispatch()`]
+///
+/// Unlike `dispatch()` this doesn't require the connection to be `Send`, so it serves clients
+/// without any middleware layers.
+pub(crate) async fn send<IO>(
+    connection: &mut EppConnection<IO>,
+    request: &Request,
+) -> Result<Reply, Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let recorder = connection.recorder.clone();
+    let exchange = async { Reply::new(connection.transact(&request.xml).await?) };
+    instrument(recorder, request, exchange).await
+}
+
+async fn instrument(
+    recorder: Option<Arc<dyn Recorder>>,
+    request: &Request,
+    exchange: impl Future<Output = Result<Reply, Error>>,
+) -> Result<Reply, Error> {
+    let span = info_span!(
+        "transaction",
+        registry = %request.registry,
+        command = request.command,
+        object = request.object.unwrap_or_default(),
+        cl_trid = %request.client_tr_id,
+        sv_trid = field::Empty,
+        code = field::Empty,
+        latency_ms = field::Empty,
+    );
+
+    let start = Instant::now();
+    let result = exchange.instrument(span.clone()).await;
+
+    let latency = start.elapsed();
+    span.record("latency_ms", latency.as_millis() as u64);
+    match &result {
+        Ok(reply) => {
+            span.record("sv_trid", field::display(&reply.status.tr_ids.server_tr_id));
+            span.record("code", reply.status.result.code);
+            span.in_scope(|| debug!("Transaction completed"));
+        }
+        Err(e) => span.in_scope(|| debug!("Transaction failed: {}", e)),
+    }
+
+    if let Some(recorder) = recorder {
+        let code = result.as_ref().ok().map(|reply| reply.status.result.code);
+        recorder.transaction(&request.registry, request.command, code, latency);
+    }
+
+    result
+}
+
+/// The end of the middleware chain
+trait Transport {
+    fn transact<'a>(&'a mut self, xml: &'a str) -> BoxFuture<'a, Result<String, Error>>;
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin + Send> Transport for EppConnection<IO> {
+    fn transact<'a>(&'a mut self, xml: &'a str) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(EppConnection::transact(self, xml))
+    }
+}
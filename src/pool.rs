@@ -0,0 +1,317 @@
+//! A pool of logged-in registry sessions shared between tasks
+//!
+//! Registries limit the number of concurrent sessions per registrar. A [`Pool`] logs in a few
+//! sessions, each owned by an [`EppHandle`], and spreads the commands of any number of tasks over
+//! them. Sessions that fail are skipped and, when the pool knows how to open new ones, replaced
+//! in the background. It backs both the `epp-proxy` and the `epp-gateway` binaries.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::config::RegistryConfig;
+//! use epp_client::domain::DomainCheck;
+//! use epp_client::pool::Pool;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//! let registry = RegistryConfig {
+//!     name: "registry_name".into(),
+//!     host: "epp.example.com".into(),
+//!     port: 700,
+//!     username: "username".into(),
+//!     password: "password".into(),
+//!     extensions: Vec::new(),
+//!     certificate: None,
+//!     key: None,
+//! };
+//!
+//! // Two registry sessions, each queueing up to 16 commands
+//! let pool = Pool::connect(&registry, 2, 16).await.unwrap();
+//! let check = DomainCheck::new(vec!["eppdev.com"]);
+//! let response = pool.transact(&check, "transaction-id").await.unwrap();
+//! pool.shutdown().await.unwrap();
+//! }
+//! ```
+
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::client::RequestData;
+use crate::config::RegistryConfig;
+use crate::error::Error;
+use crate::handle::{check_queue_depth, EppHandle};
+use crate::hello::Greeting;
+use crate::logout::Logout;
+use crate::metrics::Recorder;
+use crate::middleware::BoxFuture;
+use crate::request::{Command, Extension, Transaction};
+use crate::response::Response;
+
+/// The default number of registry sessions opened by the binaries using a pool
+pub(crate) fn default_sessions() -> usize {
+    1
+}
+
+/// The default number of commands each session queues
+pub(crate) fn default_queue_depth() -> usize {
+    16
+}
+
+/// The delay before the first attempt to replace a closed session is retried
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest delay between attempts to replace a closed session
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// Opens a logged-in session to replace the closed session with the given index
+pub type Connector = dyn Fn(usize) -> BoxFuture<'static, Result<EppHandle, Error>> + Send + Sync;
+
+/// A pool of logged-in registry sessions
+///
+/// Commands are distributed over the sessions round-robin, skipping sessions that have been
+/// closed. Cloning a pool shares its sessions.
+#[derive(Clone)]
+pub struct Pool {
+    sessions: Arc<[Session]>,
+    next: Arc<AtomicUsize>,
+    connector: Option<Arc<Connector>>,
+    recorder: Option<Arc<dyn Recorder>>,
+    closing: Arc<AtomicBool>,
+}
+
+struct Session {
+    handle: Mutex<EppHandle>,
+    reconnecting: AtomicBool,
+}
+
+impl Pool {
+    /// Creates a pool from handles to logged-in registry sessions
+    ///
+    /// Closed sessions are skipped, but not replaced unless a connector is set with
+    /// [`Pool::reconnect()`]. Panics if `handles` is empty.
+    pub fn new(handles: Vec<EppHandle>) -> Self {
+        assert!(!handles.is_empty(), "a pool needs at least one session");
+        let sessions = handles
+            .into_iter()
+            .map(|handle| Session {
+                handle: Mutex::new(handle),
+                reconnecting: AtomicBool::new(false),
+            })
+            .collect();
+
+        Self {
+            sessions,
+            next: Arc::new(AtomicUsize::new(0)),
+            connector: None,
+            recorder: None,
+            closing: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Replaces closed sessions with sessions opened by `connector`
+    ///
+    /// A session closes when its connection to the registry fails. The first command to find it
+    /// closed starts a background task calling `connector` with the session's index until it
+    /// succeeds, backing off between attempts. Must be used from within a tokio runtime.
+    pub fn reconnect(
+        mut self,
+        connector: impl Fn(usize) -> BoxFuture<'static, Result<EppHandle, Error>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.connector = Some(Arc::new(connector));
+        self
+    }
+
+    /// Reports the sessions replaced after a failure to `recorder`
+    ///
+    /// Metrics for the sessions themselves come from recorders attached to their clients, see
+    /// [`EppClient::with_recorder()`](crate::EppClient::with_recorder).
+    pub fn with_recorder(mut self, recorder: Arc<dyn Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Opens and logs in `sessions` sessions to `registry`, each queueing up to `queue_depth`
+    /// commands
+    ///
+    /// `queue_depth` must be at least 1, or [`Error::Invalid`] is returned before connecting.
+    /// Sessions that fail are replaced by logging in to `registry` again.
+    pub async fn connect(
+        registry: &RegistryConfig,
+        sessions: usize,
+        queue_depth: usize,
+    ) -> Result<Self, Error> {
+        check_queue_depth(queue_depth)?;
+        let mut handles = Vec::with_capacity(sessions);
+        for i in 0..sessions.max(1) {
+            let client = registry.connect(&format!("pool-login-{}", i + 1)).await?;
+            handles.push(client.into_handle(queue_depth)?);
+        }
+
+        info!("Opened {} sessions to {}", handles.len(), registry.name);
+        let registry = registry.clone();
+        let logins = Arc::new(AtomicUsize::new(handles.len()));
+        Ok(Self::new(handles).reconnect(move |_| {
+            let registry = registry.clone();
+            let id = format!("pool-login-{}", logins.fetch_add(1, Ordering::Relaxed) + 1);
+            Box::pin(async move {
+                let client = registry.connect(&id).await?;
+                client.into_handle(queue_depth)
+            })
+        }))
+    }
+
+    /// Sends a command through the next open session
+    pub async fn transact<'a, C, E>(
+        &self,
+        data: impl Into<RequestData<'a, C, E>> + 'a,
+        id: &str,
+    ) -> Result<Response<C::Response, E::Response>, Error>
+    where
+        C: Transaction<E> + Command + 'a,
+        E: Extension + 'a,
+    {
+        self.handle()?.transact(data, id).await
+    }
+
+    /// Sends raw EPP XML through the next open session and returns the raw EPP XML response
+    pub async fn transact_xml(&self, xml: &str) -> Result<String, Error> {
+        self.handle()?.transact_xml(xml).await
+    }
+
+    /// Returns the greeting received from the registry
+    #[allow(clippy::result_large_err)]
+    pub fn greeting(&self) -> Result<Greeting, Error> {
+        self.sessions[0].handle().greeting()
+    }
+
+    /// Logs out and closes every open session
+    ///
+    /// Closed sessions are no longer replaced once the pool is shutting down. Every session is
+    /// closed even if closing some of them fails; the failures are reported together.
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        self.closing.store(true, Ordering::SeqCst);
+        let mut errors = Vec::new();
+        for (i, session) in self.sessions.iter().enumerate() {
+            let handle = session.handle();
+            if handle.is_closed() {
+                continue;
+            }
+
+            let id = format!("pool-logout-{}", i + 1);
+            if let Err(e) = handle.transact(&Logout, &id).await {
+                warn!("Failed to log out registry session {}: {}", i + 1, e);
+            }
+            if let Err(e) = handle.shutdown().await {
+                warn!("Failed to close registry session {}: {}", i + 1, e);
+                errors.push((i, e));
+            }
+        }
+
+        if errors.len() > 1 {
+            let errors = errors
+                .iter()
+                .map(|(i, e)| format!("session {}: {}", i + 1, e))
+                .collect::<Vec<_>>();
+            return Err(Error::Other(
+                format!("failed to close registry sessions: {}", errors.join("; ")).into(),
+            ));
+        }
+
+        match errors.pop() {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the next open session, starting the replacement of closed ones passed over
+    #[allow(clippy::result_large_err)]
+    fn handle(&self) -> Result<EppHandle, Error> {
+        for _ in 0..self.sessions.len() {
+            let i = self.next.fetch_add(1, Ordering::Relaxed) % self.sessions.len();
+            let handle = self.sessions[i].handle();
+            if !handle.is_closed() {
+                return Ok(handle);
+            }
+
+            self.replace(i);
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotConnected,
+            "all registry sessions are closed",
+        )
+        .into())
+    }
+
+    /// Starts a background task replacing the closed session `i`, unless one is running already
+    fn replace(&self, i: usize) {
+        let connector = match &self.connector {
+            Some(connector) => connector.clone(),
+            None => return,
+        };
+
+        let session = &self.sessions[i];
+        if self.closing.load(Ordering::SeqCst) || session.reconnecting.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        warn!("Registry session {} closed, reconnecting", i + 1);
+        let registry = session.handle().registry().to_owned();
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut delay = RECONNECT_DELAY;
+            while !pool.closing.load(Ordering::SeqCst) {
+                match connector(i).await {
+                    Ok(handle) if pool.closing.load(Ordering::SeqCst) => {
+                        let _ = handle.shutdown().await;
+                    }
+                    Ok(handle) => {
+                        info!("Replaced registry session {}", i + 1);
+                        *pool.sessions[i].handle.lock().unwrap() = handle;
+                        if let Some(recorder) = &pool.recorder {
+                            recorder.reconnect(&registry);
+                        }
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to replace registry session {}, retrying in {:?}: {}",
+                            i + 1,
+                            delay,
+                            e
+                        );
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    }
+                }
+            }
+
+            pool.sessions[i].reconnecting.store(false, Ordering::SeqCst);
+        });
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("sessions", &self.sessions.len())
+            .field("reconnect", &self.connector.is_some())
+            .field("recorder", &self.recorder.is_some())
+            .finish()
+    }
+}
+
+impl Session {
+    fn handle(&self) -> EppHandle {
+        self.handle.lock().unwrap().clone()
+    }
+}
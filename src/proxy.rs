@@ -0,0 +1,417 @@
+//! An EPP proxy sharing a few registry sessions between many local clients
+//!
+//! Registries limit the number of concurrent sessions per registrar. A [`Proxy`] logs in to the
+//! registry with a small [`Pool`] of sessions and serves any number of local EPP clients through
+//! a [`Server`](crate::server::Server). Local clients log in to the proxy with their own
+//! credentials; their commands are checked against a per-client [`Acl`] and queued onto the
+//! pool, which sends them to the registry one at a time per session.
+//!
+//! The client transaction ID of every forwarded command is replaced by one unique to the proxy,
+//! so that IDs chosen independently by different clients never collide in the registry's logs,
+//! and restored in the response before it is returned to the client.
+//!
+//! The `epp-proxy` binary, built with the `proxy` feature, runs a proxy configured by a TOML
+//! file. See [`ProxyConfig`] for its format.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::config::RegistryConfig;
+//! use epp_client::pool::Pool;
+//! use epp_client::proxy::{Acl, ClientConfig, Proxy};
+//! use epp_client::server::Server;
+//! use tokio::net::TcpListener;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//! let registry = RegistryConfig {
+//!     name: "registry_name".into(),
+//!     host: "epp.example.com".into(),
+//!     port: 700,
+//!     username: "username".into(),
+//!     password: "password".into(),
+//!     extensions: Vec::new(),
+//!     certificate: None,
+//!     key: None,
+//! };
+//!
+//! // Two registry sessions, each queueing up to 16 commands
+//! let pool = Pool::connect(&registry, 2, 16).await.unwrap();
+//! let proxy = Proxy::new(pool).client(ClientConfig {
+//!     id: "billing".into(),
+//!     password: "billing-password".into(),
+//!     acl: Acl {
+//!         commands: Some(vec!["check".into(), "info".into()]),
+//!         objects: Some(vec!["domain".into()]),
+//!     },
+//! });
+//!
+//! let greeting = proxy.greeting().unwrap();
+//! let listener = TcpListener::bind("127.0.0.1:7000").await.unwrap();
+//! Server::new(proxy, greeting).run(listener).await.unwrap();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::Utc;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use crate::common::NoExtension;
+use crate::config::RegistryConfig;
+use crate::error::Error;
+use crate::hello::{Greeting, GreetingBuilder};
+use crate::login::Login;
+use crate::middleware::BoxFuture;
+use crate::pool::Pool;
+use crate::response::ResponseDocument;
+use crate::server::{Handler, Request};
+use crate::xml::EppXml;
+
+/// The configuration of the `epp-proxy` binary
+///
+/// ```toml
+/// listen = "127.0.0.1:7000"
+/// sessions = 2
+///
+/// [registry]
+/// name = "example"
+/// host = "epp.example.com"
+/// username = "registrar"
+/// password = "secret"
+///
+/// [[clients]]
+/// id = "billing"
+/// password = "billing-secret"
+/// commands = ["check", "info"]
+/// objects = ["domain"]
+///
+/// [[clients]]
+/// id = "provisioning"
+/// password = "provisioning-secret"
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ProxyConfig {
+    /// The address to accept local clients on
+    pub listen: SocketAddr,
+    /// The number of registry sessions to open
+    #[serde(default = "crate::pool::default_sessions")]
+    pub sessions: usize,
+    /// The number of commands each registry session queues before clients have to wait, at
+    /// least 1
+    #[serde(default = "crate::pool::default_queue_depth")]
+    pub queue_depth: usize,
+    /// The registry to forward commands to
+    pub registry: RegistryConfig,
+    /// The local clients allowed to log in to the proxy
+    #[serde(default)]
+    pub clients: Vec<ClientConfig>,
+}
+
+/// The credentials and permissions of a local client
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ClientConfig {
+    /// The client ID to log in to the proxy with
+    pub id: String,
+    /// The password to log in to the proxy with
+    pub password: String,
+    /// The commands the client may send
+    #[serde(flatten)]
+    pub acl: Acl,
+}
+
+/// The commands a local client may send through a [`Proxy`]
+///
+/// Poll commands, which don't operate on an object, are only restricted by `commands`. Object
+/// types are resolved from the namespace of the command's object element (see
+/// [`object_type()`](crate::server::object_type)); once `objects` is set, commands on objects of
+/// unknown type are refused.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Acl {
+    /// The command names allowed, e.g. `check` or `transfer`, or all commands if `None`
+    pub commands: Option<Vec<String>>,
+    /// The object types allowed, e.g. `domain`, or all object types if `None`
+    pub objects: Option<Vec<String>>,
+}
+
+impl Acl {
+    /// Returns whether `command` on `object` is allowed
+    pub fn allows(&self, command: &str, object: Option<&str>) -> bool {
+        let command_allowed = match &self.commands {
+            Some(commands) => commands.iter().any(|c| c == command),
+            None => true,
+        };
+
+        let object_allowed = match (&self.objects, object) {
+            (None, _) => true,
+            (Some(objects), Some(object)) => objects.iter().any(|o| o == object),
+            (Some(_), None) => command == "poll",
+        };
+
+        command_allowed && object_allowed
+    }
+}
+
+/// A [`Handler`] forwarding the commands of local clients to a [`Pool`]
+///
+/// Logins and logouts are answered by the proxy itself, authenticating clients against the
+/// configured [`ClientConfig`]s. Commands from clients that aren't logged in are refused with a
+/// 2002 result, and commands their [`Acl`] doesn't allow with a 2201 result.
+#[derive(Debug)]
+pub struct Proxy {
+    pool: Pool,
+    clients: HashMap<String, ClientConfig>,
+    prefix: String,
+    client_tr_id: AtomicU64,
+}
+
+impl Proxy {
+    /// Creates a proxy forwarding commands to `pool`, with no clients allowed to log in yet
+    pub fn new(pool: Pool) -> Self {
+        Self {
+            pool,
+            clients: HashMap::new(),
+            prefix: format!("proxy-{}", Utc::now().timestamp()),
+            client_tr_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Allows the client described by `client` to log in
+    pub fn client(mut self, client: ClientConfig) -> Self {
+        self.clients.insert(client.id.clone(), client);
+        self
+    }
+
+    /// The pool commands are forwarded to
+    pub fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    /// Returns a greeting for local clients announcing the services of the registry
+    #[allow(clippy::result_large_err)]
+    pub fn greeting(&self) -> Result<GreetingBuilder, Error> {
+        let registry = self.pool.greeting()?;
+        let services = &registry.svc_menu.services;
+        let objects = services
+            .obj_uris
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let mut greeting = Greeting::builder(&registry.service_id)
+            .objects(&objects.iter().map(String::as_str).collect::<Vec<_>>());
+        let extensions = services
+            .svc_ext
+            .as_ref()
+            .and_then(|ext| ext.ext_uris.as_ref());
+        for uri in extensions.into_iter().flatten() {
+            greeting = greeting.extension(uri.as_ref());
+        }
+
+        Ok(greeting)
+    }
+
+    /// Authenticates a login against the configured clients
+    #[allow(clippy::result_large_err)]
+    fn login(&self, session: &mut Option<String>, request: &Request) -> Result<u16, Error> {
+        if session.is_some() {
+            return Ok(2002);
+        }
+
+        let login = request.data::<Login>()?;
+        let id = login.username.to_string();
+        match self.clients.get(&id) {
+            Some(client) if client.password == login.password.to_string() => {
+                info!("Client {} logged in", id);
+                *session = Some(id);
+                Ok(1000)
+            }
+            _ => {
+                warn!("Failed login to proxy as {}", id);
+                Ok(2200)
+            }
+        }
+    }
+
+    /// Forwards a command to the pool under a new client transaction ID
+    async fn forward(&self, request: &Request) -> Result<String, Error> {
+        let n = self.client_tr_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let tr_id = format!("{}-{}", self.prefix, n);
+
+        let (xml, original) = replace_tr_id(&request.xml, &tr_id);
+        let response = self.pool.transact_xml(&xml).await?;
+        Ok(match original {
+            Some(original) => replace_tr_id(&response, original).0,
+            None => response,
+        })
+    }
+}
+
+impl Handler for Proxy {
+    type Session = Option<String>;
+
+    fn handle<'a>(
+        &'a self,
+        session: &'a mut Option<String>,
+        request: &'a Request,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(async move {
+            let code = match request.command.as_str() {
+                "login" => self.login(session, request)?,
+                "logout" => 1500,
+                command => match session.as_ref().and_then(|id| self.clients.get(id)) {
+                    None => 2002,
+                    Some(client) if !client.acl.allows(command, request.object.as_deref()) => {
+                        debug!("Refused {} from client {}", command, client.id);
+                        2201
+                    }
+                    Some(_) => return self.forward(request).await,
+                },
+            };
+
+            ResponseDocument::new(request.response::<(), NoExtension>(code)).serialize()
+        })
+    }
+}
+
+/// Replaces the text of the clTRID element of the command or response in `xml` with `tr_id`,
+/// returning the new XML and the replaced text as it appeared in `xml`
+fn replace_tr_id<'x>(xml: &'x str, tr_id: &str) -> (String, Option<&'x str>) {
+    match find_tr_id(xml) {
+        Some((start, end)) => {
+            let replaced = format!("{}{}{}", &xml[..start], tr_id, &xml[end..]);
+            (replaced, Some(&xml[start..end]))
+        }
+        None => (xml.to_owned(), None),
+    }
+}
+
+/// Returns the byte range of the text of the clTRID element under &lt;command&gt; or
+/// &lt;response&gt;&lt;trID&gt;, matching elements by their local names like
+/// [`Request::parse()`] does
+fn find_tr_id(xml: &str) -> Option<(usize, usize)> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut start = None;
+
+    loop {
+        let before = reader.buffer_position();
+        match reader.read_event(&mut buf).ok()? {
+            Event::Start(ref e) => {
+                path.push(e.local_name().to_vec());
+                let parent = path[..path.len() - 1].iter().map(Vec::as_slice);
+                let in_command = parent.clone().eq([&b"epp"[..], b"command"]);
+                let in_response = parent.eq([&b"epp"[..], b"response", b"trID"]);
+                if e.local_name() == b"clTRID" && (in_command || in_response) {
+                    start = Some(reader.buffer_position());
+                }
+            }
+            Event::End(_) => {
+                if let Some(start) = start {
+                    return Some((start, before));
+                }
+                path.pop();
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_tr_id, Acl, ProxyConfig};
+
+    #[test]
+    fn tr_ids() {
+        let xml = "<epp><command><logout/><clTRID>a&amp;b</clTRID></command></epp>";
+        let (replaced, original) = replace_tr_id(xml, "proxy-1");
+        assert_eq!(
+            replaced,
+            "<epp><command><logout/><clTRID>proxy-1</clTRID></command></epp>"
+        );
+        assert_eq!(original, Some("a&amp;b"));
+        assert_eq!(replace_tr_id(&replaced, original.unwrap()).0, xml);
+
+        let xml = "<epp><command><logout/></command></epp>";
+        assert_eq!(replace_tr_id(xml, "proxy-1"), (xml.to_owned(), None));
+
+        // Prefixed elements and elements with attributes are found too, but not elements of the
+        // same name elsewhere in the document
+        let xml = r#"<e:epp xmlns:e="urn:ietf:params:xml:ns:epp-1.0"><e:command><e:logout/><e:extension><x:clTRID xmlns:x="urn:example">x</x:clTRID></e:extension><e:clTRID a="b">client-1</e:clTRID></e:command></e:epp>"#;
+        let (replaced, original) = replace_tr_id(xml, "proxy-2");
+        assert_eq!(original, Some("client-1"));
+        assert_eq!(replaced, xml.replace(">client-1<", ">proxy-2<"));
+
+        let xml = "<epp:epp xmlns:epp=\"urn:ietf:params:xml:ns:epp-1.0\"><epp:response>\
+                   <epp:trID><epp:clTRID>proxy-2</epp:clTRID><epp:svTRID>sv-1</epp:svTRID>\
+                   </epp:trID></epp:response></epp:epp>";
+        let (restored, original) = replace_tr_id(xml, "client-1");
+        assert_eq!(original, Some("proxy-2"));
+        assert!(restored.contains("<epp:clTRID>client-1</epp:clTRID>"));
+    }
+
+    #[test]
+    fn acl() {
+        assert!(Acl::default().allows("transfer", Some("domain")));
+
+        let acl = Acl {
+            commands: Some(vec!["check".into(), "info".into(), "poll".into()]),
+            objects: Some(vec!["domain".into()]),
+        };
+        assert!(acl.allows("check", Some("domain")));
+        assert!(acl.allows("poll", None));
+        assert!(!acl.allows("check", Some("contact")));
+        assert!(!acl.allows("create", Some("domain")));
+        assert!(!acl.allows("check", None));
+
+        let acl = Acl {
+            commands: None,
+            objects: Some(vec!["domain".into()]),
+        };
+        assert!(acl.allows("create", Some("domain")));
+        assert!(!acl.allows("create", None));
+        assert!(Acl::default().allows("create", None));
+    }
+
+    #[test]
+    fn config() {
+        let config: ProxyConfig = toml::from_str(
+            r#"
+            listen = "127.0.0.1:7000"
+            sessions = 2
+
+            [registry]
+            name = "example"
+            host = "epp.example.com"
+            username = "registrar"
+            password = "secret"
+
+            [[clients]]
+            id = "billing"
+            password = "billing-secret"
+            commands = ["check", "info"]
+            objects = ["domain"]
+
+            [[clients]]
+            id = "provisioning"
+            password = "provisioning-secret"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.sessions, 2);
+        assert_eq!(config.queue_depth, 16);
+        assert_eq!(config.registry.name, "example");
+        assert_eq!(config.clients.len(), 2);
+        assert!(!config.clients[0].acl.allows("create", Some("domain")));
+        assert_eq!(config.clients[1].acl, Acl::default());
+    }
+}
@@ -0,0 +1,275 @@
+//! Client-side rate limiting of commands sent to registries
+//!
+//! Registries enforce command quotas and answer with a 2502 result or drop the session when they
+//! are exceeded. A [`RateLimiter`] holds token buckets keyed by registry and, optionally, by
+//! command, and delays commands until every applicable bucket has a token available. It is
+//! registered as [`Middleware`], so a single limiter can be shared by all clients connected to
+//! the same registry.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::net::ToSocketAddrs;
+//! use std::sync::Arc;
+//!
+//! use epp_client::EppClient;
+//! use epp_client::ratelimit::{Quota, RateLimiter};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! // At most 10 commands per second overall, of which at most 5 may be checks,
+//! // and 60 creates per minute
+//! let limiter = Arc::new(
+//!     RateLimiter::default()
+//!         .limit("registry_name", None, Quota::per_second(10))
+//!         .limit("registry_name", Some("check"), Quota::per_second(5))
+//!         .limit("registry_name", Some("create"), Quota::per_minute(60)),
+//! );
+//!
+//! let host = "example.com";
+//! let addr = (host, 7000).to_socket_addrs().unwrap().next().unwrap();
+//! let mut client = EppClient::connect("registry_name".to_string(), addr, host, None).await.unwrap();
+//! client.with_middleware(limiter.clone());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use crate::error::Error;
+use crate::metrics::Recorder;
+use crate::middleware::{BoxFuture, Middleware, Next, Reply, Request};
+
+/// The rate at which a token bucket refills, and the burst it allows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quota {
+    /// The number of commands allowed per `period`, which is also the maximum burst size
+    pub commands: u32,
+    /// The period over which `commands` are allowed
+    pub period: Duration,
+}
+
+impl Quota {
+    /// Creates a quota of `commands` per `period`
+    ///
+    /// Panics if `commands` is zero or `period` is empty.
+    pub fn new(commands: u32, period: Duration) -> Self {
+        assert!(commands > 0, "quota must allow at least one command");
+        assert!(!period.is_zero(), "quota period must not be empty");
+        Self { commands, period }
+    }
+
+    /// Creates a quota of `commands` per second
+    pub fn per_second(commands: u32) -> Self {
+        Self::new(commands, Duration::from_secs(1))
+    }
+
+    /// Creates a quota of `commands` per minute
+    pub fn per_minute(commands: u32) -> Self {
+        Self::new(commands, Duration::from_secs(60))
+    }
+}
+
+/// The registry and, optionally, the command a bucket applies to
+type Key = (String, Option<String>);
+
+/// Token buckets for commands sent to one or more registries
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<Key, Bucket>>,
+    recorder: Option<Arc<dyn Recorder>>,
+}
+
+impl RateLimiter {
+    /// Limits commands sent to `registry` to `quota`
+    ///
+    /// With `command` set to `None` the quota applies to all commands sent to the registry;
+    /// otherwise it only applies to commands with that name (as given by
+    /// [`Command::COMMAND`](crate::request::Command::COMMAND)). A command must satisfy both its
+    /// registry-wide and its command-specific quota before it is sent.
+    pub fn limit(self, registry: &str, command: Option<&str>, quota: Quota) -> Self {
+        let key = (registry.to_owned(), command.map(str::to_owned));
+        self.buckets
+            .lock()
+            .unwrap()
+            .insert(key, Bucket::new(quota, Instant::now()));
+        self
+    }
+
+    /// Reports the time commands spend waiting for a token to `recorder`
+    pub fn with_recorder(mut self, recorder: Arc<dyn Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Reserves a token at `now` from the bucket for `command` on `registry`, or from the
+    /// registry-wide bucket if `command` is `None`, and returns how long the caller has to wait
+    /// before the reservation becomes valid
+    fn reserve(&self, registry: &str, command: Option<&str>, now: Instant) -> Duration {
+        let key = (registry.to_owned(), command.map(str::to_owned));
+        self.buckets
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .map(|bucket| bucket.reserve(now))
+            .unwrap_or_default()
+    }
+}
+
+impl Middleware for RateLimiter {
+    fn call<'a>(
+        &'a self,
+        request: Request,
+        mut next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Reply, Error>> {
+        Box::pin(async move {
+            // The command-specific bucket goes first, and the registry-wide token is only
+            // reserved once the command is actually due. Reserving both up front would spend a
+            // registry token on a command that is then held back by its own quota, letting the
+            // commands queued behind it exceed the registry quota at the time they are sent.
+            let mut waited = Duration::ZERO;
+            for command in [Some(request.command), None] {
+                let wait = self.reserve(&request.registry, command, Instant::now());
+                if wait.is_zero() {
+                    continue;
+                }
+
+                debug!(
+                    "{}: Delaying {} by {:?} to stay within quota",
+                    request.registry, request.command, wait
+                );
+                tokio::time::sleep(wait).await;
+                waited += wait;
+            }
+
+            match &self.recorder {
+                Some(recorder) if !waited.is_zero() => {
+                    recorder.throttled(&request.registry, request.command, waited)
+                }
+                _ => {}
+            }
+
+            next.run(&request).await
+        })
+    }
+}
+
+/// A token bucket holding up to `capacity` tokens, refilled at `rate` tokens per second
+///
+/// Tokens are reserved up front, so the balance may go negative; the debt determines how long
+/// the reserving caller has to wait, which keeps waiting callers in the order they arrived.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(quota: Quota, now: Instant) -> Self {
+        let capacity = f64::from(quota.commands);
+        Self {
+            capacity,
+            rate: capacity / quota.period.as_secs_f64(),
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+
+        self.tokens -= 1.0;
+        if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Bucket, Quota, RateLimiter};
+
+    #[test]
+    fn bucket() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(Quota::per_second(2), start);
+
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(start), Duration::from_secs(1));
+
+        // After two seconds the debt has been paid off and the bucket is full again
+        let later = start + Duration::from_secs(2);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn keys() {
+        let now = Instant::now();
+        let limiter = RateLimiter::default()
+            .limit("test", None, Quota::per_second(3))
+            .limit("test", Some("check"), Quota::per_minute(1));
+
+        assert_eq!(limiter.reserve("test", Some("check"), now), Duration::ZERO);
+        assert!(limiter.reserve("test", Some("check"), now) > Duration::from_secs(59));
+        assert_eq!(limiter.reserve("test", Some("info"), now), Duration::ZERO);
+        assert_eq!(limiter.reserve("test", None, now), Duration::ZERO);
+        assert_eq!(limiter.reserve("other", Some("check"), now), Duration::ZERO);
+        assert_eq!(limiter.reserve("other", None, now), Duration::ZERO);
+    }
+
+    #[test]
+    fn slow_command_quota() {
+        let start = Instant::now();
+        let limiter = RateLimiter::default()
+            .limit("test", None, Quota::per_second(1))
+            .limit(
+                "test",
+                Some("check"),
+                Quota::new(1, Duration::from_secs(10)),
+            );
+
+        // The first check is sent right away; the second one has to wait for its own quota
+        assert_eq!(
+            limiter.reserve("test", Some("check"), start),
+            Duration::ZERO
+        );
+        assert_eq!(limiter.reserve("test", None, start), Duration::ZERO);
+        let check = start + limiter.reserve("test", Some("check"), start);
+        assert_eq!(check, start + Duration::from_secs(10));
+
+        // It doesn't hold a registry token while it waits, so the info commands queued behind it
+        // go out in the meantime, one per second
+        let mut sent = vec![start];
+        sent.extend((0..12).map(|_| start + limiter.reserve("test", None, start)));
+        assert_eq!(sent[1], start + Duration::from_secs(1));
+
+        // Once due, the check takes its registry token behind the info commands
+        sent.push(check + limiter.reserve("test", None, check));
+
+        // However the commands are interleaved, the registry never sees more than one per second
+        sent.sort();
+        for pair in sent.windows(2) {
+            assert!(
+                pair[1] - pair[0] >= Duration::from_millis(999),
+                "{:?}",
+                pair
+            );
+        }
+    }
+}
@@ -0,0 +1,640 @@
+//! Recording of EPP sessions and their deterministic replay
+//!
+//! A [`SessionRecorder`] wraps the stream passed to [`EppClient::new()`](crate::EppClient::new)
+//! and writes every frame exchanged with the registry to a sink, one line per frame. Passwords
+//! are redacted before they are written. Frames are written by a background thread, so a slow
+//! sink never blocks the session; the recording is complete once the recorder has been dropped.
+//! A recorded session can be fed back to a client with [`Replay`], which answers the client with
+//! the recorded responses in order, so a failure seen in production can be reproduced offline or
+//! turned into a regression test.
+//!
+//! Each line holds the direction (`sent` or `received`), an RFC 3339 timestamp, the registry
+//! name and the frame's XML, separated by tabs. Backslashes, tabs and line breaks in the XML are
+//! escaped as `\\`, `\t`, `\r` and `\n`.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::EppClient;
+//! use epp_client::recording::{Replay, SessionRecorder};
+//! use tokio::net::TcpStream;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! // Record a session
+//! let stream = TcpStream::connect("127.0.0.1:700").await.unwrap();
+//! let stream = SessionRecorder::to_file(stream, "registry_name", "session.log").unwrap();
+//! let mut client = EppClient::new("registry_name".to_string(), stream).await.unwrap();
+//!
+//! // ... and replay it later
+//! let replay = Replay::from_file("session.log").unwrap();
+//! let mut client = EppClient::new("registry_name".to_string(), replay).await.unwrap();
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Sender};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tracing::warn;
+
+use crate::error::Error;
+
+/// The elements whose content is replaced by [`redact()`]
+const SECRETS: &[&str] = &["pw", "newPW"];
+
+/// The text secrets are replaced with
+const REDACTED: &str = "REDACTED";
+
+/// The direction a frame travelled in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    /// Sent by the client to the registry
+    Sent,
+    /// Received by the client from the registry
+    Received,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Sent => "sent",
+            Direction::Received => "received",
+        }
+    }
+}
+
+/// A single EPP frame in a recorded session
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// The direction the frame travelled in
+    pub direction: Direction,
+    /// The time the frame was sent or received
+    pub timestamp: DateTime<Utc>,
+    /// The name of the registry the session was held with
+    pub registry: String,
+    /// The frame's XML, with secrets redacted
+    pub xml: String,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t",
+            self.direction.as_str(),
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            escape(&self.registry)
+        )?;
+        f.write_str(&escape(&self.xml))
+    }
+}
+
+impl FromStr for Frame {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Other(format!("invalid session record: {}", line).into());
+
+        let mut fields = line.splitn(4, '\t');
+        let direction = match fields.next() {
+            Some("sent") => Direction::Sent,
+            Some("received") => Direction::Received,
+            _ => return Err(invalid()),
+        };
+
+        let timestamp = fields.next().ok_or_else(invalid)?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).map_err(|_| invalid())?;
+        let registry = fields.next().ok_or_else(invalid)?;
+        let xml = fields.next().ok_or_else(invalid)?;
+
+        Ok(Self {
+            direction,
+            timestamp: timestamp.with_timezone(&Utc),
+            registry: unescape(registry),
+            xml: unescape(xml),
+        })
+    }
+}
+
+/// Reads all frames of a recorded session
+#[allow(clippy::result_large_err)]
+pub fn read_session(reader: impl BufRead) -> Result<Vec<Frame>, Error> {
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.is_empty()))
+        .map(|line| line?.parse())
+        .collect()
+}
+
+/// Replaces the content of password elements in `xml`
+pub fn redact(xml: &str) -> String {
+    let mut redacted = String::with_capacity(xml.len());
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end + 1,
+            None => break,
+        };
+
+        let tag = &rest[start..end];
+        redacted.push_str(&rest[..end]);
+        rest = &rest[end..];
+
+        let secret = !tag.starts_with("</") && !tag.ends_with("/>") && {
+            let name = tag[1..tag.len() - 1].split_whitespace().next();
+            let local = name.and_then(|name| name.rsplit(':').next());
+            local.is_some_and(|local| SECRETS.contains(&local))
+        };
+
+        if let Some(close) = rest.find("</").filter(|_| secret) {
+            redacted.push_str(REDACTED);
+            rest = &rest[close..];
+        }
+    }
+
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Wraps a stream and records every frame read from or written to it
+pub struct SessionRecorder<IO> {
+    inner: IO,
+    registry: String,
+    writer: Writer,
+    sent: FrameBuffer,
+    received: FrameBuffer,
+}
+
+impl<IO> SessionRecorder<IO> {
+    /// Records the session held with `registry` over `stream` to `sink`
+    pub fn new(stream: IO, registry: &str, sink: impl Write + Send + 'static) -> Self {
+        Self {
+            inner: stream,
+            registry: registry.to_owned(),
+            writer: Writer::spawn(registry, sink),
+            sent: FrameBuffer::default(),
+            received: FrameBuffer::default(),
+        }
+    }
+
+    /// Records the session held with `registry` over `stream` to the file at `path`
+    ///
+    /// The file is created if it doesn't exist and appended to otherwise.
+    #[allow(clippy::result_large_err)]
+    pub fn to_file(stream: IO, registry: &str, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(Self::new(stream, registry, BufWriter::new(file)))
+    }
+
+    /// Returns the wrapped stream
+    ///
+    /// Waits for the frames recorded so far to be written to the sink.
+    pub fn into_inner(self) -> IO {
+        self.inner
+    }
+
+    /// Passes the complete frames buffered for `direction` on to the writer thread
+    fn record(&mut self, direction: Direction) {
+        let buffer = match direction {
+            Direction::Sent => &mut self.sent,
+            Direction::Received => &mut self.received,
+        };
+
+        while let Some(xml) = buffer.next() {
+            self.writer.send(Frame {
+                direction,
+                timestamp: Utc::now(),
+                registry: self.registry.clone(),
+                xml: redact(&xml),
+            });
+        }
+    }
+}
+
+impl<IO> fmt::Debug for SessionRecorder<IO> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionRecorder")
+            .field("registry", &self.registry)
+            .finish()
+    }
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for SessionRecorder<IO> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+
+        if let Poll::Ready(Ok(())) = result {
+            this.received.extend(&buf.filled()[before..]);
+            this.record(Direction::Received);
+        }
+        result
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for SessionRecorder<IO> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+
+        if let Poll::Ready(Ok(written)) = result {
+            this.sent.extend(&buf[..written]);
+            this.record(Direction::Sent);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// The thread writing recorded frames to the sink
+///
+/// Dropping the writer waits for the thread to write the frames still queued for it.
+struct Writer {
+    frames: Option<Sender<Frame>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Writer {
+    /// Starts a thread writing frames to `sink`
+    ///
+    /// Failing to record doesn't interrupt the session: if the thread can't be started, frames
+    /// are discarded.
+    fn spawn(registry: &str, mut sink: impl Write + Send + 'static) -> Self {
+        let (frames, queue) = mpsc::channel::<Frame>();
+        let thread = thread::Builder::new()
+            .name(format!("epp-recorder-{}", registry))
+            .spawn(move || {
+                for frame in queue {
+                    if let Err(e) = writeln!(sink, "{}", frame).and_then(|_| sink.flush()) {
+                        warn!("{}: Failed to record frame: {}", frame.registry, e);
+                    }
+                }
+            });
+
+        match thread {
+            Ok(thread) => Self {
+                frames: Some(frames),
+                thread: Some(thread),
+            },
+            Err(e) => {
+                warn!("{}: Failed to start session recorder: {}", registry, e);
+                Self {
+                    frames: None,
+                    thread: None,
+                }
+            }
+        }
+    }
+
+    fn send(&self, frame: Frame) {
+        if let Some(frames) = &self.frames {
+            // The thread only stops once `frames` is dropped
+            let _ = frames.send(frame);
+        }
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread once it has written the queued frames
+        self.frames.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A stream replaying the registry's side of a recorded session
+///
+/// Reads return the recorded responses, starting with the greeting. Each response is only
+/// released once the client has written the request preceding it in the recording, and the
+/// stream reaches EOF at the end of the recording. Writing a request the recording doesn't
+/// expect fails with an `InvalidData` error.
+#[derive(Debug)]
+pub struct Replay {
+    frames: VecDeque<Frame>,
+    strict: bool,
+    /// Bytes of the current response not read yet
+    pending: VecDeque<u8>,
+    requests: FrameBuffer,
+    waker: Option<Waker>,
+}
+
+impl Replay {
+    /// Replays `frames`, typically from [`read_session()`]
+    pub fn new(frames: Vec<Frame>) -> Self {
+        Self {
+            frames: frames.into(),
+            strict: false,
+            pending: VecDeque::new(),
+            requests: FrameBuffer::default(),
+            waker: None,
+        }
+    }
+
+    /// Replays the session recorded in the file at `path`
+    #[allow(clippy::result_large_err)]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        Ok(Self::new(read_session(BufReader::new(file))?))
+    }
+
+    /// Requires every request to match the recorded one after redaction
+    ///
+    /// Requests then have to be sent with the client transaction IDs used in the recording.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Returns `true` once all recorded frames have been replayed
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty() && self.pending.is_empty()
+    }
+
+    /// Moves the next recorded response to `pending` if it is due
+    fn advance(&mut self) {
+        if !self.pending.is_empty() {
+            return;
+        }
+
+        if let Some(Direction::Received) = self.frames.front().map(|frame| frame.direction) {
+            let xml = self.frames.pop_front().unwrap().xml;
+            let len = (xml.len() + 4) as u32;
+            self.pending.extend(len.to_be_bytes());
+            self.pending.extend(xml.as_bytes());
+        }
+    }
+
+    /// Matches a complete request written by the client against the recording
+    fn request(&mut self, xml: &str) -> io::Result<()> {
+        let expected = match self.frames.front() {
+            Some(frame) if frame.direction == Direction::Sent => frame,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected request: {}", xml),
+                ))
+            }
+        };
+
+        if self.strict && redact(xml) != expected.xml {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "request does not match recording\nexpected: {}\nreceived: {}",
+                    expected.xml, xml
+                ),
+            ));
+        }
+
+        self.frames.pop_front();
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+impl AsyncRead for Replay {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.advance();
+
+        if this.pending.is_empty() {
+            if this.frames.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+
+            this.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let len = this.pending.len().min(buf.remaining());
+        let bytes: Vec<u8> = this.pending.drain(..len).collect();
+        buf.put_slice(&bytes);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for Replay {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.requests.extend(buf);
+        while let Some(xml) = this.requests.next() {
+            this.request(&xml)?;
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Splits a byte stream into length-prefixed EPP frames
+#[derive(Debug, Default)]
+struct FrameBuffer {
+    buf: Vec<u8>,
+}
+
+impl FrameBuffer {
+    fn extend(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Removes and returns the next complete frame, if any
+    fn next(&mut self) -> Option<String> {
+        let len: [u8; 4] = self.buf.get(..4)?.try_into().unwrap();
+        let len = (u32::from_be_bytes(len) as usize).max(4);
+        if self.buf.len() < len {
+            return None;
+        }
+
+        let frame = String::from_utf8_lossy(&self.buf[4..len]).into_owned();
+        self.buf.drain(..len);
+        Some(frame)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::{mpsc, Arc, Mutex};
+
+    use chrono::{TimeZone, Utc};
+    use tokio::io::AsyncWriteExt;
+
+    use super::{read_session, redact, Direction, Frame, FrameBuffer, SessionRecorder};
+    use crate::tests::get_xml;
+
+    #[test]
+    fn redaction() {
+        let xml = get_xml("request/login.xml").unwrap();
+        let redacted = redact(&xml);
+        assert!(redacted.contains("<pw>REDACTED</pw>"));
+        assert!(redacted.contains("<clID>username</clID>"));
+
+        let xml = get_xml("response/domain/info.xml").unwrap();
+        assert!(redact(&xml).contains("<domain:pw>REDACTED</domain:pw>"));
+
+        let xml = "<login><pw/><newPW>secret</newPW><pwd>kept</pwd></login>";
+        assert_eq!(
+            redact(xml),
+            "<login><pw/><newPW>REDACTED</newPW><pwd>kept</pwd></login>"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let frame = Frame {
+            direction: Direction::Received,
+            timestamp: Utc.with_ymd_and_hms(2021, 7, 25, 14, 51, 17).unwrap(),
+            registry: "registry".into(),
+            xml: "<?xml version=\"1.0\"?>\r\n<epp>\t\\</epp>".into(),
+        };
+
+        let line = frame.to_string();
+        assert_eq!(
+            line,
+            "received\t2021-07-25T14:51:17.000Z\tregistry\t<?xml version=\"1.0\"?>\\r\\n<epp>\\t\\\\</epp>"
+        );
+        assert_eq!(line.parse::<Frame>().unwrap(), frame);
+
+        let session = format!("{}\n\n{}\n", line, line);
+        assert_eq!(read_session(session.as_bytes()).unwrap().len(), 2);
+        assert!(read_session("sent\tyesterday\tregistry\t<epp/>".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn frames() {
+        let mut buffer = FrameBuffer::default();
+        buffer.extend(&[0, 0, 0, 9, b'<', b'a']);
+        assert_eq!(buffer.next(), None);
+        buffer.extend(&[b'/', b'>', b'!', 0, 0]);
+        assert_eq!(buffer.next().as_deref(), Some("<a/>!"));
+        assert_eq!(buffer.next(), None);
+        buffer.extend(&[0, 4]);
+        assert_eq!(buffer.next().as_deref(), Some(""));
+    }
+
+    /// A sink that blocks every write until it is released
+    struct Gate {
+        release: mpsc::Receiver<()>,
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Write for Gate {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let _ = self.release.recv();
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_sink() {
+        let (release, gate) = mpsc::channel();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink = Gate {
+            release: gate,
+            written: written.clone(),
+        };
+
+        // Writing to the stream completes while the sink is still blocked
+        let mut recorder = SessionRecorder::new(tokio::io::sink(), "registry", sink);
+        recorder
+            .write_all(&[0, 0, 0, 10, b'<', b'e', b'p', b'p', b'/', b'>'])
+            .await
+            .unwrap();
+        assert!(written.lock().unwrap().is_empty());
+
+        // Dropping the released recorder waits for the frame to be written
+        drop(release);
+        drop(recorder);
+        let session = written.lock().unwrap().clone();
+        let frames = read_session(session.as_slice()).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].direction, Direction::Sent);
+        assert_eq!(frames[0].xml, "<epp/>");
+    }
+}
@@ -15,11 +15,13 @@ pub const EPP_LANG: &str = "en";
 
 /// Trait to set correct value for xml tags when tags are being generated from generic types
 pub trait Transaction<Ext: Extension>: Command + Sized {
+    #[allow(clippy::result_large_err)]
     fn serialize_request(
         &self,
         extension: Option<&Ext>,
         client_tr_id: &str,
     ) -> Result<String, Error> {
+        self.validate()?;
         <CommandDocument<Self, Ext> as EppXml>::serialize(&CommandDocument::new(CommandWrapper {
             command: Self::COMMAND,
             data: self,
@@ -28,6 +30,7 @@ pub trait Transaction<Ext: Extension>: Command + Sized {
         }))
     }
 
+    #[allow(clippy::result_large_err)]
     fn deserialize_response(
         epp_xml: &str,
     ) -> Result<Response<Self::Response, Ext::Response>, Error> {
@@ -46,6 +49,15 @@ pub trait Transaction<Ext: Extension>: Command + Sized {
 pub trait Command: Serialize + Debug {
     type Response: DeserializeOwned + Debug;
     const COMMAND: &'static str;
+    /// The type of object the command operates on (`domain`, `contact` or `host`), if any
+    const OBJECT: Option<&'static str> = None;
+
+    /// Checks constraints of the EPP RFCs that the command's types can't express, before it is
+    /// serialized
+    #[allow(clippy::result_large_err)]
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 pub trait Extension: Serialize + Debug {
@@ -1,17 +1,18 @@
 //! Types for EPP responses
 
-use serde::{de::DeserializeOwned, Deserialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-use crate::common::StringValue;
+use crate::common::{StringValue, EPP_XMLNS};
 use crate::xml::EppXml;
 
 /// Type corresponding to the <undef> tag an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Undef;
 
 /// Type corresponding to the <value> tag under <extValue> in an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct ResultValue {
     /// The XML namespace for the <value> tag
     #[serde(rename = "xmlns:epp")]
@@ -21,7 +22,7 @@ pub struct ResultValue {
 }
 
 /// Type corresponding to the <extValue> tag in an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct ExtValue {
     /// Data under the <value> tag
     pub value: ResultValue,
@@ -30,7 +31,7 @@ pub struct ExtValue {
 }
 
 /// Type corresponding to the <result> tag in an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct EppResult {
     /// The result code
     pub code: u16,
@@ -43,7 +44,7 @@ pub struct EppResult {
 }
 
 /// Type corresponding to the <trID> tag in an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct ResponseTRID {
     /// The client TRID
     #[serde(rename = "clTRID")]
@@ -54,21 +55,21 @@ pub struct ResponseTRID {
 }
 
 /// Type corresponding to the <msgQ> tag in an EPP response XML
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct MessageQueue {
     /// The message count
     pub count: u32,
     /// The message ID
     pub id: String,
     /// The message date
-    #[serde(rename = "qDate")]
-    pub date: Option<StringValue<'static>>,
+    #[serde(rename = "qDate", default, with = "crate::common::datetime::option")]
+    pub date: Option<DateTime<Utc>>,
     /// The message text
     #[serde(rename = "msg")]
     pub message: Option<StringValue<'static>>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// Type corresponding to the &lt;response&gt; tag in an EPP response XML
 /// containing an &lt;extension&gt; tag
 pub struct Response<D, E> {
@@ -87,14 +88,29 @@ pub struct Response<D, E> {
     pub tr_ids: ResponseTRID,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename = "epp")]
 pub struct ResponseDocument<D, E> {
+    #[serde(skip_deserializing, default = "epp_xmlns")]
+    xmlns: &'static str,
     #[serde(rename = "response")]
     pub data: Response<D, E>,
 }
 
-impl<D: DeserializeOwned, E: DeserializeOwned> EppXml for ResponseDocument<D, E> {}
+impl<D, E> ResponseDocument<D, E> {
+    pub fn new(data: Response<D, E>) -> Self {
+        Self {
+            xmlns: EPP_XMLNS,
+            data,
+        }
+    }
+}
+
+impl<D, E> EppXml for ResponseDocument<D, E> {}
+
+fn epp_xmlns() -> &'static str {
+    EPP_XMLNS
+}
 
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename = "epp")]
@@ -105,7 +121,7 @@ pub struct ResultDocument {
 
 impl EppXml for ResultDocument {}
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// Type corresponding to the &lt;response&gt; tag in an EPP response XML
 /// without <msgQ> or &lt;resData&gt; sections. Generally used for error handling
 pub struct ResponseStatus {
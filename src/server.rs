@@ -0,0 +1,707 @@
+//! Building blocks for EPP servers
+//!
+//! The command and response types used by [`EppClient`](crate::EppClient) work in the other
+//! direction too: commands can be deserialized from the EPP XML sent by a client, and responses
+//! serialized to be sent back. A [`Server`] sends a [`Greeting`] to every client that connects,
+//! answers hellos, and passes every other command to a [`Handler`] as a [`Request`], writing back
+//! the EPP XML it returns. Sessions are closed once a logout has been answered.
+//!
+//! [`Server::run()`] accepts plain TCP connections; to serve EPP over TLS, accept connections
+//! yourself and pass the TLS streams to [`Server::serve()`].
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use epp_client::common::NoExtension;
+//! use epp_client::domain::check::{
+//!     DomainAvailable, DomainCheck, DomainCheckResponse, DomainCheckResponseData,
+//!     DomainCheckResponseDataItem,
+//! };
+//! use epp_client::hello::Greeting;
+//! use epp_client::middleware::BoxFuture;
+//! use epp_client::response::ResponseDocument;
+//! use epp_client::server::{Handler, Request, Server};
+//! use epp_client::xml::EppXml;
+//! use epp_client::{domain, Error};
+//! use tokio::net::TcpListener;
+//!
+//! /// Reports every domain as available
+//! struct Registry;
+//!
+//! impl Handler for Registry {
+//!     type Session = ();
+//!
+//!     fn handle<'a>(&'a self, _: &'a mut (), request: &'a Request) -> BoxFuture<'a, Result<String, Error>> {
+//!         Box::pin(async move {
+//!             match (request.command.as_str(), request.object.as_deref()) {
+//!                 ("check", Some("domain")) => {
+//!                     let check = request.data::<DomainCheck>()?;
+//!                     let mut response = request.response::<_, NoExtension>(1000);
+//!                     response.res_data = Some(DomainCheckResponse {
+//!                         check_data: DomainCheckResponseData {
+//!                             xmlns: domain::XMLNS.into(),
+//!                             domain_list: check
+//!                                 .list
+//!                                 .domains
+//!                                 .iter()
+//!                                 .map(|name| DomainCheckResponseDataItem {
+//!                                     domain: DomainAvailable {
+//!                                         name: name.to_string().into(),
+//!                                         available: true,
+//!                                     },
+//!                                     reason: None,
+//!                                 })
+//!                                 .collect(),
+//!                         },
+//!                     });
+//!                     ResponseDocument::new(response).serialize()
+//!                 }
+//!                 ("login", _) | ("logout", _) => {
+//!                     let code = if request.command == "login" { 1000 } else { 1500 };
+//!                     ResponseDocument::new(request.response::<(), NoExtension>(code)).serialize()
+//!                 }
+//!                 _ => ResponseDocument::new(request.response::<(), NoExtension>(2101)).serialize(),
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let listener = TcpListener::bind("127.0.0.1:700").await.unwrap();
+//!     let server = Server::new(Registry, Greeting::builder("Example EPP server"));
+//!     server.run(listener).await.unwrap();
+//! }
+//! ```
+
+use std::convert::TryInto;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::Utc;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+use crate::common::NoExtension;
+use crate::error::Error;
+use crate::hello::{GreetingBuilder, GreetingDocument};
+use crate::middleware::BoxFuture;
+use crate::response::{EppResult, Response, ResponseDocument, ResponseTRID};
+use crate::xml::EppXml;
+
+/// Answers the commands received by a [`Server`]
+pub trait Handler: Send + Sync + 'static {
+    /// State kept for each connected client, e.g. the client ID it logged in with
+    type Session: Default + Send;
+
+    /// Returns the EPP XML response to `request`
+    ///
+    /// Errors are answered with a 2001 result if the request couldn't be deserialized, and a
+    /// 2400 result otherwise.
+    fn handle<'a>(
+        &'a self,
+        session: &'a mut Self::Session,
+        request: &'a Request,
+    ) -> BoxFuture<'a, Result<String, Error>>;
+}
+
+/// A command received by a [`Server`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    /// The command name, e.g. `check` or `login`, or `hello` for a hello
+    pub command: String,
+    /// The object type the command operates on, e.g. `domain`, if any
+    ///
+    /// The type is taken from the namespace URI of the object element, see [`object_type()`],
+    /// so it's `None` for commands on objects in namespaces this crate doesn't know.
+    pub object: Option<String>,
+    /// The client transaction ID, if any
+    pub client_tr_id: Option<String>,
+    /// The server transaction ID assigned to the command
+    pub server_tr_id: String,
+    /// The raw EPP XML of the request
+    pub xml: String,
+}
+
+impl Request {
+    /// Extracts the command, object type and client transaction ID from a raw EPP XML request
+    #[allow(clippy::result_large_err)]
+    pub fn parse(xml: &str) -> Result<Self, Error> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut ns_buf = Vec::new();
+        let mut path: Vec<String> = Vec::new();
+        let mut request = Self {
+            command: String::new(),
+            object: None,
+            client_tr_id: None,
+            server_tr_id: String::new(),
+            xml: xml.to_owned(),
+        };
+
+        loop {
+            let (namespace, event) = reader
+                .read_namespaced_event(&mut buf, &mut ns_buf)
+                .map_err(|e| Error::Xml(e.into()))?;
+
+            match event {
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let name = String::from_utf8_lossy(e.local_name()).into_owned();
+                    let namespace = namespace.map(String::from_utf8_lossy);
+                    request.visit(&path, &name, namespace.as_deref());
+                    if let Event::Start(_) = event {
+                        path.push(name);
+                    }
+                }
+                Event::Text(ref e) if path.len() == 3 && path[2] == "clTRID" => {
+                    let text = e
+                        .unescape_and_decode(&reader)
+                        .map_err(|e| Error::Xml(e.into()))?;
+                    request.client_tr_id = Some(text);
+                }
+                Event::End(_) => {
+                    path.pop();
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if request.command.is_empty() {
+            Err(Error::Other("request contains no command".into()))
+        } else {
+            Ok(request)
+        }
+    }
+
+    /// Records the command and object type from an element with the local `name` in
+    /// `namespace`, opened under `path`
+    fn visit(&mut self, path: &[String], name: &str, namespace: Option<&str>) {
+        match path.len() {
+            1 if name == "hello" => self.command = name.to_owned(),
+            2 if path[1] == "command" && name != "extension" && name != "clTRID" => {
+                self.command = name.to_owned();
+            }
+            3 if path[1] == "command" && path[2] == self.command && self.object.is_none() => {
+                self.object = namespace.and_then(object_type).map(str::to_owned);
+            }
+            _ => {}
+        }
+    }
+
+    /// Deserializes the command, e.g. `request.data::<DomainCreate>()`
+    ///
+    /// Elements are matched by their local names, so check `object` to pick the type to
+    /// deserialize rather than relying on this to fail for commands on other object types.
+    #[allow(clippy::result_large_err)]
+    pub fn data<C: DeserializeOwned>(&self) -> Result<C, Error> {
+        Ok(
+            <RequestDocument<C, IgnoredAny> as EppXml>::deserialize(&self.xml)?
+                .command
+                .data,
+        )
+    }
+
+    /// Deserializes the extension sent with the command, if any
+    #[allow(clippy::result_large_err)]
+    pub fn extension<E: DeserializeOwned>(&self) -> Result<Option<E>, Error> {
+        Ok(
+            <RequestDocument<IgnoredAny, E> as EppXml>::deserialize(&self.xml)?
+                .command
+                .extension,
+        )
+    }
+
+    /// Returns a response to the request with the standard message for `code`
+    ///
+    /// The response carries the transaction IDs of the request; set its `res_data`, `extension`
+    /// or `message_queue` before serializing it through a [`ResponseDocument`].
+    pub fn response<D, E>(&self, code: u16) -> Response<D, E> {
+        Response {
+            result: EppResult {
+                code,
+                message: result_message(code).into(),
+                ext_value: None,
+            },
+            message_queue: None,
+            res_data: None,
+            extension: None,
+            tr_ids: ResponseTRID {
+                client_tr_id: self.client_tr_id.clone().map(Into::into),
+                server_tr_id: self.server_tr_id.clone().into(),
+            },
+        }
+    }
+}
+
+/// The object type of commands on elements in the `namespace` XML namespace, e.g. `domain` for
+/// `urn:ietf:params:xml:ns:domain-1.0`
+///
+/// Object types are resolved from namespace URIs rather than element prefixes, which the client
+/// is free to choose.
+pub fn object_type(namespace: &str) -> Option<&'static str> {
+    match namespace {
+        crate::domain::XMLNS => Some("domain"),
+        crate::contact::XMLNS => Some("contact"),
+        crate::host::XMLNS => Some("host"),
+        _ => None,
+    }
+}
+
+/// Type corresponding to the &lt;epp&gt; tag of a command received by a server
+#[derive(Deserialize)]
+#[serde(rename = "epp")]
+struct RequestDocument<D, E> {
+    command: RequestCommand<D, E>,
+}
+
+impl<D: DeserializeOwned, E: DeserializeOwned> EppXml for RequestDocument<D, E> {}
+
+/// Type corresponding to the &lt;command&gt; tag of a command received by a server
+#[derive(Deserialize)]
+struct RequestCommand<D, E> {
+    #[serde(
+        rename = "check",
+        alias = "create",
+        alias = "delete",
+        alias = "info",
+        alias = "login",
+        alias = "logout",
+        alias = "poll",
+        alias = "renew",
+        alias = "transfer",
+        alias = "update"
+    )]
+    data: D,
+    extension: Option<E>,
+}
+
+/// Returns the message RFC 5730 defines for a result code
+pub fn result_message(code: u16) -> &'static str {
+    match code {
+        1000 => "Command completed successfully",
+        1001 => "Command completed successfully; action pending",
+        1300 => "Command completed successfully; no messages",
+        1301 => "Command completed successfully; ack to dequeue",
+        1500 => "Command completed successfully; ending session",
+        2000 => "Unknown command",
+        2001 => "Command syntax error",
+        2002 => "Command use error",
+        2003 => "Required parameter missing",
+        2004 => "Parameter value range error",
+        2005 => "Parameter value syntax error",
+        2100 => "Unimplemented protocol version",
+        2101 => "Unimplemented command",
+        2102 => "Unimplemented option",
+        2103 => "Unimplemented extension",
+        2104 => "Billing failure",
+        2105 => "Object is not eligible for renewal",
+        2106 => "Object is not eligible for transfer",
+        2200 => "Authentication error",
+        2201 => "Authorization error",
+        2202 => "Invalid authorization information",
+        2300 => "Object pending transfer",
+        2301 => "Object not pending transfer",
+        2302 => "Object exists",
+        2303 => "Object does not exist",
+        2304 => "Object status prohibits operation",
+        2305 => "Object association prohibits operation",
+        2306 => "Parameter value policy error",
+        2307 => "Unimplemented object service",
+        2308 => "Data management policy violation",
+        2500 => "Command failed; server closing connection",
+        2501 => "Authentication error; server closing connection",
+        2502 => "Session limit exceeded; server closing connection",
+        _ => "Command failed",
+    }
+}
+
+/// An EPP server passing the commands it receives to a [`Handler`]
+#[derive(Debug)]
+pub struct Server<H> {
+    handler: H,
+    greeting: GreetingBuilder,
+    prefix: String,
+    server_tr_id: AtomicU64,
+}
+
+impl<H: Handler> Server<H> {
+    /// Creates a server greeting its clients with the greeting built by `greeting`
+    pub fn new(handler: H, greeting: GreetingBuilder) -> Self {
+        Self {
+            handler,
+            greeting,
+            prefix: Utc::now().timestamp().to_string(),
+            server_tr_id: AtomicU64::new(0),
+        }
+    }
+
+    /// The handler answering commands
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Accepts connections on `listener`, serving each of them on its own task
+    pub async fn run(self, listener: TcpListener) -> Result<(), Error> {
+        info!("EPP server listening on {}", listener.local_addr()?);
+        let server = Arc::new(self);
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.serve(stream).await {
+                    warn!("EPP connection from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    /// Serves a single client connected through `stream` until it logs out or disconnects
+    pub async fn serve<IO: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        mut stream: IO,
+    ) -> Result<(), Error> {
+        write_frame(&mut stream, &self.greeting()?).await?;
+        let mut session = H::Session::default();
+
+        loop {
+            let xml = match read_frame(&mut stream).await {
+                Ok(xml) => xml,
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let sv_tr_id = self.server_tr_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let server_tr_id = format!("{}-{}", self.prefix, sv_tr_id);
+            let request = match Request::parse(&xml) {
+                Ok(request) => Request {
+                    server_tr_id,
+                    ..request
+                },
+                Err(e) => {
+                    debug!("Failed to parse request: {}", e);
+                    let request = Request {
+                        command: String::new(),
+                        object: None,
+                        client_tr_id: None,
+                        server_tr_id,
+                        xml,
+                    };
+                    write_frame(&mut stream, &failure(&request, 2001)?).await?;
+                    continue;
+                }
+            };
+
+            let response = match request.command.as_str() {
+                "hello" => self.greeting()?,
+                _ => match self.handler.handle(&mut session, &request).await {
+                    Ok(response) => response,
+                    Err(Error::Xml(e)) => {
+                        debug!("Failed to deserialize {}: {}", request.command, e);
+                        failure(&request, 2001)?
+                    }
+                    Err(e) => {
+                        warn!("Failed to handle {}: {}", request.command, e);
+                        failure(&request, 2400)?
+                    }
+                },
+            };
+
+            write_frame(&mut stream, &response).await?;
+            if request.command == "logout" {
+                return Ok(stream.shutdown().await?);
+            }
+        }
+    }
+
+    /// Serializes the greeting, dated now
+    #[allow(clippy::result_large_err)]
+    fn greeting(&self) -> Result<String, Error> {
+        GreetingDocument::new(self.greeting.clone().build()).serialize()
+    }
+}
+
+/// Serializes a response to `request` consisting of a result code only
+#[allow(clippy::result_large_err)]
+fn failure(request: &Request, code: u16) -> Result<String, Error> {
+    ResponseDocument::new(request.response::<(), NoExtension>(code)).serialize()
+}
+
+/// The largest frame a [`Server`] accepts, including its 4-byte length header
+///
+/// EPP commands are small; the limit keeps a peer from making the server allocate up to 4 GiB
+/// with a single length header.
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+/// Reads a single length-prefixed EPP frame of at most [`MAX_FRAME_LEN`] bytes
+pub(crate) async fn read_frame<IO: AsyncRead + Unpin>(stream: &mut IO) -> Result<String, Error> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).await?;
+
+    let len: usize = u32::from_be_bytes(len).try_into()?;
+    if !(4..=MAX_FRAME_LEN).contains(&len) {
+        return Err(Error::Other(format!("invalid frame length {}", len).into()));
+    }
+
+    let mut buf = vec![0; len - 4];
+    stream.read_exact(&mut buf).await?;
+
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Writes `xml` as a single length-prefixed EPP frame
+pub(crate) async fn write_frame<IO: AsyncWrite + Unpin>(
+    stream: &mut IO,
+    xml: &str,
+) -> Result<(), Error> {
+    let len: u32 = (xml.len() + 4).try_into()?;
+    stream.write_all(&len.to_be_bytes()).await?;
+    stream.write_all(xml.as_bytes()).await?;
+    Ok(stream.flush().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+
+    use super::{read_frame, result_message, write_frame, Request, MAX_FRAME_LEN};
+    use crate::common::{NoExtension, TransferStatus};
+    use crate::contact::{
+        ContactCheck, ContactCreate, ContactDelete, ContactInfo, ContactTransfer, ContactUpdate,
+    };
+    use crate::domain::{
+        DomainCheck, DomainCreate, DomainDelete, DomainInfo, DomainRenew, DomainTransfer,
+        DomainUpdate,
+    };
+    use crate::error::Error;
+    use crate::extensions::rgp::report::RgpRestoreReport;
+    use crate::extensions::rgp::request::RgpRestoreRequest;
+    use crate::extensions::rgp::Update;
+    use crate::hello::{Greeting, GreetingDocument};
+    use crate::host::{HostCheck, HostCreate, HostDelete, HostInfo, HostUpdate};
+    use crate::login::Login;
+    use crate::logout::Logout;
+    use crate::message::{MessageAck, MessagePoll};
+    use crate::request::{Extension, Transaction};
+    use crate::response::ResponseDocument;
+    use crate::tests::{get_xml, CLTRID};
+    use crate::xml::EppXml;
+
+    /// Deserializes the request in `path` and checks it serializes back to the same XML
+    fn round_trip<C, E>(path: &str)
+    where
+        C: Transaction<E> + DeserializeOwned,
+        E: Extension + DeserializeOwned,
+    {
+        round_trip_with::<C, E>(path, |_| {})
+    }
+
+    /// Like `round_trip()`, but lets `prepare` fill in client-side settings that aren't part of
+    /// the XML before the command is serialized again
+    fn round_trip_with<C, E>(path: &str, prepare: impl FnOnce(&mut C))
+    where
+        C: Transaction<E> + DeserializeOwned,
+        E: Extension + DeserializeOwned,
+    {
+        let xml = get_xml(path).unwrap();
+        let request = Request::parse(&xml).unwrap();
+        let mut command = request.data::<C>().unwrap();
+        prepare(&mut command);
+        let extension = request.extension::<E>().unwrap();
+
+        let serialized = command
+            .serialize_request(extension.as_ref(), request.client_tr_id.as_deref().unwrap())
+            .unwrap();
+        assert_eq!(xml, serialized, "{}", path);
+    }
+
+    #[test]
+    fn commands() {
+        round_trip::<Login, NoExtension>("request/login.xml");
+        round_trip::<Logout, NoExtension>("request/logout.xml");
+
+        round_trip::<ContactCheck, NoExtension>("request/contact/check.xml");
+        round_trip::<ContactCreate, NoExtension>("request/contact/create.xml");
+        round_trip::<ContactDelete, NoExtension>("request/contact/delete.xml");
+        round_trip::<ContactInfo, NoExtension>("request/contact/info.xml");
+        round_trip::<ContactInfo, NoExtension>("request/contact/info_roid.xml");
+        round_trip::<ContactUpdate, NoExtension>("request/contact/update.xml");
+        for op in &["approve", "cancel", "query", "reject", "request"] {
+            let path = format!("request/contact/transfer_{}.xml", op);
+            round_trip::<ContactTransfer, NoExtension>(&path);
+        }
+
+        round_trip::<DomainCheck, NoExtension>("request/domain/check.xml");
+        round_trip::<DomainCreate, NoExtension>("request/domain/create.xml");
+        round_trip::<DomainCreate, NoExtension>("request/domain/create_with_host_attr.xml");
+        round_trip::<DomainCreate, NoExtension>("request/domain/create_with_host_obj.xml");
+        round_trip::<DomainDelete, NoExtension>("request/domain/delete.xml");
+        round_trip::<DomainInfo, NoExtension>("request/domain/info.xml");
+        round_trip::<DomainInfo, NoExtension>("request/domain/info_hosts.xml");
+        round_trip::<DomainRenew, NoExtension>("request/domain/renew.xml");
+        round_trip::<DomainRenew, NoExtension>("request/domain/renew_months.xml");
+        round_trip::<DomainUpdate, NoExtension>("request/domain/update.xml");
+        for op in &["approve", "cancel", "query", "reject", "request"] {
+            let path = format!("request/domain/transfer_{}.xml", op);
+            round_trip::<DomainTransfer, NoExtension>(&path);
+        }
+
+        round_trip::<HostCheck, NoExtension>("request/host/check.xml");
+        round_trip_with::<HostCreate, NoExtension>("request/host/create.xml", |create| {
+            create.glue_zones = &["eppdev-1.com"]
+        });
+        round_trip::<HostDelete, NoExtension>("request/host/delete.xml");
+        round_trip::<HostInfo, NoExtension>("request/host/info.xml");
+        round_trip_with::<HostUpdate, NoExtension>("request/host/update.xml", |update| {
+            update.glue_zones(&["eppdev-1.com"])
+        });
+
+        round_trip::<MessagePoll, NoExtension>("request/message/poll.xml");
+        round_trip::<MessageAck, NoExtension>("request/message/ack.xml");
+
+        round_trip::<DomainUpdate, Update<RgpRestoreRequest>>(
+            "request/extensions/rgp_restore_request.xml",
+        );
+        round_trip::<DomainUpdate, Update<RgpRestoreReport>>(
+            "request/extensions/rgp_restore_report.xml",
+        );
+    }
+
+    #[test]
+    fn command_data() {
+        let xml = get_xml("request/domain/check.xml").unwrap();
+        let request = Request::parse(&xml).unwrap();
+        assert_eq!(request.command, "check");
+        assert_eq!(request.object.as_deref(), Some("domain"));
+
+        let check = request.data::<DomainCheck>().unwrap();
+        assert_eq!(
+            check.list.domains,
+            vec!["eppdev.com".into(), "eppdev.net".into()]
+        );
+        assert!(request.extension::<NoExtension>().unwrap().is_none());
+        assert!(request.data::<Login>().is_err());
+    }
+
+    #[tokio::test]
+    async fn frames() {
+        let mut frame = Vec::new();
+        write_frame(&mut frame, "<epp/>").await.unwrap();
+        assert_eq!(read_frame(&mut frame.as_slice()).await.unwrap(), "<epp/>");
+
+        let too_long = (MAX_FRAME_LEN as u32 + 1).to_be_bytes();
+        for header in [too_long, 3u32.to_be_bytes(), u32::MAX.to_be_bytes()].iter() {
+            let err = read_frame(&mut &header[..]).await.unwrap_err();
+            assert!(matches!(err, Error::Other(_)), "{}", err);
+        }
+    }
+
+    #[test]
+    fn object_types() {
+        let object = |xml: &str| Request::parse(xml).unwrap().object;
+
+        // The object type follows the namespace URI, whatever the prefix
+        let xml = get_xml("request/contact/check.xml").unwrap();
+        assert_eq!(object(&xml).as_deref(), Some("contact"));
+        let xml = xml
+            .replace("contact:", "domain:")
+            .replace("xmlns:contact", "xmlns:domain");
+        assert_eq!(object(&xml).as_deref(), Some("contact"));
+
+        let xml = r#"<epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><command>
+            <create xmlns="urn:ietf:params:xml:ns:contact-1.0"><id>c1</id></create>
+            </command></epp>"#;
+        let request = Request::parse(xml).unwrap();
+        assert_eq!(request.command, "create");
+        assert_eq!(request.object.as_deref(), Some("contact"));
+
+        let xml = r#"<e:epp xmlns:e="urn:ietf:params:xml:ns:epp-1.0"><e:command>
+            <e:info><h:info xmlns:h="urn:ietf:params:xml:ns:host-1.0"/></e:info>
+            <e:clTRID>c-1</e:clTRID></e:command></e:epp>"#;
+        let request = Request::parse(xml).unwrap();
+        assert_eq!(request.object.as_deref(), Some("host"));
+        assert_eq!(request.client_tr_id.as_deref(), Some("c-1"));
+
+        let xml = r#"<epp><command><info><domain:info xmlns:domain="urn:example:domain">
+            <domain:name>eppdev.com</domain:name></domain:info></info></command></epp>"#;
+        assert_eq!(object(xml), None);
+    }
+
+    #[test]
+    fn responses() {
+        let xml = get_xml("response/domain/info.xml").unwrap();
+        let response =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let serialized = ResponseDocument::new(response).serialize().unwrap();
+
+        let response =
+            <DomainInfo as Transaction<NoExtension>>::deserialize_response(&serialized).unwrap();
+        let result = response.res_data().unwrap();
+        assert_eq!(response.result.code, 1000);
+        assert_eq!(result.info_data.name, "eppdev-1.com".into());
+        assert_eq!(result.info_data.statuses.as_ref().unwrap().len(), 2);
+        assert_eq!(
+            ResponseDocument::new(response).serialize().unwrap(),
+            serialized
+        );
+
+        let xml = get_xml("response/host/info.xml").unwrap();
+        let response = <HostInfo as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let serialized = ResponseDocument::new(response).serialize().unwrap();
+        let response =
+            <HostInfo as Transaction<NoExtension>>::deserialize_response(&serialized).unwrap();
+        assert_eq!(response.res_data().unwrap().info_data.addresses.len(), 2);
+
+        let xml = get_xml("response/domain/transfer_query.xml").unwrap();
+        let response =
+            <DomainTransfer as Transaction<NoExtension>>::deserialize_response(&xml).unwrap();
+        let serialized = ResponseDocument::new(response).serialize().unwrap();
+        assert!(serialized.contains("<domain:trStatus>pending</domain:trStatus>"));
+        let response =
+            <DomainTransfer as Transaction<NoExtension>>::deserialize_response(&serialized)
+                .unwrap();
+        let transfer = response.res_data().unwrap().transfer_data.transfer();
+        assert_eq!(transfer.status, TransferStatus::Pending);
+    }
+
+    #[test]
+    fn result() {
+        let xml = get_xml("request/logout.xml").unwrap();
+        let request = Request {
+            server_tr_id: "SV-1".into(),
+            ..Request::parse(&xml).unwrap()
+        };
+
+        let response = request.response::<(), NoExtension>(1500);
+        assert_eq!(response.result.message, result_message(1500).into());
+        assert_eq!(response.tr_ids.client_tr_id.unwrap(), CLTRID.into());
+        assert_eq!(response.tr_ids.server_tr_id, "SV-1".into());
+    }
+
+    #[test]
+    fn greeting() {
+        let greeting = Greeting::builder("Test EPP Server")
+            .extension("urn:ietf:params:xml:ns:rgp-1.0")
+            .build();
+        let xml = GreetingDocument::new(greeting).serialize().unwrap();
+
+        let greeting = GreetingDocument::deserialize(&xml).unwrap().data;
+        assert_eq!(greeting.service_id, "Test EPP Server");
+        assert_eq!(greeting.svc_menu.services.obj_uris.len(), 3);
+        let ext = greeting.svc_menu.services.svc_ext.unwrap();
+        assert_eq!(
+            ext.ext_uris.unwrap()[0],
+            "urn:ietf:params:xml:ns:rgp-1.0".into()
+        );
+    }
+}
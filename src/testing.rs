@@ -0,0 +1,465 @@
+//! An in-process mock EPP server for integration tests
+//!
+//! [`MockServer`] listens on a local port, sends a greeting to every client that connects and
+//! answers each command with the response of the first [`Rule`] matching it. Rules match on the
+//! command name, the object type and the client transaction ID rather than on the exact bytes of
+//! the request, so tests don't break when serialization details change. Responses are templates
+//! in which `{clTRID}` and `{svTRID}` are replaced by the client and server transaction IDs.
+//!
+//! For flows spanning several commands, a [`Simulator`] can answer commands not matched by any
+//! rule from the state of an in-memory registry instead.
+//!
+//! Enabled by the `testing` feature.
+//!
+//! ## Example
+//!
+//! ```
+//! use epp_client::EppClient;
+//! use epp_client::domain::DomainCheck;
+//! use epp_client::testing::{MockServer, Rule};
+//! use tokio::net::TcpStream;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! let server = MockServer::builder()
+//!     .rule(
+//!         Rule::command("check").object("domain"),
+//!         r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+//!         <epp xmlns="urn:ietf:params:xml:ns:epp-1.0">
+//!           <response>
+//!             <result code="1000"><msg>Command completed successfully</msg></result>
+//!             <resData>
+//!               <domain:chkData xmlns:domain="urn:ietf:params:xml:ns:domain-1.0">
+//!                 <domain:cd><domain:name avail="1">eppdev.com</domain:name></domain:cd>
+//!               </domain:chkData>
+//!             </resData>
+//!             <trID><clTRID>{clTRID}</clTRID><svTRID>{svTRID}</svTRID></trID>
+//!           </response>
+//!         </epp>"#,
+//!     )
+//!     .start()
+//!     .await
+//!     .unwrap();
+//!
+//! let stream = TcpStream::connect(server.addr()).await.unwrap();
+//! let mut client = EppClient::new("mock".to_string(), stream).await.unwrap();
+//!
+//! let response = client.transact(&DomainCheck::new(vec!["eppdev.com"]), "check-1").await.unwrap();
+//! assert!(response.res_data.unwrap().check_data.domain_list[0].domain.available);
+//! assert_eq!(server.requests()[0].client_tr_id.as_deref(), Some("check-1"));
+//! }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+#[cfg(feature = "tokio-rustls")]
+use crate::common::{Certificate, PrivateKey};
+use crate::error::Error;
+use crate::server::{read_frame, write_frame, Request};
+use crate::xml::EPP_XML_HEADER;
+
+pub mod simulator;
+pub use simulator::Simulator;
+
+use simulator::Session;
+
+/// The greeting sent by a `MockServer` unless configured otherwise
+pub const DEFAULT_GREETING: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><greeting><svID>Mock EPP Server</svID><svDate>2021-07-25T14:51:17.0Z</svDate><svcMenu><version>1.0</version><lang>en</lang><objURI>urn:ietf:params:xml:ns:host-1.0</objURI><objURI>urn:ietf:params:xml:ns:contact-1.0</objURI><objURI>urn:ietf:params:xml:ns:domain-1.0</objURI><svcExtension><extURI>urn:ietf:params:xml:ns:rgp-1.0</extURI></svcExtension></svcMenu><dcp><access><all/></access><statement><purpose><admin/><prov/></purpose><recipient><ours/><public/></recipient><retention><stated/></retention></statement></dcp></greeting></epp>"#;
+
+/// Renders a response template containing only a result code and message
+///
+/// The returned template keeps the `{clTRID}` and `{svTRID}` placeholders.
+pub fn result_template(code: u16, message: &str) -> String {
+    format!(
+        "{}\r\n<epp xmlns=\"urn:ietf:params:xml:ns:epp-1.0\"><response><result code=\"{}\"><msg>{}</msg></result><trID><clTRID>{{clTRID}}</clTRID><svTRID>{{svTRID}}</svTRID></trID></response></epp>",
+        EPP_XML_HEADER, code, message
+    )
+}
+
+/// A command received by a `MockServer`
+pub type ReceivedRequest = Request;
+
+/// Matches commands received by a `MockServer` to a response
+#[derive(Clone, Debug)]
+pub struct Rule {
+    command: Option<String>,
+    object: Option<String>,
+    client_tr_id: Option<String>,
+    times: Option<usize>,
+}
+
+impl Rule {
+    /// Matches every command
+    pub fn any() -> Self {
+        Self {
+            command: None,
+            object: None,
+            client_tr_id: None,
+            times: None,
+        }
+    }
+
+    /// Matches commands named `command`, e.g. `check` or `poll`
+    pub fn command(command: &str) -> Self {
+        Self {
+            command: Some(command.to_owned()),
+            ..Self::any()
+        }
+    }
+
+    /// Only matches commands on objects of type `object`, e.g. `domain`
+    pub fn object(mut self, object: &str) -> Self {
+        self.object = Some(object.to_owned());
+        self
+    }
+
+    /// Only matches commands with a client transaction ID matching `pattern`
+    ///
+    /// A `*` in the pattern matches any sequence of characters.
+    pub fn client_tr_id(mut self, pattern: &str) -> Self {
+        self.client_tr_id = Some(pattern.to_owned());
+        self
+    }
+
+    /// Stops matching after the rule has been used `times` times
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    fn matches(&self, request: &ReceivedRequest) -> bool {
+        if self.times == Some(0) {
+            return false;
+        }
+
+        let command = match &self.command {
+            Some(command) => *command == request.command,
+            None => true,
+        };
+
+        let object = match &self.object {
+            Some(object) => request.object.as_ref() == Some(object),
+            None => true,
+        };
+
+        let client_tr_id = match (&self.client_tr_id, &request.client_tr_id) {
+            (Some(pattern), Some(id)) => glob(pattern, id),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        command && object && client_tr_id
+    }
+}
+
+/// Matches `value` against `pattern`, in which `*` matches any sequence of characters
+fn glob(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match value.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.is_empty()
+}
+
+/// Builds a `MockServer`
+#[derive(Debug)]
+pub struct MockServerBuilder {
+    greeting: String,
+    rules: Vec<(Rule, String)>,
+    simulator: Option<Simulator>,
+    #[cfg(feature = "tokio-rustls")]
+    tls: Option<Arc<tokio_rustls::rustls::ServerConfig>>,
+}
+
+impl MockServerBuilder {
+    /// Sets the greeting sent on connection and in reply to a hello
+    pub fn greeting(mut self, greeting: &str) -> Self {
+        self.greeting = greeting.to_owned();
+        self
+    }
+
+    /// Replies to commands matching `rule` with `response`
+    ///
+    /// Rules are tried in the order they were added. `{clTRID}` and `{svTRID}` in the response
+    /// are replaced by the client and server transaction IDs. Commands not matching any rule
+    /// are answered with a 2000 "Unknown command" result.
+    pub fn rule(mut self, rule: Rule, response: &str) -> Self {
+        self.rules.push((rule, response.to_owned()));
+        self
+    }
+
+    /// Answers commands not matching any rule from the state of `simulator`
+    ///
+    /// Rules still take precedence, which allows injecting failures into simulated flows.
+    pub fn simulator(mut self, simulator: Simulator) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    /// Accepts TLS connections using the given certificate chain and private key
+    #[cfg(feature = "tokio-rustls")]
+    #[allow(clippy::result_large_err)]
+    pub fn tls(mut self, certs: Vec<Certificate>, key: PrivateKey) -> Result<Self, Error> {
+        let certs = certs
+            .into_iter()
+            .map(|cert| tokio_rustls::rustls::Certificate(cert.0))
+            .collect();
+
+        let config = tokio_rustls::rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, tokio_rustls::rustls::PrivateKey(key.0))
+            .map_err(|e| Error::Other(e.into()))?;
+
+        self.tls = Some(Arc::new(config));
+        Ok(self)
+    }
+
+    /// Starts listening on a random port on the loopback interface
+    pub async fn start(self) -> Result<MockServer, Error> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        info!("Mock EPP server listening on {}", addr);
+
+        let state = Arc::new(State {
+            greeting: self.greeting,
+            rules: Mutex::new(self.rules),
+            requests: Mutex::new(Vec::new()),
+            simulator: self.simulator,
+            sv_tr_id: AtomicU64::new(0),
+        });
+
+        #[cfg(feature = "tokio-rustls")]
+        let tls = self.tls.map(tokio_rustls::TlsAcceptor::from);
+
+        let task = tokio::spawn({
+            let state = state.clone();
+            async move {
+                loop {
+                    let (stream, peer) = match listener.accept().await {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            debug!("Mock EPP server failed to accept: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let state = state.clone();
+                    #[cfg(feature = "tokio-rustls")]
+                    let tls = tls.clone();
+
+                    tokio::spawn(async move {
+                        #[cfg(feature = "tokio-rustls")]
+                        let result = match tls {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(stream) => state.serve(stream).await,
+                                Err(e) => Err(e.into()),
+                            },
+                            None => state.serve(stream).await,
+                        };
+                        #[cfg(not(feature = "tokio-rustls"))]
+                        let result = state.serve(stream).await;
+
+                        if let Err(e) = result {
+                            debug!("Mock EPP server connection from {} failed: {}", peer, e);
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(MockServer { addr, state, task })
+    }
+}
+
+/// A mock EPP server running on a background task
+///
+/// The server stops when it is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Returns a builder for a server sending the default greeting and without any rules
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder {
+            greeting: DEFAULT_GREETING.to_owned(),
+            rules: Vec::new(),
+            simulator: None,
+            #[cfg(feature = "tokio-rustls")]
+            tls: None,
+        }
+    }
+
+    /// The address the server is listening on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns all requests received so far, in the order they were received
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// The simulator answering commands not matching any rule, if any
+    pub fn simulator(&self) -> Option<&Simulator> {
+        self.state.simulator.as_ref()
+    }
+
+    /// Adds a rule to the running server, see [`MockServerBuilder::rule()`]
+    pub fn rule(&self, rule: Rule, response: &str) {
+        self.state
+            .rules
+            .lock()
+            .unwrap()
+            .push((rule, response.to_owned()));
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    greeting: String,
+    rules: Mutex<Vec<(Rule, String)>>,
+    requests: Mutex<Vec<ReceivedRequest>>,
+    simulator: Option<Simulator>,
+    sv_tr_id: AtomicU64,
+}
+
+impl State {
+    async fn serve<IO: AsyncRead + AsyncWrite + Unpin>(&self, mut stream: IO) -> Result<(), Error> {
+        write_frame(&mut stream, &self.greeting).await?;
+        let mut session = Session::default();
+
+        loop {
+            let xml = match read_frame(&mut stream).await {
+                Ok(xml) => xml,
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let request = ReceivedRequest::parse(&xml)?;
+            let response = self.respond(&mut session, &request);
+            let logout = request.command == "logout";
+            self.requests.lock().unwrap().push(request);
+
+            write_frame(&mut stream, &response).await?;
+            if logout {
+                return Ok(stream.shutdown().await?);
+            }
+        }
+    }
+
+    fn respond(&self, session: &mut Session, request: &ReceivedRequest) -> String {
+        if request.command == "hello" {
+            return self.greeting.clone();
+        }
+
+        let mut rules = self.rules.lock().unwrap();
+        let template = match rules.iter_mut().find(|(rule, _)| rule.matches(request)) {
+            Some((rule, response)) => {
+                if let Some(times) = &mut rule.times {
+                    *times -= 1;
+                }
+                response.clone()
+            }
+            None => match &self.simulator {
+                Some(simulator) => simulator.respond(session, &request.xml),
+                None => result_template(2000, "Unknown command"),
+            },
+        };
+
+        let sv_tr_id = self.sv_tr_id.fetch_add(1, Ordering::Relaxed) + 1;
+        template
+            .replace(
+                "{clTRID}",
+                request.client_tr_id.as_deref().unwrap_or_default(),
+            )
+            .replace("{svTRID}", &format!("MOCK-{}", sv_tr_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob, ReceivedRequest, Rule};
+    use crate::tests::{get_xml, CLTRID};
+
+    #[test]
+    fn parse() {
+        let xml = get_xml("request/domain/check.xml").unwrap();
+        let request = ReceivedRequest::parse(&xml).unwrap();
+        assert_eq!(request.command, "check");
+        assert_eq!(request.object.as_deref(), Some("domain"));
+        assert_eq!(request.client_tr_id.as_deref(), Some(CLTRID));
+
+        let xml = get_xml("request/message/poll.xml").unwrap();
+        let request = ReceivedRequest::parse(&xml).unwrap();
+        assert_eq!(request.command, "poll");
+        assert_eq!(request.object, None);
+
+        let xml = get_xml("request/extensions/rgp_restore_request.xml").unwrap();
+        let request = ReceivedRequest::parse(&xml).unwrap();
+        assert_eq!(request.command, "update");
+        assert_eq!(request.object.as_deref(), Some("domain"));
+
+        let xml = get_xml("request/hello.xml").unwrap();
+        assert_eq!(ReceivedRequest::parse(&xml).unwrap().command, "hello");
+    }
+
+    #[test]
+    fn rules() {
+        let xml = get_xml("request/domain/check.xml").unwrap();
+        let request = ReceivedRequest::parse(&xml).unwrap();
+
+        assert!(Rule::any().matches(&request));
+        assert!(Rule::command("check").object("domain").matches(&request));
+        assert!(!Rule::command("check").object("host").matches(&request));
+        assert!(Rule::any().client_tr_id("cltrid:*").matches(&request));
+        assert!(!Rule::any().client_tr_id("other-*").matches(&request));
+        assert!(!Rule::any().times(0).matches(&request));
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(glob("abc", "abc"));
+        assert!(!glob("abc", "abcd"));
+        assert!(glob("a*", "abcd"));
+        assert!(glob("*d", "abcd"));
+        assert!(glob("a*c*", "abcd"));
+        assert!(glob("*", ""));
+        assert!(!glob("a*c", "abcd"));
+        assert!(!glob("a*b*b", "ab"));
+    }
+}
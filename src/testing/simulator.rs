@@ -0,0 +1,1793 @@
+//! A stateful in-memory registry for end-to-end tests
+//!
+//! Where rules answer every command with a canned response, a [`Simulator`] keeps the objects a
+//! real registry would: domains, contacts and hosts with their statuses, sponsoring registrars,
+//! expiry dates, pending transfers and RGP (RFC 3915) grace periods, plus a poll queue per
+//! registrar. Multi-step flows such as create, renew, transfer, delete and restore behave like
+//! they would against an OT&E environment, including the errors a registry returns when a flow
+//! is used out of order.
+//!
+//! The simulator's clock starts at the current time and can be moved forward with
+//! [`Simulator::advance()`] to expire grace periods and let pending transfers be approved by the
+//! server. Time-based transitions are applied whenever a command is received.
+//!
+//! ## Example
+//!
+//! ```
+//! use epp_client::EppClient;
+//! use epp_client::domain::DomainCheck;
+//! use epp_client::login::Login;
+//! use epp_client::testing::Simulator;
+//! use tokio::net::TcpStream;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!
+//! let server = Simulator::default()
+//!     .registrar("eppdev", "password")
+//!     .start()
+//!     .await
+//!     .unwrap();
+//!
+//! let stream = TcpStream::connect(server.addr()).await.unwrap();
+//! let mut client = EppClient::new("simulator".to_string(), stream).await.unwrap();
+//! client.transact(&Login::new("eppdev", "password", None), "login-1").await.unwrap();
+//!
+//! let response = client.transact(&DomainCheck::new(vec!["eppdev.com"]), "check-1").await.unwrap();
+//! assert!(response.res_data.unwrap().check_data.domain_list[0].domain.available);
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Months, NaiveDate, SecondsFormat, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{MockServer, EPP_XML_HEADER};
+use crate::error::Error;
+use crate::server::{object_type, result_message};
+
+/// The length of the add, renew and transfer grace periods
+const GRACE_PERIOD: i64 = 5;
+/// The number of days a deleted domain can be restored
+const REDEMPTION_PERIOD: i64 = 30;
+/// The number of days a restore request waits for its report
+const PENDING_RESTORE_PERIOD: i64 = 7;
+/// The number of days between the end of the redemption period and the domain being purged
+const PENDING_DELETE_PERIOD: i64 = 5;
+/// The maximum number of months a domain can be registered for in advance
+const MAX_VALIDITY: u32 = 120;
+
+/// An in-memory registry implementing the domain, contact and host mappings and RGP
+#[derive(Debug)]
+pub struct Simulator {
+    registry: Mutex<Registry>,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self {
+            registry: Mutex::new(Registry {
+                registrars: HashMap::new(),
+                transfer_window: Duration::days(5),
+                offset: Duration::zero(),
+                roid: 0,
+                domains: BTreeMap::new(),
+                contacts: BTreeMap::new(),
+                hosts: BTreeMap::new(),
+                messages: Vec::new(),
+                message_id: 0,
+            }),
+        }
+    }
+}
+
+impl Simulator {
+    /// Accepts logins from the registrar `client_id` with `password`
+    ///
+    /// As long as no registrar has been added, any credentials are accepted.
+    pub fn registrar(mut self, client_id: &str, password: &str) -> Self {
+        let registry = self.registry.get_mut().unwrap();
+        registry
+            .registrars
+            .insert(client_id.to_owned(), password.to_owned());
+        self
+    }
+
+    /// Sets how long a transfer stays pending before the server approves it, 5 days by default
+    pub fn transfer_window(mut self, window: Duration) -> Self {
+        self.registry.get_mut().unwrap().transfer_window = window;
+        self
+    }
+
+    /// Starts a [`MockServer`] answering all commands from this simulator
+    pub async fn start(self) -> Result<MockServer, Error> {
+        MockServer::builder().simulator(self).start().await
+    }
+
+    /// The current time on the simulator's clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.registry.lock().unwrap().now()
+    }
+
+    /// Moves the simulator's clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let mut registry = self.registry.lock().unwrap();
+        registry.offset += duration;
+    }
+
+    /// Executes the command in `xml` and returns the response as a template
+    ///
+    /// The template keeps the `{clTRID}` and `{svTRID}` placeholders.
+    pub(super) fn respond(&self, session: &mut Session, xml: &str) -> String {
+        let answer = match Element::parse(xml) {
+            Ok(epp) => self.registry.lock().unwrap().execute(session, &epp),
+            Err(_) => Err(Failure(2001)),
+        };
+
+        let (code, answer) = match answer {
+            Ok(answer) => (answer.code, answer),
+            Err(Failure(code)) => (code, Answer::new(code)),
+        };
+
+        let mut xml = format!(
+            "{}\r\n<epp xmlns=\"urn:ietf:params:xml:ns:epp-1.0\"><response><result code=\"{}\"><msg>{}</msg></result>",
+            EPP_XML_HEADER,
+            code,
+            result_message(code)
+        );
+        if let Some(queue) = answer.queue {
+            xml.push_str(&queue);
+        }
+        if let Some(data) = answer.data {
+            write!(xml, "<resData>{}</resData>", data).unwrap();
+        }
+        if let Some(extension) = answer.extension {
+            write!(xml, "<extension>{}</extension>", extension).unwrap();
+        }
+        xml.push_str(
+            "<trID><clTRID>{clTRID}</clTRID><svTRID>{svTRID}</svTRID></trID></response></epp>",
+        );
+        xml
+    }
+}
+
+/// The state of a single connection to the simulator
+#[derive(Debug, Default)]
+pub(super) struct Session {
+    /// The registrar logged in on this connection
+    client_id: Option<String>,
+    /// Whether the RGP extension was negotiated at login
+    rgp: bool,
+}
+
+#[derive(Debug)]
+struct Registry {
+    registrars: HashMap<String, String>,
+    transfer_window: Duration,
+    offset: Duration,
+    roid: u64,
+    domains: BTreeMap<String, Domain>,
+    contacts: BTreeMap<String, Contact>,
+    hosts: BTreeMap<String, Host>,
+    messages: Vec<Message>,
+    message_id: u64,
+}
+
+impl Registry {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.offset
+    }
+
+    fn execute(&mut self, session: &mut Session, epp: &Element) -> Result<Answer, Failure> {
+        let command = epp.child("command").ok_or(Failure(2001))?;
+        let verb = command
+            .children
+            .iter()
+            .find(|child| child.local() != "extension" && child.local() != "clTRID")
+            .ok_or(Failure(2001))?;
+
+        let now = self.now();
+        self.tick(now);
+
+        match verb.local() {
+            "login" => return self.login(session, verb),
+            "logout" => {
+                session.client_id = None;
+                return Ok(Answer::new(1500));
+            }
+            _ => {}
+        }
+
+        let client_id = session.client_id.clone().ok_or(Failure(2002))?;
+        let cx = Context {
+            client_id: &client_id,
+            now,
+            rgp: session.rgp,
+        };
+
+        if verb.local() == "poll" {
+            return self.poll(&cx, verb);
+        }
+
+        let object = verb.children.first().ok_or(Failure(2001))?;
+        let restore = command
+            .child("extension")
+            .and_then(|extension| extension.child("update"))
+            .and_then(|update| update.child("restore"))
+            .and_then(|restore| restore.attr("op"));
+
+        match (object.object(), verb.local()) {
+            ("domain", "check") => self.domain_check(object),
+            ("domain", "info") => self.domain_info(&cx, object),
+            ("domain", "create") => self.domain_create(&cx, object),
+            ("domain", "delete") => self.domain_delete(&cx, object),
+            ("domain", "renew") => self.domain_renew(&cx, object),
+            ("domain", "transfer") => self.domain_transfer(&cx, verb.attr("op"), object),
+            ("domain", "update") => match restore {
+                Some(op) => self.domain_restore(&cx, op, object),
+                None => self.domain_update(&cx, object),
+            },
+            ("contact", "check") => self.contact_check(object),
+            ("contact", "info") => self.contact_info(&cx, object),
+            ("contact", "create") => self.contact_create(&cx, object),
+            ("contact", "delete") => self.contact_delete(&cx, object),
+            ("contact", "update") => self.contact_update(&cx, object),
+            ("host", "check") => self.host_check(object),
+            ("host", "info") => self.host_info(object),
+            ("host", "create") => self.host_create(&cx, object),
+            ("host", "delete") => self.host_delete(&cx, object),
+            ("host", "update") => self.host_update(&cx, object),
+            ("domain", _) | ("contact", _) | ("host", _) => Err(Failure(2101)),
+            _ => Err(Failure(2307)),
+        }
+    }
+
+    /// Applies the transitions that became due by `now`
+    fn tick(&mut self, now: DateTime<Utc>) {
+        let names: Vec<String> = self.domains.keys().cloned().collect();
+        for name in names {
+            let domain = &self.domains[&name];
+            if let Some(transfer) = domain.transfer.as_ref().filter(|t| t.status == "pending") {
+                if transfer.action <= now {
+                    let (requester, sponsor) =
+                        (transfer.requester.clone(), transfer.sponsor.clone());
+                    let action = transfer.action;
+                    let data = self.resolve_transfer(&name, "serverApproved", action);
+                    self.queue(&requester, action, "Transfer approved by server.", &data);
+                    self.queue(&sponsor, action, "Transfer approved by server.", &data);
+                }
+            }
+
+            while let Some(domain) = self.domains.get_mut(&name) {
+                let (rgp, end) = match domain.rgp {
+                    Some((rgp, end)) if end <= now => (rgp, end),
+                    _ => break,
+                };
+
+                domain.rgp = match rgp {
+                    Rgp::AddPeriod | Rgp::RenewPeriod | Rgp::TransferPeriod => None,
+                    // Restores that were never reported fall back to redemption
+                    Rgp::PendingRestore => Some((Rgp::RedemptionPeriod, end)),
+                    Rgp::RedemptionPeriod => Some((
+                        Rgp::PendingDelete,
+                        end + Duration::days(PENDING_DELETE_PERIOD),
+                    )),
+                    Rgp::PendingDelete => {
+                        self.domains.remove(&name);
+                        break;
+                    }
+                };
+            }
+        }
+    }
+
+    fn login(&mut self, session: &mut Session, login: &Element) -> Result<Answer, Failure> {
+        if session.client_id.is_some() {
+            return Err(Failure(2002));
+        }
+
+        let client_id = login.value("clID").ok_or(Failure(2003))?;
+        let password = login.value("pw").ok_or(Failure(2003))?;
+        if !self.registrars.is_empty()
+            && self.registrars.get(client_id).map(String::as_str) != Some(password)
+        {
+            return Err(Failure(2200));
+        }
+
+        if let (Some(new), Some(current)) =
+            (login.value("newPW"), self.registrars.get_mut(client_id))
+        {
+            *current = new.to_owned();
+        }
+
+        session.rgp = login
+            .child("svcs")
+            .and_then(|svcs| svcs.child("svcExtension"))
+            .map(|ext| {
+                ext.children("extURI")
+                    .any(|uri| uri.text() == crate::extensions::rgp::XMLNS)
+            })
+            .unwrap_or(false);
+        session.client_id = Some(client_id.to_owned());
+        Ok(Answer::new(1000))
+    }
+
+    fn poll(&mut self, cx: &Context, poll: &Element) -> Result<Answer, Failure> {
+        match poll.attr("op") {
+            Some("req") => {
+                let mut pending = self.messages.iter().filter(|m| m.client_id == cx.client_id);
+                let message = match pending.next() {
+                    Some(message) => message,
+                    None => return Ok(Answer::new(1300)),
+                };
+
+                let mut answer = Answer::new(1301).data(message.data.clone());
+                answer.queue = Some(format!(
+                    "<msgQ count=\"{}\" id=\"{}\"><qDate>{}</qDate><msg>{}</msg></msgQ>",
+                    pending.count() + 1,
+                    message.id,
+                    date(&message.date),
+                    escape(&message.text)
+                ));
+                Ok(answer)
+            }
+            Some("ack") => {
+                let id = poll.attr("msgID").ok_or(Failure(2003))?;
+                let index = self
+                    .messages
+                    .iter()
+                    .position(|m| m.client_id == cx.client_id && m.id.to_string() == id)
+                    .ok_or(Failure(2303))?;
+                self.messages.remove(index);
+
+                let mut answer = Answer::new(1000);
+                let mut pending = self.messages.iter().filter(|m| m.client_id == cx.client_id);
+                if let Some(next) = pending.next() {
+                    answer.queue = Some(format!(
+                        "<msgQ count=\"{}\" id=\"{}\"/>",
+                        pending.count() + 1,
+                        next.id
+                    ));
+                }
+                Ok(answer)
+            }
+            Some(_) => Err(Failure(2005)),
+            None => Err(Failure(2003)),
+        }
+    }
+
+    fn domain_check(&self, check: &Element) -> Result<Answer, Failure> {
+        let mut data = format!("<domain:chkData xmlns:domain=\"{}\">", crate::domain::XMLNS);
+        for name in check.children("name") {
+            let available = !self.domains.contains_key(&key(name.text()));
+            write!(
+                data,
+                "<domain:cd><domain:name avail=\"{}\">{}</domain:name></domain:cd>",
+                available as u8,
+                escape(name.text())
+            )
+            .unwrap();
+        }
+        data.push_str("</domain:chkData>");
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn domain_info(&self, cx: &Context, info: &Element) -> Result<Answer, Failure> {
+        let name = info.child("name").ok_or(Failure(2003))?;
+        let domain = self.domains.get(&key(name.text())).ok_or(Failure(2303))?;
+        let authorized = authorize(&domain.meta, &domain.auth, cx, info)?;
+        let hosts = name.attr("hosts").unwrap_or("all");
+
+        let mut data = format!(
+            "<domain:infData xmlns:domain=\"{}\"><domain:name>{}</domain:name><domain:roid>{}</domain:roid>",
+            crate::domain::XMLNS,
+            escape(&domain.name),
+            domain.meta.roid
+        );
+        write_statuses(&mut data, "domain", &domain.statuses());
+        if let Some(registrant) = &domain.registrant {
+            write!(
+                data,
+                "<domain:registrant>{}</domain:registrant>",
+                escape(registrant)
+            )
+            .unwrap();
+        }
+        for (kind, id) in &domain.contacts {
+            write!(
+                data,
+                "<domain:contact type=\"{}\">{}</domain:contact>",
+                escape(kind),
+                escape(id)
+            )
+            .unwrap();
+        }
+        if !domain.ns.is_empty() && (hosts == "all" || hosts == "del") {
+            data.push_str("<domain:ns>");
+            for host in &domain.ns {
+                write!(data, "<domain:hostObj>{}</domain:hostObj>", escape(host)).unwrap();
+            }
+            data.push_str("</domain:ns>");
+        }
+        if hosts == "all" || hosts == "sub" {
+            for host in self.subordinates(&domain.name) {
+                write!(data, "<domain:host>{}</domain:host>", escape(&host.name)).unwrap();
+            }
+        }
+        domain.meta.write_sponsor(&mut data, "domain");
+        write!(
+            data,
+            "<domain:exDate>{}</domain:exDate>",
+            date(&domain.expires)
+        )
+        .unwrap();
+        domain.meta.write_transfer(&mut data, "domain");
+        if authorized {
+            write!(
+                data,
+                "<domain:authInfo><domain:pw>{}</domain:pw></domain:authInfo>",
+                escape(&domain.auth)
+            )
+            .unwrap();
+        }
+        data.push_str("</domain:infData>");
+
+        let mut answer = Answer::new(1000).data(data);
+        if let Some((rgp, end)) = domain.rgp.filter(|_| cx.rgp) {
+            answer.extension = Some(format!(
+                "<rgp:infData xmlns:rgp=\"{}\"><rgp:rgpStatus s=\"{}\">endDate={}</rgp:rgpStatus></rgp:infData>",
+                crate::extensions::rgp::XMLNS,
+                rgp.as_str(),
+                date(&end)
+            ));
+        }
+        Ok(answer)
+    }
+
+    fn domain_create(&mut self, cx: &Context, create: &Element) -> Result<Answer, Failure> {
+        let name = key(create.value("name").ok_or(Failure(2003))?);
+        if self.domains.contains_key(&name) {
+            return Err(Failure(2302));
+        }
+
+        let months = period(create.child("period"))?;
+        let expires = add_months(cx.now, months)?;
+        let auth = auth_info(create).ok_or(Failure(2003))?;
+
+        let registrant = create.value("registrant").map(str::to_owned);
+        let contacts = domain_contacts(create)?;
+        let ns = match create.child("ns") {
+            Some(ns) => host_objects(ns)?,
+            None => Vec::new(),
+        };
+        self.check_references(
+            registrant.iter().chain(contacts.iter().map(|(_, id)| id)),
+            &ns,
+        )?;
+
+        let domain = Domain {
+            name: name.clone(),
+            meta: Meta::new(self.roid("DOMAIN"), cx),
+            registrant,
+            contacts,
+            ns,
+            expires,
+            auth: auth.to_owned(),
+            transfer: None,
+            rgp: Some((Rgp::AddPeriod, cx.now + Duration::days(GRACE_PERIOD))),
+        };
+
+        let data = format!(
+            "<domain:creData xmlns:domain=\"{}\"><domain:name>{}</domain:name><domain:crDate>{}</domain:crDate><domain:exDate>{}</domain:exDate></domain:creData>",
+            crate::domain::XMLNS,
+            escape(&name),
+            date(&cx.now),
+            date(&expires)
+        );
+        self.domains.insert(name, domain);
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn domain_delete(&mut self, cx: &Context, delete: &Element) -> Result<Answer, Failure> {
+        let name = key(delete.value("name").ok_or(Failure(2003))?);
+        let domain = self.domains.get(&name).ok_or(Failure(2303))?;
+        domain.meta.sponsored_by(cx)?;
+        domain.permits("Delete")?;
+        if self.subordinates(&name).next().is_some() {
+            return Err(Failure(2305));
+        }
+
+        let domain = self.domains.get_mut(&name).unwrap();
+        match domain.rgp {
+            Some((Rgp::AddPeriod, _)) => {
+                self.domains.remove(&name);
+                Ok(Answer::new(1000))
+            }
+            _ => {
+                domain.rgp = Some((
+                    Rgp::RedemptionPeriod,
+                    cx.now + Duration::days(REDEMPTION_PERIOD),
+                ));
+                domain.meta.touch(cx);
+                Ok(Answer::new(1001))
+            }
+        }
+    }
+
+    fn domain_renew(&mut self, cx: &Context, renew: &Element) -> Result<Answer, Failure> {
+        let name = key(renew.value("name").ok_or(Failure(2003))?);
+        let domain = self.domains.get_mut(&name).ok_or(Failure(2303))?;
+        domain.meta.sponsored_by(cx)?;
+        domain.permits("Renew")?;
+
+        let current = renew.value("curExpDate").ok_or(Failure(2003))?;
+        let current = NaiveDate::parse_from_str(current, "%Y-%m-%d").map_err(|_| Failure(2005))?;
+        if current != domain.expires.date_naive() {
+            return Err(Failure(2306));
+        }
+
+        let expires = add_months(domain.expires, period(renew.child("period"))?)?;
+        if expires > add_months(cx.now, MAX_VALIDITY)? {
+            return Err(Failure(2306));
+        }
+
+        domain.expires = expires;
+        if domain.rgp.is_none() {
+            domain.rgp = Some((Rgp::RenewPeriod, cx.now + Duration::days(GRACE_PERIOD)));
+        }
+
+        let data = format!(
+            "<domain:renData xmlns:domain=\"{}\"><domain:name>{}</domain:name><domain:exDate>{}</domain:exDate></domain:renData>",
+            crate::domain::XMLNS,
+            escape(&name),
+            date(&expires)
+        );
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn domain_transfer(
+        &mut self,
+        cx: &Context,
+        op: Option<&str>,
+        transfer: &Element,
+    ) -> Result<Answer, Failure> {
+        let op = op.ok_or(Failure(2003))?;
+        let name = key(transfer.value("name").ok_or(Failure(2003))?);
+        let domain = self.domains.get_mut(&name).ok_or(Failure(2303))?;
+        let pending = domain.transfer.as_ref().filter(|t| t.status == "pending");
+
+        match op {
+            "request" => {
+                if domain.meta.client_id == cx.client_id {
+                    return Err(Failure(2106));
+                }
+                if auth_info(transfer) != Some(domain.auth.as_str()) {
+                    return Err(Failure(2202));
+                }
+                if pending.is_some() {
+                    return Err(Failure(2300));
+                }
+                domain.permits("Transfer")?;
+
+                let months = period(transfer.child("period"))?;
+                let pending = Transfer {
+                    status: "pending",
+                    requester: cx.client_id.to_owned(),
+                    requested: cx.now,
+                    sponsor: domain.meta.client_id.clone(),
+                    action: cx.now + self.transfer_window,
+                    expires: add_months(domain.expires, months)?,
+                };
+
+                let data = pending.data(&domain.name);
+                let sponsor = pending.sponsor.clone();
+                domain.transfer = Some(pending);
+                self.queue(&sponsor, cx.now, "Transfer requested.", &data);
+                Ok(Answer::new(1001).data(data))
+            }
+            "query" => {
+                let transfer_data = domain.transfer.as_ref().ok_or(Failure(2301))?;
+                let involved = [&transfer_data.requester, &transfer_data.sponsor];
+                if !involved.iter().any(|id| *id == cx.client_id)
+                    && auth_info(transfer) != Some(domain.auth.as_str())
+                {
+                    return Err(Failure(2201));
+                }
+                Ok(Answer::new(1000).data(transfer_data.data(&domain.name)))
+            }
+            "approve" | "reject" | "cancel" => {
+                let pending = pending.ok_or(Failure(2301))?;
+                let (actor, notify, status, text) = match op {
+                    "approve" => (
+                        &pending.sponsor,
+                        &pending.requester,
+                        "clientApproved",
+                        "Transfer approved.",
+                    ),
+                    "reject" => (
+                        &pending.sponsor,
+                        &pending.requester,
+                        "clientRejected",
+                        "Transfer rejected.",
+                    ),
+                    _ => (
+                        &pending.requester,
+                        &pending.sponsor,
+                        "clientCancelled",
+                        "Transfer cancelled.",
+                    ),
+                };
+                if actor != cx.client_id {
+                    return Err(Failure(2201));
+                }
+
+                let notify = notify.clone();
+                let data = self.resolve_transfer(&name, status, cx.now);
+                self.queue(&notify, cx.now, text, &data);
+                Ok(Answer::new(1000).data(data))
+            }
+            _ => Err(Failure(2005)),
+        }
+    }
+
+    /// Completes the pending transfer of `name` with `status` and returns its `trnData`
+    fn resolve_transfer(&mut self, name: &str, status: &'static str, now: DateTime<Utc>) -> String {
+        let domain = self.domains.get_mut(name).unwrap();
+        let transfer = domain.transfer.as_mut().unwrap();
+        transfer.status = status;
+        transfer.action = now;
+
+        if status == "clientApproved" || status == "serverApproved" {
+            let requester = transfer.requester.clone();
+            domain.expires = transfer.expires;
+            domain.meta.client_id = requester.clone();
+            domain.meta.transferred = Some(now);
+            domain.rgp = Some((Rgp::TransferPeriod, now + Duration::days(GRACE_PERIOD)));
+
+            let suffix = format!(".{}", name);
+            for host in self
+                .hosts
+                .values_mut()
+                .filter(|h| h.name.ends_with(&suffix))
+            {
+                host.meta.client_id = requester.clone();
+                host.meta.transferred = Some(now);
+            }
+        }
+
+        let domain = &self.domains[name];
+        domain.transfer.as_ref().unwrap().data(&domain.name)
+    }
+
+    fn domain_update(&mut self, cx: &Context, update: &Element) -> Result<Answer, Failure> {
+        let name = key(update.value("name").ok_or(Failure(2003))?);
+        let current = self.domains.get(&name).ok_or(Failure(2303))?;
+        current.meta.sponsored_by(cx)?;
+        if current.rgp.is_some_and(|(rgp, _)| rgp.pending_delete()) {
+            return Err(Failure(2304));
+        }
+        check_update_prohibited(&current.meta.statuses, update)?;
+
+        let mut domain = current.clone();
+        if let Some(rem) = update.child("rem") {
+            if let Some(ns) = rem.child("ns") {
+                let hosts = host_objects(ns)?;
+                domain.ns.retain(|host| !hosts.contains(host));
+            }
+            let contacts = domain_contacts(rem)?;
+            domain
+                .contacts
+                .retain(|contact| !contacts.contains(contact));
+            for status in statuses(rem)? {
+                domain.meta.statuses.remove(status);
+            }
+        }
+        if let Some(add) = update.child("add") {
+            if let Some(ns) = add.child("ns") {
+                for host in host_objects(ns)? {
+                    if !domain.ns.contains(&host) {
+                        domain.ns.push(host);
+                    }
+                }
+            }
+            for contact in domain_contacts(add)? {
+                if !domain.contacts.contains(&contact) {
+                    domain.contacts.push(contact);
+                }
+            }
+            for status in statuses(add)? {
+                domain.meta.statuses.insert(status.to_owned());
+            }
+        }
+        if let Some(chg) = update.child("chg") {
+            if let Some(registrant) = chg.value("registrant") {
+                domain.registrant = Some(registrant.to_owned());
+            }
+            if let Some(auth) = auth_info(chg) {
+                domain.auth = auth.to_owned();
+            }
+        }
+
+        let contacts = domain
+            .registrant
+            .iter()
+            .chain(domain.contacts.iter().map(|(_, id)| id));
+        self.check_references(contacts, &domain.ns)?;
+
+        domain.meta.touch(cx);
+        self.domains.insert(name, domain);
+        Ok(Answer::new(1000))
+    }
+
+    fn domain_restore(
+        &mut self,
+        cx: &Context,
+        op: &str,
+        update: &Element,
+    ) -> Result<Answer, Failure> {
+        let name = key(update.value("name").ok_or(Failure(2003))?);
+        let domain = self.domains.get_mut(&name).ok_or(Failure(2303))?;
+        domain.meta.sponsored_by(cx)?;
+
+        match (op, domain.rgp) {
+            ("request", Some((Rgp::RedemptionPeriod, _))) => {
+                domain.rgp = Some((
+                    Rgp::PendingRestore,
+                    cx.now + Duration::days(PENDING_RESTORE_PERIOD),
+                ));
+                domain.meta.touch(cx);
+
+                let mut answer = Answer::new(1000);
+                answer.extension = Some(format!(
+                    "<rgp:upData xmlns:rgp=\"{}\"><rgp:rgpStatus s=\"pendingRestore\"/></rgp:upData>",
+                    crate::extensions::rgp::XMLNS
+                ));
+                Ok(answer)
+            }
+            ("report", Some((Rgp::PendingRestore, _))) => {
+                domain.rgp = None;
+                domain.meta.touch(cx);
+                Ok(Answer::new(1000))
+            }
+            ("request", _) | ("report", _) => Err(Failure(2105)),
+            _ => Err(Failure(2005)),
+        }
+    }
+
+    fn contact_check(&self, check: &Element) -> Result<Answer, Failure> {
+        let mut data = format!(
+            "<contact:chkData xmlns:contact=\"{}\">",
+            crate::contact::XMLNS
+        );
+        for id in check.children("id") {
+            let available = !self.contacts.contains_key(id.text());
+            write!(
+                data,
+                "<contact:cd><contact:id avail=\"{}\">{}</contact:id></contact:cd>",
+                available as u8,
+                escape(id.text())
+            )
+            .unwrap();
+        }
+        data.push_str("</contact:chkData>");
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn contact_info(&self, cx: &Context, info: &Element) -> Result<Answer, Failure> {
+        let id = info.value("id").ok_or(Failure(2003))?;
+        let contact = self.contacts.get(id).ok_or(Failure(2303))?;
+        let authorized = authorize(&contact.meta, &contact.auth, cx, info)?;
+
+        let mut data = format!(
+            "<contact:infData xmlns:contact=\"{}\"><contact:id>{}</contact:id><contact:roid>{}</contact:roid>",
+            crate::contact::XMLNS,
+            escape(&contact.id),
+            contact.meta.roid
+        );
+        let mut statuses: Vec<&str> = contact.meta.statuses.iter().map(String::as_str).collect();
+        if self.contact_linked(id) {
+            statuses.push("linked");
+        }
+        write_statuses(&mut data, "contact", &statuses);
+        for name in &["postalInfo", "voice", "fax", "email"] {
+            for field in contact.fields.iter().filter(|f| f.local() == *name) {
+                field.write("contact", &mut data);
+            }
+        }
+        contact.meta.write_sponsor(&mut data, "contact");
+        contact.meta.write_transfer(&mut data, "contact");
+        if authorized {
+            write!(
+                data,
+                "<contact:authInfo><contact:pw>{}</contact:pw></contact:authInfo>",
+                escape(&contact.auth)
+            )
+            .unwrap();
+        }
+        for field in contact.fields.iter().filter(|f| f.local() == "disclose") {
+            field.write("contact", &mut data);
+        }
+        data.push_str("</contact:infData>");
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn contact_create(&mut self, cx: &Context, create: &Element) -> Result<Answer, Failure> {
+        let id = create.value("id").ok_or(Failure(2003))?;
+        if self.contacts.contains_key(id) {
+            return Err(Failure(2302));
+        }
+        if create.child("postalInfo").is_none() || create.child("email").is_none() {
+            return Err(Failure(2003));
+        }
+        let auth = auth_info(create).ok_or(Failure(2003))?;
+
+        let contact = Contact {
+            id: id.to_owned(),
+            meta: Meta::new(self.roid("CONTACT"), cx),
+            fields: create
+                .children
+                .iter()
+                .filter(|f| !matches!(f.local(), "id" | "authInfo"))
+                .cloned()
+                .collect(),
+            auth: auth.to_owned(),
+        };
+
+        let data = format!(
+            "<contact:creData xmlns:contact=\"{}\"><contact:id>{}</contact:id><contact:crDate>{}</contact:crDate></contact:creData>",
+            crate::contact::XMLNS,
+            escape(id),
+            date(&cx.now)
+        );
+        self.contacts.insert(id.to_owned(), contact);
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn contact_delete(&mut self, cx: &Context, delete: &Element) -> Result<Answer, Failure> {
+        let id = delete.value("id").ok_or(Failure(2003))?;
+        let contact = self.contacts.get(id).ok_or(Failure(2303))?;
+        contact.meta.sponsored_by(cx)?;
+        check_prohibited(&contact.meta.statuses, "Delete")?;
+        if self.contact_linked(id) {
+            return Err(Failure(2305));
+        }
+
+        self.contacts.remove(id);
+        Ok(Answer::new(1000))
+    }
+
+    fn contact_update(&mut self, cx: &Context, update: &Element) -> Result<Answer, Failure> {
+        let id = update.value("id").ok_or(Failure(2003))?;
+        let current = self.contacts.get(id).ok_or(Failure(2303))?;
+        current.meta.sponsored_by(cx)?;
+        check_update_prohibited(&current.meta.statuses, update)?;
+
+        let mut contact = current.clone();
+        if let Some(rem) = update.child("rem") {
+            for status in statuses(rem)? {
+                contact.meta.statuses.remove(status);
+            }
+        }
+        if let Some(add) = update.child("add") {
+            for status in statuses(add)? {
+                contact.meta.statuses.insert(status.to_owned());
+            }
+        }
+        if let Some(chg) = update.child("chg") {
+            for field in &chg.children {
+                if field.local() == "authInfo" {
+                    contact.auth = auth_info(chg).ok_or(Failure(2003))?.to_owned();
+                    continue;
+                }
+
+                // Postal info is replaced per type, all other fields by name
+                contact
+                    .fields
+                    .retain(|f| f.local() != field.local() || f.attr("type") != field.attr("type"));
+                contact.fields.push(field.clone());
+            }
+        }
+
+        contact.meta.touch(cx);
+        self.contacts.insert(id.to_owned(), contact);
+        Ok(Answer::new(1000))
+    }
+
+    fn host_check(&self, check: &Element) -> Result<Answer, Failure> {
+        let mut data = format!("<host:chkData xmlns:host=\"{}\">", crate::host::XMLNS);
+        for name in check.children("name") {
+            let available = !self.hosts.contains_key(&key(name.text()));
+            write!(
+                data,
+                "<host:cd><host:name avail=\"{}\">{}</host:name></host:cd>",
+                available as u8,
+                escape(name.text())
+            )
+            .unwrap();
+        }
+        data.push_str("</host:chkData>");
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn host_info(&self, info: &Element) -> Result<Answer, Failure> {
+        let name = key(info.value("name").ok_or(Failure(2003))?);
+        let host = self.hosts.get(&name).ok_or(Failure(2303))?;
+
+        let mut data = format!(
+            "<host:infData xmlns:host=\"{}\"><host:name>{}</host:name><host:roid>{}</host:roid>",
+            crate::host::XMLNS,
+            escape(&host.name),
+            host.meta.roid
+        );
+        let mut statuses: Vec<&str> = host.meta.statuses.iter().map(String::as_str).collect();
+        if self.host_linked(&name) {
+            statuses.push("linked");
+        }
+        write_statuses(&mut data, "host", &statuses);
+        for (ip, address) in &host.addresses {
+            write!(
+                data,
+                "<host:addr ip=\"{}\">{}</host:addr>",
+                ip,
+                escape(address)
+            )
+            .unwrap();
+        }
+        host.meta.write_sponsor(&mut data, "host");
+        host.meta.write_transfer(&mut data, "host");
+        data.push_str("</host:infData>");
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn host_create(&mut self, cx: &Context, create: &Element) -> Result<Answer, Failure> {
+        let name = key(create.value("name").ok_or(Failure(2003))?);
+        if self.hosts.contains_key(&name) {
+            return Err(Failure(2302));
+        }
+
+        let addresses = addresses(create)?;
+        self.check_glue(cx, &name, &addresses)?;
+
+        let host = Host {
+            name: name.clone(),
+            meta: Meta::new(self.roid("HOST"), cx),
+            addresses,
+        };
+
+        let data = format!(
+            "<host:creData xmlns:host=\"{}\"><host:name>{}</host:name><host:crDate>{}</host:crDate></host:creData>",
+            crate::host::XMLNS,
+            escape(&name),
+            date(&cx.now)
+        );
+        self.hosts.insert(name, host);
+        Ok(Answer::new(1000).data(data))
+    }
+
+    fn host_delete(&mut self, cx: &Context, delete: &Element) -> Result<Answer, Failure> {
+        let name = key(delete.value("name").ok_or(Failure(2003))?);
+        let host = self.hosts.get(&name).ok_or(Failure(2303))?;
+        host.meta.sponsored_by(cx)?;
+        check_prohibited(&host.meta.statuses, "Delete")?;
+        if self.host_linked(&name) {
+            return Err(Failure(2305));
+        }
+
+        self.hosts.remove(&name);
+        Ok(Answer::new(1000))
+    }
+
+    fn host_update(&mut self, cx: &Context, update: &Element) -> Result<Answer, Failure> {
+        let name = key(update.value("name").ok_or(Failure(2003))?);
+        let current = self.hosts.get(&name).ok_or(Failure(2303))?;
+        current.meta.sponsored_by(cx)?;
+        check_update_prohibited(&current.meta.statuses, update)?;
+
+        let mut host = current.clone();
+        if let Some(rem) = update.child("rem") {
+            let addresses = addresses(rem)?;
+            host.addresses
+                .retain(|address| !addresses.contains(address));
+            for status in statuses(rem)? {
+                host.meta.statuses.remove(status);
+            }
+        }
+        if let Some(add) = update.child("add") {
+            for address in addresses(add)? {
+                if !host.addresses.contains(&address) {
+                    host.addresses.push(address);
+                }
+            }
+            for status in statuses(add)? {
+                host.meta.statuses.insert(status.to_owned());
+            }
+        }
+        if let Some(new) = update.child("chg").and_then(|chg| chg.value("name")) {
+            host.name = key(new);
+            if host.name != name && self.hosts.contains_key(&host.name) {
+                return Err(Failure(2302));
+            }
+        }
+        self.check_glue(cx, &host.name, &host.addresses)?;
+
+        // Renaming a host object renames it in every delegation referring to it
+        if host.name != name {
+            for domain in self.domains.values_mut() {
+                for ns in domain.ns.iter_mut().filter(|ns| **ns == name) {
+                    *ns = host.name.clone();
+                }
+            }
+        }
+
+        host.meta.touch(cx);
+        self.hosts.remove(&name);
+        self.hosts.insert(host.name.clone(), host);
+        Ok(Answer::new(1000))
+    }
+
+    /// Checks that subordinate hosts have addresses and are sponsored along with their domain,
+    /// and that external hosts don't have any
+    fn check_glue(
+        &self,
+        cx: &Context,
+        name: &str,
+        addresses: &[(String, String)],
+    ) -> Result<(), Failure> {
+        match self.superordinate(name) {
+            Some(domain) => {
+                domain.meta.sponsored_by(cx)?;
+                if addresses.is_empty() {
+                    Err(Failure(2003))
+                } else {
+                    Ok(())
+                }
+            }
+            None if addresses.is_empty() => Ok(()),
+            None => Err(Failure(2306)),
+        }
+    }
+
+    /// Checks that all referenced contacts and hosts exist
+    fn check_references<'a>(
+        &self,
+        mut contacts: impl Iterator<Item = &'a String>,
+        hosts: &[String],
+    ) -> Result<(), Failure> {
+        if contacts.all(|id| self.contacts.contains_key(id))
+            && hosts.iter().all(|host| self.hosts.contains_key(host))
+        {
+            Ok(())
+        } else {
+            Err(Failure(2303))
+        }
+    }
+
+    /// The domain `host` is subordinate to, if it is registered
+    fn superordinate(&self, host: &str) -> Option<&Domain> {
+        host.match_indices('.')
+            .find_map(|(i, _)| self.domains.get(&host[i + 1..]))
+    }
+
+    /// The hosts subordinate to `domain`
+    fn subordinates<'a>(&'a self, domain: &str) -> impl Iterator<Item = &'a Host> + 'a {
+        let suffix = format!(".{}", domain);
+        self.hosts
+            .values()
+            .filter(move |host| host.name.ends_with(&suffix))
+    }
+
+    fn contact_linked(&self, id: &str) -> bool {
+        self.domains.values().any(|domain| {
+            domain.registrant.as_deref() == Some(id)
+                || domain.contacts.iter().any(|(_, contact)| contact == id)
+        })
+    }
+
+    fn host_linked(&self, name: &str) -> bool {
+        self.domains
+            .values()
+            .any(|domain| domain.ns.iter().any(|host| host == name))
+    }
+
+    fn roid(&mut self, kind: &str) -> String {
+        self.roid += 1;
+        format!("{}_{}-SIM", self.roid, kind)
+    }
+
+    /// Adds a message for `client_id` to the poll queue
+    fn queue(&mut self, client_id: &str, date: DateTime<Utc>, text: &str, data: &str) {
+        self.message_id += 1;
+        self.messages.push(Message {
+            id: self.message_id,
+            client_id: client_id.to_owned(),
+            date,
+            text: text.to_owned(),
+            data: data.to_owned(),
+        });
+    }
+}
+
+/// The registrar executing a command, and when
+struct Context<'a> {
+    client_id: &'a str,
+    now: DateTime<Utc>,
+    rgp: bool,
+}
+
+/// Properties shared by all objects
+#[derive(Clone, Debug)]
+struct Meta {
+    roid: String,
+    /// Statuses set by clients or the server; derived statuses are added when rendering
+    statuses: BTreeSet<String>,
+    client_id: String,
+    creator_id: String,
+    created: DateTime<Utc>,
+    updater_id: Option<String>,
+    updated: Option<DateTime<Utc>>,
+    transferred: Option<DateTime<Utc>>,
+}
+
+impl Meta {
+    fn new(roid: String, cx: &Context) -> Self {
+        Self {
+            roid,
+            statuses: BTreeSet::new(),
+            client_id: cx.client_id.to_owned(),
+            creator_id: cx.client_id.to_owned(),
+            created: cx.now,
+            updater_id: None,
+            updated: None,
+            transferred: None,
+        }
+    }
+
+    fn sponsored_by(&self, cx: &Context) -> Result<(), Failure> {
+        if self.client_id != cx.client_id {
+            return Err(Failure(2201));
+        }
+
+        Ok(())
+    }
+
+    fn touch(&mut self, cx: &Context) {
+        self.updater_id = Some(cx.client_id.to_owned());
+        self.updated = Some(cx.now);
+    }
+
+    /// Writes the sponsoring client, creation and last update
+    fn write_sponsor(&self, out: &mut String, prefix: &str) {
+        write!(
+            out,
+            "<{0}:clID>{1}</{0}:clID><{0}:crID>{2}</{0}:crID><{0}:crDate>{3}</{0}:crDate>",
+            prefix,
+            escape(&self.client_id),
+            escape(&self.creator_id),
+            date(&self.created)
+        )
+        .unwrap();
+        if let (Some(updater_id), Some(updated)) = (&self.updater_id, &self.updated) {
+            write!(
+                out,
+                "<{0}:upID>{1}</{0}:upID><{0}:upDate>{2}</{0}:upDate>",
+                prefix,
+                escape(updater_id),
+                date(updated)
+            )
+            .unwrap();
+        }
+    }
+
+    fn write_transfer(&self, out: &mut String, prefix: &str) {
+        if let Some(transferred) = &self.transferred {
+            write!(
+                out,
+                "<{0}:trDate>{1}</{0}:trDate>",
+                prefix,
+                date(transferred)
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Domain {
+    name: String,
+    meta: Meta,
+    registrant: Option<String>,
+    contacts: Vec<(String, String)>,
+    ns: Vec<String>,
+    expires: DateTime<Utc>,
+    auth: String,
+    /// The pending or most recent transfer
+    transfer: Option<Transfer>,
+    rgp: Option<(Rgp, DateTime<Utc>)>,
+}
+
+impl Domain {
+    fn statuses(&self) -> Vec<&str> {
+        let mut statuses: Vec<&str> = self.meta.statuses.iter().map(String::as_str).collect();
+        if self.ns.is_empty() {
+            statuses.push("inactive");
+        }
+        if self
+            .transfer
+            .as_ref()
+            .is_some_and(|t| t.status == "pending")
+        {
+            statuses.push("pendingTransfer");
+        }
+        if self.rgp.is_some_and(|(rgp, _)| rgp.pending_delete()) {
+            statuses.push("pendingDelete");
+        }
+        statuses
+    }
+
+    /// Checks that the domain's statuses permit `action`, e.g. `Delete`
+    fn permits(&self, action: &str) -> Result<(), Failure> {
+        check_prohibited(&self.meta.statuses, action)?;
+        let pending = self
+            .transfer
+            .as_ref()
+            .is_some_and(|t| t.status == "pending");
+        let deleted = self.rgp.is_some_and(|(rgp, _)| rgp.pending_delete());
+        if pending || deleted {
+            return Err(Failure(2304));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Transfer {
+    status: &'static str,
+    requester: String,
+    requested: DateTime<Utc>,
+    sponsor: String,
+    action: DateTime<Utc>,
+    /// The expiry date once the transfer has been approved
+    expires: DateTime<Utc>,
+}
+
+impl Transfer {
+    fn data(&self, name: &str) -> String {
+        format!(
+            "<domain:trnData xmlns:domain=\"{}\"><domain:name>{}</domain:name><domain:trStatus>{}</domain:trStatus><domain:reID>{}</domain:reID><domain:reDate>{}</domain:reDate><domain:acID>{}</domain:acID><domain:acDate>{}</domain:acDate><domain:exDate>{}</domain:exDate></domain:trnData>",
+            crate::domain::XMLNS,
+            escape(name),
+            self.status,
+            escape(&self.requester),
+            date(&self.requested),
+            escape(&self.sponsor),
+            date(&self.action),
+            date(&self.expires)
+        )
+    }
+}
+
+/// RGP states, see RFC 3915
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Rgp {
+    AddPeriod,
+    RenewPeriod,
+    TransferPeriod,
+    RedemptionPeriod,
+    PendingRestore,
+    PendingDelete,
+}
+
+impl Rgp {
+    fn as_str(self) -> &'static str {
+        match self {
+            Rgp::AddPeriod => "addPeriod",
+            Rgp::RenewPeriod => "renewPeriod",
+            Rgp::TransferPeriod => "transferPeriod",
+            Rgp::RedemptionPeriod => "redemptionPeriod",
+            Rgp::PendingRestore => "pendingRestore",
+            Rgp::PendingDelete => "pendingDelete",
+        }
+    }
+
+    /// Whether the domain has been deleted and awaits restoration or purging
+    fn pending_delete(self) -> bool {
+        matches!(
+            self,
+            Rgp::RedemptionPeriod | Rgp::PendingRestore | Rgp::PendingDelete
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Contact {
+    id: String,
+    meta: Meta,
+    /// The postal info, phone numbers, email and disclosure preferences as submitted
+    fields: Vec<Element>,
+    auth: String,
+}
+
+#[derive(Clone, Debug)]
+struct Host {
+    name: String,
+    meta: Meta,
+    /// Pairs of IP version (`v4` or `v6`) and address
+    addresses: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct Message {
+    id: u64,
+    client_id: String,
+    date: DateTime<Utc>,
+    text: String,
+    /// The `resData` content
+    data: String,
+}
+
+/// A successful result
+#[derive(Debug)]
+struct Answer {
+    code: u16,
+    queue: Option<String>,
+    data: Option<String>,
+    extension: Option<String>,
+}
+
+impl Answer {
+    fn new(code: u16) -> Self {
+        Self {
+            code,
+            queue: None,
+            data: None,
+            extension: None,
+        }
+    }
+
+    fn data(mut self, data: String) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// An error result with the given code
+#[derive(Debug, PartialEq)]
+struct Failure(u16);
+
+/// Normalizes a domain or host name for lookups
+fn key(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Returns whether `cx` may see the authorization info of an object, checking the password
+/// supplied in the info command if the client doesn't sponsor the object
+fn authorize(meta: &Meta, auth: &str, cx: &Context, info: &Element) -> Result<bool, Failure> {
+    if meta.client_id == cx.client_id {
+        return Ok(true);
+    }
+
+    match auth_info(info) {
+        Some(pw) if pw == auth => Ok(true),
+        Some(_) => Err(Failure(2202)),
+        None => Ok(false),
+    }
+}
+
+/// Fails if `statuses` contain the client or server prohibition for `action`
+fn check_prohibited(statuses: &BTreeSet<String>, action: &str) -> Result<(), Failure> {
+    let client = format!("client{}Prohibited", action);
+    let server = format!("server{}Prohibited", action);
+    if statuses.contains(&client) || statuses.contains(&server) {
+        return Err(Failure(2304));
+    }
+
+    Ok(())
+}
+
+/// Fails if updates are prohibited, unless the update removes `clientUpdateProhibited`
+fn check_update_prohibited(statuses: &BTreeSet<String>, update: &Element) -> Result<(), Failure> {
+    if statuses.contains("serverUpdateProhibited") {
+        return Err(Failure(2304));
+    }
+
+    let unlock = match update.child("rem") {
+        Some(rem) => statuses_of(rem).any(|s| s == "clientUpdateProhibited"),
+        None => false,
+    };
+    if statuses.contains("clientUpdateProhibited") && !unlock {
+        return Err(Failure(2304));
+    }
+
+    Ok(())
+}
+
+/// The statuses listed in an `add` or `rem` element, which clients may only set for `client*`
+fn statuses(element: &Element) -> Result<Vec<&str>, Failure> {
+    statuses_of(element)
+        .map(|status| {
+            if status.starts_with("client") {
+                Ok(status)
+            } else {
+                Err(Failure(2306))
+            }
+        })
+        .collect()
+}
+
+fn statuses_of(element: &Element) -> impl Iterator<Item = &str> {
+    element
+        .children("status")
+        .filter_map(|status| status.attr("s"))
+}
+
+/// The password in an `authInfo` child of `element`
+fn auth_info(element: &Element) -> Option<&str> {
+    element.child("authInfo").and_then(|auth| auth.value("pw"))
+}
+
+/// The `(type, id)` pairs of `contact` children of `element`
+fn domain_contacts(element: &Element) -> Result<Vec<(String, String)>, Failure> {
+    element
+        .children("contact")
+        .map(|contact| match contact.attr("type") {
+            Some(kind) => Ok((kind.to_owned(), contact.text().to_owned())),
+            None => Err(Failure(2003)),
+        })
+        .collect()
+}
+
+/// The host object names in a `ns` element; host attributes aren't supported
+fn host_objects(ns: &Element) -> Result<Vec<String>, Failure> {
+    ns.children
+        .iter()
+        .map(|host| match host.local() {
+            "hostObj" => Ok(key(host.text())),
+            _ => Err(Failure(2102)),
+        })
+        .collect()
+}
+
+/// The `(ip, address)` pairs of `addr` children of `element`
+fn addresses(element: &Element) -> Result<Vec<(String, String)>, Failure> {
+    element
+        .children("addr")
+        .map(|addr| match addr.attr("ip").unwrap_or("v4") {
+            ip @ "v4" | ip @ "v6" => Ok((ip.to_owned(), addr.text().to_owned())),
+            _ => Err(Failure(2005)),
+        })
+        .collect()
+}
+
+/// The number of months in a `period` element, defaulting to one year
+fn period(period: Option<&Element>) -> Result<u32, Failure> {
+    let period = match period {
+        Some(period) => period,
+        None => return Ok(12),
+    };
+
+    let value: u32 = period.text().parse().map_err(|_| Failure(2005))?;
+    if !(1..=99).contains(&value) {
+        return Err(Failure(2004));
+    }
+
+    match period.attr("unit") {
+        Some("y") => Ok(value * 12),
+        Some("m") => Ok(value),
+        _ => Err(Failure(2005)),
+    }
+}
+
+fn add_months(date: DateTime<Utc>, months: u32) -> Result<DateTime<Utc>, Failure> {
+    date.checked_add_months(Months::new(months))
+        .ok_or(Failure(2004))
+}
+
+fn date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn write_statuses(out: &mut String, prefix: &str, statuses: &[&str]) {
+    if statuses.iter().all(|&s| s == "linked") {
+        write!(out, "<{}:status s=\"ok\"/>", prefix).unwrap();
+    }
+    for status in statuses {
+        write!(out, "<{}:status s=\"{}\"/>", prefix, status).unwrap();
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A parsed XML element
+#[derive(Clone, Debug, Default)]
+struct Element {
+    /// The qualified name, including any namespace prefix
+    name: String,
+    /// The namespace URI the name resolved to
+    namespace: Option<String>,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    /// Parses the root element of `xml`
+    #[allow(clippy::result_large_err)]
+    fn parse(xml: &str) -> Result<Self, Error> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut ns_buf = Vec::new();
+        let mut stack = vec![Self::default()];
+        loop {
+            let (namespace, event) = reader
+                .read_namespaced_event(&mut buf, &mut ns_buf)
+                .map_err(|e| Error::Xml(e.into()))?;
+            match event {
+                Event::Start(ref e) => stack.push(Self::open(e, namespace, &reader)?),
+                Event::Empty(ref e) => {
+                    let element = Self::open(e, namespace, &reader)?;
+                    stack.last_mut().unwrap().children.push(element);
+                }
+                Event::Text(ref e) => {
+                    let text = e
+                        .unescape_and_decode(&reader)
+                        .map_err(|e| Error::Xml(e.into()))?;
+                    stack.last_mut().unwrap().text.push_str(&text);
+                }
+                Event::End(_) => {
+                    let element = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Err(Error::Other("unbalanced XML document".into())),
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        match stack
+            .pop()
+            .and_then(|document| document.children.into_iter().next())
+        {
+            Some(root) if stack.is_empty() => Ok(root),
+            _ => Err(Error::Other("incomplete XML document".into())),
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn open(
+        start: &BytesStart,
+        namespace: Option<&[u8]>,
+        reader: &Reader<&[u8]>,
+    ) -> Result<Self, Error> {
+        let mut element = Self {
+            name: String::from_utf8_lossy(start.name()).into_owned(),
+            namespace: namespace.map(|ns| String::from_utf8_lossy(ns).into_owned()),
+            ..Self::default()
+        };
+
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|e| Error::Xml(e.into()))?;
+            let value = attribute
+                .unescape_and_decode_value(reader)
+                .map_err(|e| Error::Xml(e.into()))?;
+            element
+                .attributes
+                .push((String::from_utf8_lossy(attribute.key).into_owned(), value));
+        }
+
+        Ok(element)
+    }
+
+    /// The name without its namespace prefix
+    fn local(&self) -> &str {
+        self.name
+            .split_once(':')
+            .map_or(&self.name, |(_, local)| local)
+    }
+
+    /// The object type of the element's namespace, e.g. `domain`, or `""` if it has none
+    fn object(&self) -> &str {
+        self.namespace
+            .as_deref()
+            .and_then(object_type)
+            .unwrap_or_default()
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn text(&self) -> &str {
+        self.text.trim()
+    }
+
+    fn child(&self, local: &str) -> Option<&Self> {
+        self.children.iter().find(|child| child.local() == local)
+    }
+
+    fn children<'a>(&'a self, local: &'a str) -> impl Iterator<Item = &'a Self> + 'a {
+        self.children
+            .iter()
+            .filter(move |child| child.local() == local)
+    }
+
+    /// The text of the first child named `local`
+    fn value(&self, local: &str) -> Option<&str> {
+        self.child(local).map(Self::text)
+    }
+
+    /// Writes the element with its name in the `prefix` namespace
+    fn write(&self, prefix: &str, out: &mut String) {
+        write!(out, "<{}:{}", prefix, self.local()).unwrap();
+        for (key, value) in self
+            .attributes
+            .iter()
+            .filter(|(key, _)| !key.starts_with("xmlns"))
+        {
+            write!(out, " {}=\"{}\"", key, escape(value)).unwrap();
+        }
+        out.push('>');
+        out.push_str(&escape(self.text()));
+        for child in &self.children {
+            child.write(prefix, out);
+        }
+        write!(out, "</{}:{}>", prefix, self.local()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::{Session, Simulator, REDEMPTION_PERIOD};
+    use crate::tests::get_xml;
+
+    fn code(xml: &str) -> u16 {
+        let start = xml.find("code=\"").unwrap() + 6;
+        xml[start..start + 4].parse().unwrap()
+    }
+
+    fn login(simulator: &Simulator, client_id: &str) -> Session {
+        let mut session = Session::default();
+        let xml = get_xml("request/login.xml")
+            .unwrap()
+            .replace("<clID>username", &format!("<clID>{}", client_id));
+        assert_eq!(code(&simulator.respond(&mut session, &xml)), 1000);
+        session
+    }
+
+    #[test]
+    fn lifecycle() {
+        let simulator = Simulator::default();
+        let mut session = Session::default();
+
+        let create = get_xml("request/domain/create.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 2002);
+
+        let mut session = login(&simulator, "eppdev");
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 2303);
+
+        let contact = get_xml("request/contact/create.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut session, &contact)), 1000);
+        assert_eq!(code(&simulator.respond(&mut session, &contact)), 2302);
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 1000);
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 2302);
+
+        let delete = get_xml("request/contact/delete.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut session, &delete)), 2305);
+
+        let info = get_xml("request/contact/info.xml").unwrap();
+        let response = simulator.respond(&mut session, &info);
+        assert!(response.contains("<contact:status s=\"ok\"/><contact:status s=\"linked\"/>"));
+        assert!(response.contains("<contact:name>John Doe</contact:name>"));
+
+        // Deleting within the add grace period purges the domain right away
+        let delete = get_xml("request/domain/delete.xml")
+            .unwrap()
+            .replace("eppdev.com", "eppdev-1.com");
+        assert_eq!(code(&simulator.respond(&mut session, &delete)), 1000);
+        assert_eq!(code(&simulator.respond(&mut session, &delete)), 2303);
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 1000);
+
+        // Afterwards it enters redemption and is purged once that and pending delete are over
+        simulator.advance(Duration::days(6));
+        assert_eq!(code(&simulator.respond(&mut session, &delete)), 1001);
+        simulator.advance(Duration::days(REDEMPTION_PERIOD + 4));
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 2302);
+        simulator.advance(Duration::days(1));
+        assert_eq!(code(&simulator.respond(&mut session, &create)), 1000);
+    }
+
+    #[test]
+    fn server_approval() {
+        let simulator = Simulator::default().transfer_window(Duration::days(2));
+        let mut losing = login(&simulator, "losing");
+        let mut gaining = login(&simulator, "gaining");
+
+        let contact = get_xml("request/contact/create.xml").unwrap();
+        let create = get_xml("request/domain/create.xml")
+            .unwrap()
+            .replace("eppdev-1.com", "testing.com");
+        assert_eq!(code(&simulator.respond(&mut losing, &contact)), 1000);
+        assert_eq!(code(&simulator.respond(&mut losing, &create)), 1000);
+
+        let transfer = get_xml("request/domain/transfer_request.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut losing, &transfer)), 2106);
+        assert_eq!(code(&simulator.respond(&mut gaining, &transfer)), 1001);
+        assert_eq!(code(&simulator.respond(&mut gaining, &transfer)), 2300);
+
+        let poll = get_xml("request/message/poll.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut gaining, &poll)), 1300);
+
+        simulator.advance(Duration::days(2));
+        let response = simulator.respond(&mut gaining, &poll);
+        assert_eq!(code(&response), 1301);
+        assert!(response.contains("<domain:trStatus>serverApproved</domain:trStatus>"));
+
+        // The losing registrar was notified of the request and the approval
+        let response = simulator.respond(&mut losing, &poll);
+        assert!(response.contains("<msgQ count=\"2\" id=\"1\">"));
+
+        let ack = get_xml("request/message/ack.xml").unwrap();
+        assert_eq!(code(&simulator.respond(&mut losing, &ack)), 2303);
+        let ack = ack.replace("12345", "1");
+        let response = simulator.respond(&mut losing, &ack);
+        assert!(response.contains("<msgQ count=\"1\" id=\"3\"/>"));
+
+        let info = get_xml("request/domain/info.xml")
+            .unwrap()
+            .replace("eppdev.com", "testing.com");
+        let response = simulator.respond(&mut gaining, &info);
+        assert!(response.contains("<domain:clID>gaining</domain:clID>"));
+    }
+
+    #[test]
+    fn parse() {
+        let xml = get_xml("request/domain/update.xml").unwrap();
+        let epp = super::Element::parse(&xml).unwrap();
+        let update = &epp.child("command").unwrap().children[0].children[0];
+        assert_eq!(update.name, "domain:update");
+        assert_eq!(update.object(), "domain");
+
+        let xml = get_xml("request/contact/check.xml")
+            .unwrap()
+            .replace("contact:", "domain:")
+            .replace("xmlns:contact", "xmlns:domain");
+        let epp = super::Element::parse(&xml).unwrap();
+        let check = &epp.child("command").unwrap().children[0].children[0];
+        assert_eq!(check.object(), "contact");
+        assert_eq!(update.value("name"), Some("eppdev.com"));
+
+        let status = update.child("add").unwrap().child("status").unwrap();
+        assert_eq!(status.attr("s"), Some("clientDeleteProhibited"));
+        assert_eq!(
+            super::auth_info(update.child("chg").unwrap()),
+            Some("epP5uthd#v")
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub const EPP_XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standal
 /// Trait to be implemented by serializers. Currently the only included serializer is `quick-xml`
 pub trait EppXml: Sized {
     /// Serializes the EppObject instance to an EPP XML document
+    #[allow(clippy::result_large_err)]
     fn serialize(&self) -> Result<String, Error>
     where
         Self: Serialize,
@@ -21,6 +22,7 @@ pub trait EppXml: Sized {
     }
 
     /// Deserializes an EPP XML document to an EppObject instance
+    #[allow(clippy::result_large_err)]
     fn deserialize(epp_xml: &str) -> Result<Self, Error>
     where
         Self: DeserializeOwned + Sized,
@@ -1,13 +1,26 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
 
 use regex::Regex;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio_test::io::Builder;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
 
 use epp_client::domain::check::DomainCheck;
 use epp_client::login::Login;
-use epp_client::EppClient;
+use epp_client::metrics::PrometheusRecorder;
+use epp_client::middleware::{BoxFuture, Middleware, Next, Reply, Request};
+use epp_client::{EppClient, Error};
 
 const CLTRID: &str = "cltrid:1626454866";
 
@@ -106,8 +119,291 @@ async fn client() {
     assert_eq!(rsp.result.code, 1000);
 
     let result = rsp.res_data().unwrap();
+    assert_eq!(result.check_data.domain_list[0].domain.name, "eppdev.com");
+}
+
+/// A stream that isn't `Send`, as e.g. a connection owned by a single-threaded runtime
+struct LocalStream(tokio_test::io::Mock, PhantomData<Rc<()>>);
+
+impl AsyncRead for LocalStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for LocalStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+#[tokio::test]
+async fn client_without_send() {
+    let stream = build_stream(&[
+        "response/greeting.xml",
+        "request/domain/check.xml",
+        "response/domain/check.xml",
+    ])
+    .build();
+
+    let stream = LocalStream(stream, PhantomData);
+    let mut client = EppClient::new("test".into(), stream).await.unwrap();
+    let rsp = client
+        .transact(&DomainCheck::new(vec!["eppdev.com", "eppdev.net"]), CLTRID)
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1000);
+}
+
+#[tokio::test]
+async fn handle() {
+    let _guard = log_to_stdout();
+    let stream = build_stream(&[
+        "response/greeting.xml",
+        "request/login.xml",
+        "response/login.xml",
+        "request/domain/check.xml",
+        "response/domain/check.xml",
+    ])
+    .build();
+
+    let client = EppClient::new("test".into(), stream).await.unwrap();
+    let handle = client.into_handle(4).unwrap();
+
+    let stream = build_stream(&["response/greeting.xml"]).build();
+    let unqueued = EppClient::new("test".into(), stream).await.unwrap();
+    assert!(matches!(unqueued.into_handle(0), Err(Error::Invalid(_))));
+
+    assert_eq!(handle.xml_greeting(), xml("response/greeting.xml"));
+
+    handle
+        .transact(
+            &Login::new(
+                "username",
+                "password",
+                Some(&["http://schema.ispapi.net/epp/xml/keyvalue-1.0"]),
+            ),
+            CLTRID,
+        )
+        .await
+        .unwrap();
+
+    let other = handle.clone();
+    let rsp = tokio::spawn(async move {
+        other
+            .transact(&DomainCheck::new(vec!["eppdev.com", "eppdev.net"]), CLTRID)
+            .await
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(rsp.result.code, 1000);
+
+    handle.shutdown().await.unwrap();
+    assert!(handle.is_closed());
+    assert!(handle.transact_xml("").await.is_err());
+}
+
+type AuditLog = Arc<Mutex<Vec<(&'static str, String, u16)>>>;
+
+/// Records the command, clTRID and result code of every exchange
+struct Audit(AuditLog);
+
+impl Middleware for Audit {
+    fn call<'a>(
+        &'a self,
+        request: Request,
+        mut next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Reply, Error>> {
+        Box::pin(async move {
+            let reply = next.run(&request).await?;
+            self.0.lock().unwrap().push((
+                request.command,
+                request.client_tr_id,
+                reply.status.result.code,
+            ));
+            Ok(reply)
+        })
+    }
+}
+
+/// Answers domain checks locally instead of sending them to the registry
+struct CachedCheck;
+
+impl Middleware for CachedCheck {
+    fn call<'a>(
+        &'a self,
+        request: Request,
+        mut next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Reply, Error>> {
+        Box::pin(async move {
+            match request.command {
+                "check" => Reply::new(xml("response/domain/check.xml")),
+                _ => next.run(&request).await,
+            }
+        })
+    }
+}
+
+#[tokio::test]
+async fn middleware() {
+    let _guard = log_to_stdout();
+    let stream = build_stream(&[
+        "response/greeting.xml",
+        "request/login.xml",
+        "response/login.xml",
+    ])
+    .build();
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut client = EppClient::new("test".into(), stream).await.unwrap();
+    client
+        .with_middleware(Audit(log.clone()))
+        .with_middleware(CachedCheck);
+
+    client
+        .transact(
+            &Login::new(
+                "username",
+                "password",
+                Some(&["http://schema.ispapi.net/epp/xml/keyvalue-1.0"]),
+            ),
+            CLTRID,
+        )
+        .await
+        .unwrap();
+
+    let rsp = client
+        .transact(&DomainCheck::new(vec!["eppdev.com", "eppdev.net"]), CLTRID)
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1000);
+
+    let log = log.lock().unwrap();
     assert_eq!(
-        result.check_data.domain_list[0].domain.name,
-        "eppdev.com".into()
+        *log,
+        vec![
+            ("login", CLTRID.to_string(), 1000),
+            ("check", CLTRID.to_string(), 1000)
+        ]
     );
 }
+
+#[tokio::test]
+async fn metrics() {
+    let _guard = log_to_stdout();
+    let stream = build_stream(&[
+        "response/greeting.xml",
+        "request/domain/check.xml",
+        "response/domain/check.xml",
+    ])
+    .build();
+
+    let recorder = Arc::new(PrometheusRecorder::default());
+    let mut client = EppClient::new("test".into(), stream).await.unwrap();
+    client.with_recorder(recorder.clone());
+
+    client
+        .transact(&DomainCheck::new(vec!["eppdev.com", "eppdev.net"]), CLTRID)
+        .await
+        .unwrap();
+
+    let sent = xml("request/domain/check.xml").len() + 4;
+    let received = xml("response/greeting.xml").len() + xml("response/domain/check.xml").len() + 8;
+
+    let rendered = recorder.render();
+    assert!(rendered
+        .contains("epp_responses_total{registry=\"test\",command=\"check\",code=\"1000\"} 1"));
+    assert!(rendered.contains(&format!(
+        "epp_bytes_sent_total{{registry=\"test\"}} {}",
+        sent
+    )));
+    assert!(rendered.contains(&format!(
+        "epp_bytes_received_total{{registry=\"test\"}} {}",
+        received
+    )));
+    assert!(rendered.contains("epp_active_sessions{registry=\"test\"} 1"));
+
+    client.shutdown().await.unwrap();
+    assert!(recorder
+        .render()
+        .contains("epp_active_sessions{registry=\"test\"} 0"));
+}
+
+type SpanFields = Arc<Mutex<HashMap<String, String>>>;
+
+/// Collects the fields recorded on `transaction` spans
+struct SpanCollector(SpanFields);
+
+impl Visit for SpanCollector {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for SpanCollector {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+        if attrs.metadata().name() == "transaction" {
+            attrs.record(&mut SpanCollector(self.0.clone()));
+        }
+    }
+
+    fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+        values.record(&mut SpanCollector(self.0.clone()));
+    }
+}
+
+#[tokio::test]
+async fn transaction_span() {
+    let fields = SpanFields::default();
+    let subscriber = tracing_subscriber::registry().with(SpanCollector(fields.clone()));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let stream = build_stream(&[
+        "response/greeting.xml",
+        "request/domain/check.xml",
+        "response/domain/check.xml",
+    ])
+    .build();
+
+    let mut client = EppClient::new("test".into(), stream).await.unwrap();
+    client
+        .transact(&DomainCheck::new(vec!["eppdev.com", "eppdev.net"]), CLTRID)
+        .await
+        .unwrap();
+
+    let fields = fields.lock().unwrap();
+    assert_eq!(fields["registry"], "test");
+    assert_eq!(fields["command"], "check");
+    assert_eq!(fields["object"], "domain");
+    assert_eq!(fields["cl_trid"], CLTRID);
+    assert_eq!(fields["sv_trid"], "RO-6879-1627224678242975");
+    assert_eq!(fields["code"], "1000");
+    assert!(fields.contains_key("latency_ms"));
+}
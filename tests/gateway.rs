@@ -0,0 +1,141 @@
+use std::net::SocketAddr;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use epp_client::gateway::Gateway;
+use epp_client::login::Login;
+use epp_client::pool::Pool;
+use epp_client::testing::Simulator;
+use epp_client::EppClient;
+
+const TOKEN: &str = "gateway-token";
+
+/// Sends an HTTP request to the gateway, returning the response status and JSON body
+async fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    request_with_token(addr, method, path, body, Some(TOKEN)).await
+}
+
+async fn request_with_token(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    body: &str,
+    token: Option<&str>,
+) -> (u16, Value) {
+    let authorization = token
+        .map(|token| format!("Authorization: Bearer {}\r\n", token))
+        .unwrap_or_default();
+    request_with_headers(addr, method, path, body, &authorization).await
+}
+
+/// Sends an HTTP request with extra `headers`, each ending in CRLF
+async fn request_with_headers(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    body: &str,
+    headers: &str,
+) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: gateway\r\nConnection: close\r\n{}\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        headers,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response[9..12].parse().unwrap();
+    let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(headers.contains("content-type: application/json"));
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[tokio::test]
+async fn gateway() {
+    let registry = Simulator::default()
+        .registrar("eppdev", "password")
+        .start()
+        .await
+        .unwrap();
+
+    let stream = TcpStream::connect(registry.addr()).await.unwrap();
+    let mut client = EppClient::new("simulator".into(), stream).await.unwrap();
+    let login = Login::new("eppdev", "password", None);
+    client.transact(&login, "login").await.unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let pool = Pool::new(vec![client.into_handle(4).unwrap()]);
+    tokio::spawn(Gateway::new(pool).token(TOKEN).run(listener));
+
+    // Requests without a valid token never reach the registry
+    let path = "/domains/eppdev.com";
+    for token in [None, Some("wrong-token")] {
+        let (status, body) = request_with_token(addr, "DELETE", path, "", token).await;
+        assert_eq!(status, 401);
+        assert_eq!(body["error"]["message"], json!("unauthorized"));
+    }
+    assert!(registry.requests().iter().all(|r| r.command == "login"));
+
+    let (status, body) = request(addr, "GET", "/domains/eppdev.com/availability", "").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["available"], json!(true));
+
+    let create = json!({"name": "eppdev.com", "period": 2, "auth": "epP4uthd#v"});
+    let (status, body) = request(addr, "POST", "/domains", &create.to_string()).await;
+    assert_eq!(status, 201);
+    assert_eq!(body["name"], json!("eppdev.com"));
+    let expiry = body["expires_at"].as_str().unwrap()[..10].to_owned();
+
+    let (status, body) = request(addr, "POST", "/domains", &create.to_string()).await;
+    assert_eq!(status, 409);
+    assert_eq!(body["error"]["code"], json!(2302));
+
+    let (status, body) = request(addr, "GET", "/domains/eppdev.com", "").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["client_id"], json!("eppdev"));
+    assert_eq!(body["expires_at"].as_str().unwrap()[..10], expiry);
+
+    let headers = format!(
+        "Authorization: Bearer {}\r\nX-Auth-Info: epP4uthd#v\r\n",
+        TOKEN
+    );
+    let (status, _) = request_with_headers(addr, "GET", "/domains/eppdev.com", "", &headers).await;
+    assert_eq!(status, 200);
+    let info = registry.requests().pop().unwrap();
+    assert_eq!(info.command, "info");
+    assert!(info.xml.contains("<domain:pw>epP4uthd#v</domain:pw>"));
+
+    let renew = json!({ "expiry": expiry, "period": 1 });
+    let path = "/domains/eppdev.com/renew";
+    let (status, body) = request(addr, "POST", path, &renew.to_string()).await;
+    assert_eq!(status, 200);
+    assert_ne!(body["expires_at"].as_str().unwrap()[..10], expiry);
+
+    let (status, body) = request(addr, "POST", path, &renew.to_string()).await;
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["code"], json!(2306));
+
+    let (status, body) = request(addr, "POST", path, r#"{"expiry": "soon"}"#).await;
+    assert_eq!(status, 400);
+    assert!(body["error"].get("code").is_none());
+
+    let (status, _) = request(addr, "GET", "/domains/eppdev.net", "").await;
+    assert_eq!(status, 404);
+    let (status, _) = request(addr, "PUT", "/domains/eppdev.com", "").await;
+    assert_eq!(status, 405);
+    let (status, _) = request(addr, "GET", "/hosts", "").await;
+    assert_eq!(status, 404);
+
+    let (status, body) = request(addr, "DELETE", "/domains/eppdev.com", "").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["name"], json!("eppdev.com"));
+}
@@ -0,0 +1,163 @@
+use std::convert::TryInto;
+use std::fs;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use epp_client::common::{Certificate, PrivateKey};
+use epp_client::domain::check::DomainCheck;
+use epp_client::login::Login;
+use epp_client::logout::Logout;
+use epp_client::recording::{read_session, Direction, Replay, SessionRecorder};
+use epp_client::testing::{result_template, MockServer, Rule, Simulator};
+use epp_client::EppClient;
+
+fn response(path: &str) -> String {
+    fs::read_to_string(format!("tests/resources/response/{}", path)).unwrap()
+}
+
+#[tokio::test]
+async fn tcp() {
+    let server = MockServer::builder()
+        .rule(
+            Rule::command("login"),
+            &result_template(1000, "Command completed successfully"),
+        )
+        .rule(
+            Rule::command("check").object("domain").times(1),
+            &response("domain/check.xml"),
+        )
+        .rule(Rule::command("logout"), &response("logout.xml"))
+        .start()
+        .await
+        .unwrap();
+
+    let stream = TcpStream::connect(server.addr()).await.unwrap();
+    let mut client = EppClient::new("mock".into(), stream).await.unwrap();
+    assert_eq!(client.greeting().unwrap().service_id, "Mock EPP Server");
+
+    let rsp = client
+        .transact(&Login::new("username", "password", None), "login-1")
+        .await
+        .unwrap();
+    assert_eq!(rsp.tr_ids.client_tr_id.unwrap(), "login-1".into());
+    assert_eq!(rsp.tr_ids.server_tr_id, "MOCK-1".into());
+
+    let check = DomainCheck::new(vec!["eppdev.com", "eppdev.net"]);
+    let rsp = client.transact(&check, "check-1").await.unwrap();
+    let result = rsp.res_data().unwrap();
+    assert!(result.check_data.domain_list[0].domain.available);
+
+    // The check rule only applies once; unmatched commands get an "Unknown command" result
+    let rsp = client.transact(&check, "check-2").await.unwrap();
+    assert_eq!(rsp.result.code, 2000);
+
+    client.transact(&Logout, "logout-1").await.unwrap();
+
+    let requests = server.requests();
+    let commands = requests
+        .iter()
+        .map(|r| (r.command.as_str(), r.object.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        commands,
+        vec![
+            ("login", None),
+            ("check", Some("domain")),
+            ("check", Some("domain")),
+            ("logout", None)
+        ]
+    );
+    assert_eq!(requests[2].client_tr_id.as_deref(), Some("check-2"));
+}
+
+#[tokio::test]
+async fn tls() {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+    let der = cert.serialize_der().unwrap();
+
+    let server = MockServer::builder()
+        .tls(
+            vec![Certificate(der.clone())],
+            PrivateKey(cert.serialize_private_key_der()),
+        )
+        .unwrap()
+        .rule(
+            Rule::any().client_tr_id("tls-*"),
+            &result_template(1000, "Command completed successfully"),
+        )
+        .start()
+        .await
+        .unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(&rustls::Certificate(der)).unwrap();
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let tcp = TcpStream::connect(server.addr()).await.unwrap();
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect("localhost".try_into().unwrap(), tcp)
+        .await
+        .unwrap();
+
+    let mut client = EppClient::new("mock".into(), stream).await.unwrap();
+    let greeting = client.hello().await.unwrap();
+    assert_eq!(greeting.service_id, "Mock EPP Server");
+
+    let rsp = client
+        .transact(&Login::new("username", "password", None), "tls-1")
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1000);
+}
+
+#[tokio::test]
+async fn record_replay() {
+    let server = Simulator::default().start().await.unwrap();
+    let path = std::env::temp_dir().join(format!("epp-session-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let login = Login::new("username", "password", None);
+    let check = DomainCheck::new(vec!["eppdev.com", "eppdev.net"]);
+
+    let stream = TcpStream::connect(server.addr()).await.unwrap();
+    let stream = SessionRecorder::to_file(stream, "mock", &path).unwrap();
+    let mut client = EppClient::new("mock".into(), stream).await.unwrap();
+    client.transact(&login, "login-1").await.unwrap();
+    let recorded = client.transact(&check, "check-1").await.unwrap();
+    drop(client);
+
+    let frames = read_session(BufReader::new(fs::File::open(&path).unwrap())).unwrap();
+    let directions = frames.iter().map(|f| f.direction).collect::<Vec<_>>();
+    assert_eq!(
+        directions,
+        vec![
+            Direction::Received,
+            Direction::Sent,
+            Direction::Received,
+            Direction::Sent,
+            Direction::Received
+        ]
+    );
+    assert!(frames[1].xml.contains("<pw>REDACTED</pw>"));
+    assert!(frames.iter().all(|f| f.registry == "mock"));
+
+    let replay = Replay::from_file(&path).unwrap().strict();
+    let mut client = EppClient::new("mock".into(), replay).await.unwrap();
+    client.transact(&login, "login-1").await.unwrap();
+    let replayed = client.transact(&check, "check-1").await.unwrap();
+    assert_eq!(format!("{:?}", replayed), format!("{:?}", recorded));
+
+    // Requests deviating from the recording are rejected
+    let replay = Replay::from_file(&path).unwrap().strict();
+    let mut client = EppClient::new("mock".into(), replay).await.unwrap();
+    assert!(client.transact(&login, "login-2").await.is_err());
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,304 @@
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use epp_client::domain::{DomainCheck, DomainCreate, Period};
+use epp_client::login::Login;
+use epp_client::logout::Logout;
+use epp_client::metrics::PrometheusRecorder;
+use epp_client::pool::Pool;
+use epp_client::proxy::{Acl, ClientConfig, Proxy};
+use epp_client::server::Server;
+use epp_client::testing::Simulator;
+use epp_client::{EppClient, EppHandle, Error};
+
+async fn client(addr: SocketAddr) -> EppClient<TcpStream> {
+    let stream = TcpStream::connect(addr).await.unwrap();
+    EppClient::new("proxy".into(), stream).await.unwrap()
+}
+
+async fn login(mut session: EppClient<TcpStream>, id: &str) -> EppHandle {
+    let login = Login::new("eppdev", "password", None);
+    session.transact(&login, id).await.unwrap();
+    session.into_handle(4).unwrap()
+}
+
+/// A stream that fails to shut down
+struct Unclosable(TcpStream);
+
+impl AsyncRead for Unclosable {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Unclosable {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::ErrorKind::Other.into()))
+    }
+}
+
+/// Connects to `addr` through a relay; aborting the returned task cuts the connection
+async fn relay(addr: SocketAddr) -> (TcpStream, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay = listener.local_addr().unwrap();
+    let task = tokio::spawn(async move {
+        let (mut downstream, _) = listener.accept().await.unwrap();
+        let mut upstream = TcpStream::connect(addr).await.unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut downstream, &mut upstream).await;
+    });
+
+    (TcpStream::connect(relay).await.unwrap(), task)
+}
+
+#[tokio::test]
+async fn proxy() {
+    let registry = Simulator::default()
+        .registrar("eppdev", "password")
+        .start()
+        .await
+        .unwrap();
+
+    let mut handles = Vec::new();
+    for i in 0..2 {
+        let mut session = client(registry.addr()).await;
+        let id = format!("login-{}", i);
+        let login = Login::new("eppdev", "password", None);
+        session.transact(&login, &id).await.unwrap();
+        handles.push(session.into_handle(4).unwrap());
+    }
+
+    let proxy = Proxy::new(Pool::new(handles))
+        .client(ClientConfig {
+            id: "billing".into(),
+            password: "billing-password".into(),
+            acl: Acl {
+                commands: Some(vec!["check".into()]),
+                objects: Some(vec!["domain".into()]),
+            },
+        })
+        .client(ClientConfig {
+            id: "provisioning".into(),
+            password: "provisioning-password".into(),
+            acl: Acl::default(),
+        });
+
+    let greeting = proxy.greeting().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(Server::new(proxy, greeting).run(listener));
+
+    let mut billing = client(addr).await;
+    let mut provisioning = client(addr).await;
+
+    let login = Login::new("billing", "wrong-password", None);
+    match billing.transact(&login, "billing-login-1").await {
+        Err(Error::Command(status)) => assert_eq!(status.result.code, 2200),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let login = Login::new("billing", "billing-password", None);
+    billing.transact(&login, "billing-login-2").await.unwrap();
+    let login = Login::new("provisioning", "provisioning-password", None);
+    provisioning
+        .transact(&login, "provisioning-login")
+        .await
+        .unwrap();
+
+    // Both clients may use the same transaction ID; each gets its own back
+    let check = DomainCheck::new(vec!["eppdev.com"]);
+    let (first, second) = tokio::join!(
+        billing.transact(&check, "shared-1"),
+        provisioning.transact(&check, "shared-1"),
+    );
+    for rsp in [first.unwrap(), second.unwrap()] {
+        assert!(
+            rsp.res_data().unwrap().check_data.domain_list[0]
+                .domain
+                .available
+        );
+        assert_eq!(rsp.tr_ids.client_tr_id.unwrap(), "shared-1".into());
+    }
+
+    // Object types follow the namespace, so neither a misleading prefix nor a default namespace
+    // gets a contact command past the ACL
+    for object in [
+        r#"<domain:check xmlns:domain="urn:ietf:params:xml:ns:contact-1.0"><domain:id>c1</domain:id></domain:check>"#,
+        r#"<check xmlns="urn:ietf:params:xml:ns:contact-1.0"><id>c1</id></check>"#,
+    ] {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><command><check>{}</check><clTRID>billing-contact</clTRID></command></epp>"#,
+            object
+        );
+        let response = billing.transact_xml(&xml).await.unwrap();
+        assert!(response.contains(r#"code="2201""#), "{}", response);
+    }
+
+    let create = DomainCreate::new(
+        "eppdev.com",
+        Period::years(1),
+        None,
+        None,
+        "epP4uthd#v",
+        None,
+    );
+    match billing.transact(&create, "billing-create").await {
+        Err(Error::Command(status)) => assert_eq!(status.result.code, 2201),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let rsp = provisioning.transact(&create, "provisioning-create").await;
+    assert_eq!(
+        rsp.unwrap().tr_ids.client_tr_id.unwrap(),
+        "provisioning-create".into()
+    );
+
+    billing.transact(&Logout, "billing-logout").await.unwrap();
+    provisioning
+        .transact(&Logout, "provisioning-logout")
+        .await
+        .unwrap();
+
+    // The registry only saw transaction IDs assigned by the proxy
+    let forwarded = registry
+        .requests()
+        .into_iter()
+        .filter(|r| r.command != "login")
+        .map(|r| r.client_tr_id.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(forwarded.len(), 3);
+    assert!(forwarded.iter().all(|id| id.starts_with("proxy-")));
+    assert_ne!(forwarded[0], forwarded[1]);
+}
+
+#[tokio::test]
+async fn pool_reconnect() {
+    let registry = Simulator::default()
+        .registrar("eppdev", "password")
+        .start()
+        .await
+        .unwrap();
+    let addr = registry.addr();
+
+    let (stream, first) = relay(addr).await;
+    let session = EppClient::new("pool".into(), stream).await.unwrap();
+    let handles = vec![
+        login(session, "login-1").await,
+        login(client(addr).await, "login-2").await,
+    ];
+
+    let recorder = Arc::new(PrometheusRecorder::default());
+    let pool = Pool::new(handles)
+        .reconnect(move |i| {
+            Box::pin(async move {
+                let id = format!("relogin-{}", i + 1);
+                Ok(login(client(addr).await, &id).await)
+            })
+        })
+        .with_recorder(recorder.clone());
+
+    // Kill the first session; the command sent through it fails, and the session is closed
+    first.abort();
+    let _ = first.await;
+    let check = DomainCheck::new(vec!["eppdev.com"]);
+    match pool.transact(&check, "check-1").await {
+        Err(Error::Io(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Further commands go to the remaining session while the first one is replaced
+    for i in 2..6 {
+        let rsp = pool.transact(&check, &format!("check-{}", i)).await;
+        assert_eq!(rsp.unwrap().result.code, 1000);
+    }
+
+    let relogin = || {
+        registry
+            .requests()
+            .iter()
+            .any(|r| r.client_tr_id.as_deref() == Some("relogin-1"))
+    };
+    for _ in 0..100 {
+        if relogin() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(relogin());
+    for _ in 0..100 {
+        if recorder
+            .render()
+            .contains("epp_reconnects_total{registry=\"pool\"} 1")
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(recorder
+        .render()
+        .contains("epp_reconnects_total{registry=\"pool\"} 1"));
+
+    // Commands keep succeeding once the session has been replaced
+    for i in 6..10 {
+        let rsp = pool.transact(&check, &format!("check-{}", i)).await;
+        assert_eq!(rsp.unwrap().result.code, 1000);
+    }
+    pool.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn pool_shutdown() {
+    let registry = Simulator::default()
+        .registrar("eppdev", "password")
+        .start()
+        .await
+        .unwrap();
+    let addr = registry.addr();
+
+    let mut handles = Vec::new();
+    for i in 0..3 {
+        let stream = Unclosable(TcpStream::connect(addr).await.unwrap());
+        let mut session = EppClient::new("pool".into(), stream).await.unwrap();
+        let login = Login::new("eppdev", "password", None);
+        session
+            .transact(&login, &format!("login-{}", i))
+            .await
+            .unwrap();
+        handles.push(session.into_handle(4).unwrap());
+    }
+    handles.push(login(client(addr).await, "login-3").await);
+    let pool = Pool::new(handles.clone());
+
+    // Failing to close some sessions doesn't keep the others open
+    let err = pool.shutdown().await.unwrap_err();
+    assert!(err.to_string().contains("session 3"), "{}", err);
+    assert!(handles.iter().all(|handle| handle.is_closed()));
+    let logouts = registry
+        .requests()
+        .iter()
+        .filter(|r| r.command == "logout")
+        .count();
+    assert_eq!(logouts, 4);
+}
@@ -0,0 +1,105 @@
+use tokio::net::{TcpListener, TcpStream};
+
+use epp_client::common::NoExtension;
+use epp_client::domain::check::{
+    DomainAvailable, DomainCheck, DomainCheckResponse, DomainCheckResponseData,
+    DomainCheckResponseDataItem,
+};
+use epp_client::domain::DomainInfo;
+use epp_client::hello::Greeting;
+use epp_client::login::Login;
+use epp_client::logout::Logout;
+use epp_client::middleware::BoxFuture;
+use epp_client::response::ResponseDocument;
+use epp_client::server::{Handler, Request, Server};
+use epp_client::xml::EppXml;
+use epp_client::{domain, EppClient, Error};
+
+/// Reports domains starting with "free" as available, once logged in
+struct Registry;
+
+impl Handler for Registry {
+    type Session = Option<String>;
+
+    fn handle<'a>(
+        &'a self,
+        session: &'a mut Option<String>,
+        request: &'a Request,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(async move {
+            let code = match (request.command.as_str(), request.object.as_deref()) {
+                ("login", _) => {
+                    *session = Some(request.data::<Login>()?.username.to_string());
+                    1000
+                }
+                ("logout", _) => 1500,
+                _ if session.is_none() => 2002,
+                ("check", Some("domain")) => {
+                    let check = request.data::<DomainCheck>()?;
+                    let mut response = request.response::<_, NoExtension>(1000);
+                    response.res_data = Some(DomainCheckResponse {
+                        check_data: DomainCheckResponseData {
+                            xmlns: domain::XMLNS.into(),
+                            domain_list: check
+                                .list
+                                .domains
+                                .iter()
+                                .map(|name| DomainCheckResponseDataItem {
+                                    domain: DomainAvailable {
+                                        name: name.to_string().into(),
+                                        available: name.to_string().starts_with("free"),
+                                    },
+                                    reason: None,
+                                })
+                                .collect(),
+                        },
+                    });
+                    return ResponseDocument::new(response).serialize();
+                }
+                _ => 2101,
+            };
+
+            ResponseDocument::new(request.response::<(), NoExtension>(code)).serialize()
+        })
+    }
+}
+
+#[tokio::test]
+async fn serve() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = Server::new(Registry, Greeting::builder("Test EPP Server"));
+    tokio::spawn(server.run(listener));
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let mut client = EppClient::new("test".into(), stream).await.unwrap();
+    assert_eq!(client.greeting().unwrap().service_id, "Test EPP Server");
+
+    let check = DomainCheck::new(vec!["free.com", "taken.com"]);
+    match client.transact(&check, "check-1").await {
+        Err(Error::Command(status)) => assert_eq!(status.result.code, 2002),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let rsp = client
+        .transact(&Login::new("username", "password", None), "login-1")
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1000);
+    assert_eq!(rsp.tr_ids.client_tr_id.unwrap(), "login-1".into());
+
+    let rsp = client.transact(&check, "check-2").await.unwrap();
+    let result = rsp.res_data().unwrap();
+    assert_eq!(result.check_data.domain_list[0].domain.name, "free.com");
+    assert!(result.check_data.domain_list[0].domain.available);
+    assert!(!result.check_data.domain_list[1].domain.available);
+
+    let info = DomainInfo::new("free.com", None);
+    match client.transact(&info, "info-1").await {
+        Err(Error::Command(status)) => assert_eq!(status.result.code, 2101),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let rsp = client.transact(&Logout, "logout-1").await.unwrap();
+    assert_eq!(rsp.result.code, 1500);
+}
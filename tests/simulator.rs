@@ -0,0 +1,299 @@
+use std::net::Ipv4Addr;
+
+use chrono::{Duration, NaiveDate};
+use tokio::net::TcpStream;
+
+use epp_client::common::{DomainStatus, HostAddr, ObjectStatus, TransferStatus};
+use epp_client::contact::{
+    Address, ContactCreate, ContactDelete, Phone, PostalInfo, PostalInfoType,
+};
+use epp_client::domain::update::{DomainAddRemove, DomainChangeInfo};
+use epp_client::domain::{
+    ContactRole, DomainContact, DomainCreate, DomainDelete, DomainInfo, DomainRenew,
+    DomainTransfer, DomainUpdate, HostList, HostObjList, Period,
+};
+use epp_client::extensions::rgp::report::RgpRestoreReport;
+use epp_client::extensions::rgp::request::RgpRestoreRequest;
+use epp_client::extensions::rgp::{self, Update};
+use epp_client::host::{HostCreate, HostDelete};
+use epp_client::login::Login;
+use epp_client::message::{MessageAck, MessagePoll};
+use epp_client::testing::{MockServer, Simulator};
+use epp_client::{EppClient, Error};
+
+async fn login(server: &MockServer, client_id: &str) -> EppClient<TcpStream> {
+    let stream = TcpStream::connect(server.addr()).await.unwrap();
+    let mut client = EppClient::new("simulator".into(), stream).await.unwrap();
+    let login = Login::new(client_id, "password", Some(&[rgp::XMLNS]));
+    client.transact(&login, "login").await.unwrap();
+    client
+}
+
+fn code<T: std::fmt::Debug>(result: Result<T, Error>) -> u16 {
+    match result {
+        Err(Error::Command(status)) => status.result.code,
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+async fn info(client: &mut EppClient<TcpStream>, name: &str) -> (String, Vec<String>) {
+    let rsp = client
+        .transact(&DomainInfo::new(name, None), "info")
+        .await
+        .unwrap();
+    let data = rsp.res_data.unwrap().info_data;
+    let statuses = data.statuses.unwrap_or_default();
+    (
+        data.client_id.to_string(),
+        statuses.into_iter().map(|s| s.status.to_string()).collect(),
+    )
+}
+
+#[tokio::test]
+async fn lifecycle() {
+    let server = Simulator::default()
+        .registrar("losing", "password")
+        .registrar("gaining", "password")
+        .start()
+        .await
+        .unwrap();
+    let simulator = server.simulator().unwrap();
+
+    let mut losing = login(&server, "losing").await;
+    let mut gaining = login(&server, "gaining").await;
+
+    // Create a contact, a domain delegated to one of its own hosts, and renew it
+    let address = Address::new(&["58"], "Paris", "Paris", "392374", "FR".parse().unwrap());
+    let postal_info = PostalInfo::new(PostalInfoType::Int, "John Doe", "Acme Widgets", address);
+    let contact = ContactCreate::new(
+        "contact-1",
+        "contact@eppdev.net",
+        postal_info,
+        Phone::new("+33.47237942"),
+        "contact-pw",
+    );
+    losing.transact(&contact, "create-contact").await.unwrap();
+
+    let contacts = &[DomainContact {
+        contact_type: ContactRole::Admin,
+        id: "contact-1".into(),
+    }];
+    let create = DomainCreate::new(
+        "eppdev.com",
+        Period::years(1),
+        None,
+        Some("contact-1"),
+        "domain-pw",
+        Some(contacts),
+    );
+    losing.transact(&create, "create-domain").await.unwrap();
+
+    let addresses = &[HostAddr::from(Ipv4Addr::new(192, 0, 2, 1))];
+    let host = HostCreate::new("ns1.eppdev.com", Some(addresses)).glue_zones(&["com"]);
+    losing.transact(&host, "create-host").await.unwrap();
+    assert_eq!(
+        code(
+            losing
+                .transact(
+                    &HostCreate::new("ns1.other.com", Some(addresses)).glue_zones(&["com"]),
+                    "glue"
+                )
+                .await
+        ),
+        2306
+    );
+
+    let hosts = &["ns1.eppdev.com".into()];
+    let mut update = DomainUpdate::new("eppdev.com");
+    update.add(DomainAddRemove {
+        ns: Some(HostList::HostObjList(HostObjList {
+            hosts: hosts.into(),
+        })),
+        contacts: None,
+        statuses: None,
+    });
+    losing.transact(&update, "delegate").await.unwrap();
+
+    let rsp = losing
+        .transact(&DomainInfo::new("eppdev.com", None), "info")
+        .await
+        .unwrap();
+    let expires = rsp.res_data.unwrap().info_data.expiring_at.unwrap();
+    let expires = NaiveDate::parse_from_str(&expires.to_string()[..10], "%Y-%m-%d").unwrap();
+
+    let stale = expires.pred_opt().unwrap();
+    assert_eq!(
+        code(
+            losing
+                .transact(
+                    &DomainRenew::new("eppdev.com", stale, Period::years(1)),
+                    "renew"
+                )
+                .await
+        ),
+        2306
+    );
+    losing
+        .transact(
+            &DomainRenew::new("eppdev.com", expires, Period::years(2)),
+            "renew",
+        )
+        .await
+        .unwrap();
+
+    // Transfer requests need the right authInfo and wait for the sponsor
+    let transfer = DomainTransfer::new("eppdev.com", Some(Period::years(1)), "wrong");
+    assert_eq!(code(gaining.transact(&transfer, "transfer").await), 2202);
+
+    let transfer = DomainTransfer::new("eppdev.com", Some(Period::years(1)), "domain-pw");
+    let rsp = gaining.transact(&transfer, "transfer").await.unwrap();
+    assert_eq!(rsp.result.code, 1001);
+    assert_eq!(
+        rsp.res_data.unwrap().transfer_data.transfer_status,
+        TransferStatus::Pending
+    );
+    assert!(info(&mut losing, "eppdev.com")
+        .await
+        .1
+        .contains(&"pendingTransfer".to_string()));
+    assert_eq!(
+        code(
+            losing
+                .transact(&DomainDelete::new("eppdev.com"), "delete")
+                .await
+        ),
+        2304
+    );
+
+    let rsp = losing
+        .transact(&MessagePoll::default(), "poll")
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1301);
+    let queue = rsp.message_queue.unwrap();
+    assert_eq!(queue.count, 1);
+    assert_eq!(queue.message.unwrap(), "Transfer requested.".into());
+    let ack = MessageAck::new(queue.id.parse().unwrap());
+    losing.transact(&ack, "ack").await.unwrap();
+    let rsp = losing
+        .transact(&MessagePoll::default(), "poll")
+        .await
+        .unwrap();
+    assert_eq!(rsp.result.code, 1300);
+
+    assert_eq!(
+        code(
+            gaining
+                .transact(&DomainTransfer::approve("eppdev.com"), "approve")
+                .await
+        ),
+        2201
+    );
+    losing
+        .transact(&DomainTransfer::approve("eppdev.com"), "approve")
+        .await
+        .unwrap();
+    assert_eq!(info(&mut gaining, "eppdev.com").await.0, "gaining");
+
+    let rsp = gaining
+        .transact(&MessagePoll::default(), "poll")
+        .await
+        .unwrap();
+    assert_eq!(
+        rsp.message_queue.unwrap().message.unwrap(),
+        "Transfer approved.".into()
+    );
+
+    // The domain can't be deleted while it has subordinate hosts
+    let delete = DomainDelete::new("eppdev.com");
+    assert_eq!(code(gaining.transact(&delete, "delete").await), 2305);
+    assert_eq!(
+        code(
+            gaining
+                .transact(&HostDelete::new("ns1.eppdev.com"), "host")
+                .await
+        ),
+        2305
+    );
+
+    let mut update = DomainUpdate::new("eppdev.com");
+    update.remove(DomainAddRemove {
+        ns: Some(HostList::HostObjList(HostObjList {
+            hosts: hosts.into(),
+        })),
+        contacts: None,
+        statuses: None,
+    });
+    gaining.transact(&update, "undelegate").await.unwrap();
+    gaining
+        .transact(&HostDelete::new("ns1.eppdev.com"), "host")
+        .await
+        .unwrap();
+
+    // Outside the add grace period, deleting puts the domain into redemption
+    let rsp = gaining.transact(&delete, "delete").await.unwrap();
+    assert_eq!(rsp.result.code, 1001);
+    assert!(info(&mut gaining, "eppdev.com")
+        .await
+        .1
+        .contains(&"pendingDelete".to_string()));
+
+    let statuses = &[ObjectStatus::new(DomainStatus::ClientHold)];
+    let mut update = DomainUpdate::new("eppdev.com");
+    update.add(DomainAddRemove {
+        ns: None,
+        contacts: None,
+        statuses: Some(statuses.into()),
+    });
+    assert_eq!(code(gaining.transact(&update, "update").await), 2304);
+
+    // Restore it with a request followed by a report
+    let mut restore = DomainUpdate::new("eppdev.com");
+    restore.info(DomainChangeInfo {
+        registrant: None,
+        auth_info: None,
+    });
+    let request = Update {
+        data: RgpRestoreRequest::default(),
+    };
+    let rsp = gaining
+        .transact((&restore, &request), "restore")
+        .await
+        .unwrap();
+    assert_eq!(
+        rsp.extension.unwrap().data.rgp_status[0].status,
+        "pendingRestore"
+    );
+    assert!(info(&mut gaining, "eppdev.com")
+        .await
+        .1
+        .contains(&"pendingDelete".to_string()));
+
+    let now = simulator.now();
+    let statements = &["The registrant asked for the domain to be restored."];
+    let report = Update {
+        data: RgpRestoreReport::new("pre", "post", now, now, "Registrant error.", statements, ""),
+    };
+    gaining
+        .transact((&restore, &report), "report")
+        .await
+        .unwrap();
+    assert_eq!(info(&mut gaining, "eppdev.com").await.1, vec!["inactive"]);
+
+    // Deleted again, the domain is purged once redemption and pending delete are over
+    gaining.transact(&delete, "delete").await.unwrap();
+    simulator.advance(Duration::days(36));
+    assert_eq!(
+        code(
+            gaining
+                .transact(&DomainInfo::new("eppdev.com", None), "info")
+                .await
+        ),
+        2303
+    );
+
+    // Unreferenced contacts can be deleted by their sponsor only
+    let delete = ContactDelete::new("contact-1");
+    assert_eq!(code(gaining.transact(&delete, "delete").await), 2201);
+    losing.transact(&delete, "delete").await.unwrap();
+}